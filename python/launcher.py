@@ -6,11 +6,17 @@ import minecraft_launcher_lib
 import subprocess
 import uuid
 import sys
+import os
 import json
 import platform
 import logging
+import hashlib
+import shutil
+import shlex
+import time
 import urllib3
 import requests
+from concurrent.futures import ThreadPoolExecutor
 from pathlib import Path
 from packaging import version as pkg_version
 
@@ -21,6 +27,102 @@ requests.adapters.DEFAULT_TIMEOUT = 30
 # urllib3 timeouts.
 urllib3.util.timeout.DEFAULT_TIMEOUT = 30
 
+# Official list of Minecraft versions and their metadata URLs.
+VERSION_MANIFEST_URL = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json"
+
+# Mirrors that can be picked in the launcher settings.
+# Each mirror maps an official host to the base URL that serves the same paths.
+MIRROR_PRESETS = {
+    "bmclapi": {
+        "resources.download.minecraft.net": "https://bmclapi2.bangbang93.com/assets",
+        "libraries.minecraft.net": "https://bmclapi2.bangbang93.com/maven",
+        "piston-meta.mojang.com": "https://bmclapi2.bangbang93.com",
+        "piston-data.mojang.com": "https://bmclapi2.bangbang93.com",
+        "launchermeta.mojang.com": "https://bmclapi2.bangbang93.com",
+        "launcher.mojang.com": "https://bmclapi2.bangbang93.com",
+    },
+}
+
+# Mirror hosts tried in order when an official download host fails.
+# Third-party mirrors are only used when enabled in the launcher settings,
+# which sets DREAM_LAUNCHER_FALLBACK_MIRRORS. Custom mirrors can be supplied
+# as JSON through DREAM_LAUNCHER_MIRRORS instead.
+def get_mirrors():
+    try:
+        mirrors = json.loads(os.environ.get("DREAM_LAUNCHER_MIRRORS", ""))
+        if isinstance(mirrors, list):
+            return mirrors
+    except ValueError:
+        pass
+    if os.environ.get("DREAM_LAUNCHER_FALLBACK_MIRRORS") == "1":
+        return list(MIRROR_PRESETS.values())
+    return []
+
+# Mirror picked in the launcher settings, tried before the official host.
+def get_preferred_mirror():
+    return MIRROR_PRESETS.get(os.environ.get("DREAM_LAUNCHER_MIRROR", "official"))
+
+# Rewrite an official URL to the equivalent URL on a mirror, or None if it has no such host.
+def rewrite_url(url, mirror):
+    scheme, _, rest = url.partition("://")
+    host, _, path = rest.partition("/")
+    base = mirror.get(host)
+    return f"{base.rstrip('/')}/{path}" if base else None
+
+# Rewrite an official URL to the equivalent URL on each configured mirror.
+def get_mirror_urls(url):
+    urls = []
+    for mirror in get_mirrors():
+        mirror_url = rewrite_url(url, mirror)
+        if mirror_url:
+            urls.append(mirror_url)
+    return urls
+
+# URLs to try for a download: the preferred mirror, the official host, then the fallbacks.
+def get_download_urls(url):
+    urls = [url] + get_mirror_urls(url)
+    preferred = get_preferred_mirror()
+    if preferred:
+        preferred_url = rewrite_url(url, preferred)
+        if preferred_url:
+            urls.insert(0, preferred_url)
+    return list(dict.fromkeys(urls))
+
+_download_file = minecraft_launcher_lib.install.download_file
+
+# Download a file, falling back to mirrors when the primary host fails.
+# The SHA1 passed by minecraft_launcher_lib is forwarded untouched, so every
+# mirror response is verified exactly like the official one.
+def download_file_with_mirrors(url, path, callback={}, sha1=None, lzma_compressed=False,
+                               session=None, minecraft_directory=None, overwrite=False):
+    # minecraft_launcher_lib returns False both for a file it skips because it is
+    # already valid and for a non-success status code, so tell the two apart up front
+    if not overwrite and is_file_valid(Path(path), sha1=sha1):
+        return False
+
+    last_error = None
+    for candidate in get_download_urls(url):
+        try:
+            result = _download_file(
+                candidate, path, callback=callback, sha1=sha1,
+                lzma_compressed=lzma_compressed, session=session,
+                minecraft_directory=minecraft_directory, overwrite=True,
+            )
+        except (requests.exceptions.RequestException, urllib3.exceptions.HTTPError) as e:
+            logging.warning(f"Download failed from {candidate}: {e}")
+            last_error = e
+            continue
+
+        if result:
+            return result
+        logging.warning(f"Download returned no file from {candidate}")
+
+    if last_error is not None:
+        raise last_error
+    raise requests.exceptions.HTTPError(f"Could not download {url} from any host")
+
+minecraft_launcher_lib.install.download_file = download_file_with_mirrors
+
 # Get Minecraft directory.
 def get_minecraft_directory():
     return minecraft_launcher_lib.utils.get_minecraft_directory()
@@ -46,9 +148,112 @@ def needs_rosetta(minecraft_version):
         # If version parsing fails, assume it needs Rosetta for safety
         return True
 
+# Quick Play (--quickPlayMultiplayer) replaced --server/--port in 1.20 (snapshot 23w14a).
+def supports_quick_play(minecraft_version):
+    try:
+        if 'w' in minecraft_version and minecraft_version[0:2].isdigit():
+            year = int(minecraft_version[0:2])
+            week = int(minecraft_version[3:5])
+            return (year, week) >= (23, 14)
+
+        return pkg_version.parse(minecraft_version) >= pkg_version.parse("1.20")
+    except:
+        return True
+
+# Split "host", "host:port" or "[ipv6]:port" into host and port.
+def split_server_address(address):
+    address = address.strip()
+    if address.startswith("["):
+        host, _, rest = address[1:].partition("]")
+        port = rest[1:] if rest.startswith(":") else ""
+    elif address.count(":") == 1:
+        host, _, port = address.partition(":")
+    else:
+        host, port = address, ""
+    return host, port or "25565"
+
+# Mod loaders installed through minecraft_launcher_lib.mod_loader.
+MOD_LOADERS = ("forge", "neoforge", "fabric", "quilt")
+
+# Get the vanilla version a (possibly mod loader) version inherits from.
+def get_base_version(version, minecraft_directory):
+    version_json = Path(minecraft_directory) / "versions" / version / f"{version}.json"
+    try:
+        with open(version_json) as f:
+            return json.load(f).get("inheritsFrom", version)
+    except (OSError, ValueError):
+        return version
+
+# Minimum seconds between two progress lines, so thousands of small files don't flood stdout.
+PROGRESS_INTERVAL = 0.1
+
+# Report install progress as JSON lines on stdout while the script runs.
+# The launcher forwards them as progress events; the result stays the last line.
+def progress_callback():
+    """Return minecraft_launcher_lib callbacks that print progress lines"""
+    state = {"status": None, "max": None, "printed": 0.0}
+
+    def set_status(status):
+        state["status"] = status
+
+    def set_max(value):
+        state["max"] = value
+
+    def set_progress(value):
+        now = time.monotonic()
+        finished = state["max"] is not None and value >= state["max"]
+        if not finished and now - state["printed"] < PROGRESS_INTERVAL:
+            return
+        state["printed"] = now
+        print(json.dumps({
+            "type": "progress",
+            "status": state["status"],
+            "done": value,
+            "total": state["max"],
+        }), flush=True)
+
+    return {"setStatus": set_status, "setProgress": set_progress, "setMax": set_max}
+
+# Install a mod loader on top of an installed vanilla version.
+# Without a loader version the newest one for the Minecraft version is installed.
+def install_mod_loader(loader, version, minecraft_directory, loader_version=None):
+    """Install a mod loader and return the installed version id"""
+    mod_loader = minecraft_launcher_lib.mod_loader.get_mod_loader(loader)
+    if not mod_loader.is_minecraft_version_supported(version):
+        raise ValueError(f"{mod_loader.get_name()} does not support Minecraft {version}")
+
+    installed_version = mod_loader.install(
+        version,
+        minecraft_directory,
+        loader_version=loader_version,
+        callback=progress_callback()
+    )
+    logging.info(f"{mod_loader.get_name()} installed as {installed_version}")
+    return installed_version
+
+# Error of the last failed install, reported to the launcher with its kind.
+last_error = None
+
+# Kind of an exception, matching ErrorKind::id in the launcher.
+def error_kind(e):
+    exceptions = minecraft_launcher_lib.exceptions
+    if isinstance(e, getattr(exceptions, "InvalidChecksum", ())):
+        return "verification"
+    if isinstance(e, (exceptions.VersionNotFound, FileNotFoundError)):
+        return "not_found"
+    if isinstance(e, (requests.exceptions.Timeout, TimeoutError)):
+        return "timeout"
+    if isinstance(e, (requests.exceptions.RequestException, urllib3.exceptions.HTTPError)):
+        return "network"
+    if isinstance(e, OSError):
+        return "io"
+    if isinstance(e, ValueError):
+        return "parse"
+    return None
+
 # Install Minecraft version.
-def install_minecraft_version(version, minecraft_directory):
-    """Install Minecraft version"""
+def install_minecraft_version(version, minecraft_directory, loader="vanilla", loader_version=None):
+    """Install Minecraft version and return the version id to launch"""
     try:
         
         # Check if Rosetta is needed for older versions on Apple Silicon
@@ -59,63 +264,339 @@ def install_minecraft_version(version, minecraft_directory):
         session = requests.Session()
         session.timeout = 30
         
+        # Abort early instead of failing partway through the download
+        ensure_disk_space(version, minecraft_directory)
+
         # Install the version using minecraft_launcher_lib with timeout
-        minecraft_launcher_lib.install.install_minecraft_version(
-            version, 
-            minecraft_directory,
-            callback={"setStatus": lambda x: None, "setProgress": lambda x: None, "setMax": lambda x: None}
-        )
+        try:
+            minecraft_launcher_lib.install.install_minecraft_version(
+                version, 
+                minecraft_directory,
+                callback=progress_callback()
+            )
+        except FileExistsError as e:
+            # Handle the case where natives directory already exists
+            if "META-INF" in str(e) and "natives" in str(e):
+                logging.info(f"Version {version} natives already exist, installation completed")
+            else:
+                raise
         
         logging.info(f"Version {version} installed successfully")
-        return True
+
+        if loader in MOD_LOADERS:
+            return install_mod_loader(loader, version, minecraft_directory, loader_version)
+        return version
         
-    except FileExistsError as e:
-        # Handle the case where natives directory already exists
-        if "META-INF" in str(e) and "natives" in str(e):
-            logging.info(f"Version {version} natives already exist, installation completed")
-            return True
-        else:
-            logging.error(f"File exists error installing version {version}: {e}")
-            return False
     except Exception as e:
-        logging.error(f"Error installing version {version}: {e}")
+        global last_error
+        logging.error(f"Error installing version {version} ({loader}): {e}")
+        last_error = {"error": str(e), "error_kind": error_kind(e)}
+        return None
+
+# Operating system names used by library rules and natives.
+def get_os_name():
+    return {"Windows": "windows", "Darwin": "osx"}.get(platform.system(), "linux")
+
+# Check whether a library applies to the current operating system.
+def is_library_used(library):
+    rules = library.get("rules")
+    if not rules:
+        return True
+
+    allowed = False
+    for rule in rules:
+        rule_os = rule.get("os", {}).get("name")
+        if rule_os is None or rule_os == get_os_name():
+            allowed = rule.get("action") == "allow"
+    return allowed
+
+# List the client jar and libraries a version needs on this system, as (path, download) pairs.
+def list_version_files(version, data, minecraft_directory):
+    version_dir = minecraft_directory / "versions" / version
+    files = []
+
+    client = data.get("downloads", {}).get("client")
+    if client:
+        files.append((version_dir / f"{version}.jar", client))
+
+    for library in data.get("libraries", []):
+        if not is_library_used(library):
+            continue
+        downloads = library.get("downloads", {})
+        artifact = downloads.get("artifact")
+        if artifact and artifact.get("path"):
+            files.append((minecraft_directory / "libraries" / artifact["path"], artifact))
+
+        native = library.get("natives", {}).get(get_os_name())
+        if native:
+            arch = "64" if platform.architecture()[0] == "64bit" else "32"
+            classifier = downloads.get("classifiers", {}).get(native.replace("${arch}", arch))
+            if classifier and classifier.get("path"):
+                files.append((minecraft_directory / "libraries" / classifier["path"], classifier))
+    return files
+
+# Read the metadata of a version from disk, or from Mojang if it is not installed yet.
+def get_version_data(version, minecraft_directory):
+    version_json = Path(minecraft_directory) / "versions" / version / f"{version}.json"
+    if version_json.is_file():
+        with open(version_json) as f:
+            return json.load(f)
+
+    manifest = requests.get(VERSION_MANIFEST_URL, timeout=30).json()
+    for entry in manifest.get("versions", []):
+        if entry.get("id") == version:
+            return requests.get(entry["url"], timeout=30).json()
+    raise ValueError(f"Version {version} not found in the version manifest")
+
+# Fail before downloading anything when the version does not fit on the disk.
+def ensure_disk_space(version, minecraft_directory):
+    minecraft_directory = Path(minecraft_directory)
+    try:
+        data = get_version_data(version, minecraft_directory)
+    except (requests.exceptions.RequestException, OSError, ValueError) as e:
+        # The install itself reports an unreachable or unknown version
+        logging.warning(f"Cannot estimate download size of {version}: {e}")
+        return
+
+    needed = sum(
+        download.get("size", 0)
+        for path, download in list_version_files(version, data, minecraft_directory)
+        if not path.is_file()
+    )
+
+    asset_index = data.get("assetIndex")
+    if asset_index:
+        index_path = minecraft_directory / "assets" / "indexes" / f"{asset_index['id']}.json"
+        if index_path.is_file():
+            with open(index_path) as f:
+                objects = json.load(f).get("objects", {})
+            needed += sum(
+                asset.get("size", 0)
+                for asset in objects.values()
+                if not (minecraft_directory / "assets" / "objects" / asset["hash"][:2] / asset["hash"]).is_file()
+            )
+        else:
+            needed += asset_index.get("size", 0) + asset_index.get("totalSize", 0)
+
+    # The Minecraft directory may not exist before the first install
+    volume = minecraft_directory
+    while not volume.exists() and volume != volume.parent:
+        volume = volume.parent
+    free = shutil.disk_usage(volume).free
+
+    if needed > free:
+        raise OSError(
+            f"Not enough disk space: need {needed // (1024 * 1024) + 1} MB, "
+            f"have {free // (1024 * 1024)} MB free"
+        )
+
+# Check a downloaded file against the size and SHA1 from version metadata.
+def is_file_valid(path, size=None, sha1=None, check_hashes=True):
+    if not path.is_file():
+        return False
+    if size is not None and path.stat().st_size != size:
         return False
+    if sha1 is not None and check_hashes:
+        digest = hashlib.sha1()
+        with open(path, "rb") as f:
+            for chunk in iter(lambda: f.read(1024 * 1024), b""):
+                digest.update(chunk)
+        if digest.hexdigest() != sha1:
+            return False
+    return True
+
+# Files checked at once, bounded so thousands of assets do not exhaust file handles.
+VERIFY_WORKERS = min(16, (os.cpu_count() or 1) * 2)
+
+# Collect the client jar, libraries and assets of a version that are missing or corrupted.
+# Without check_hashes only presence and size are checked, which is much faster.
+# The files are hashed in parallel once the metadata has been read.
+def find_corrupted_files(version, minecraft_directory, check_hashes=True):
+    minecraft_directory = Path(minecraft_directory)
+    corrupted = []
+    files = []
+
+    def is_valid(file):
+        path, download = file
+        return is_file_valid(path, download.get("size"), download.get("sha1"), check_hashes)
+
+    while version:
+        version_dir = minecraft_directory / "versions" / version
+        with open(version_dir / f"{version}.json") as f:
+            data = json.load(f)
+
+        files.extend(list_version_files(version, data, minecraft_directory))
+
+        asset_index = data.get("assetIndex")
+        if asset_index:
+            index_path = minecraft_directory / "assets" / "indexes" / f"{asset_index['id']}.json"
+            # The index is checked right away since it lists the assets to check
+            if is_valid((index_path, asset_index)):
+                with open(index_path) as f:
+                    objects = json.load(f).get("objects", {})
+                for asset in objects.values():
+                    asset_hash = asset["hash"]
+                    asset_path = minecraft_directory / "assets" / "objects" / asset_hash[:2] / asset_hash
+                    files.append((asset_path, {"size": asset.get("size"), "sha1": asset_hash}))
+            else:
+                corrupted.append(index_path)
+
+        version = data.get("inheritsFrom")
+
+    with ThreadPoolExecutor(max_workers=VERIFY_WORKERS) as executor:
+        results = executor.map(is_valid, files)
+        corrupted.extend(path for (path, _), valid in zip(files, results) if not valid)
+
+    logging.info(f"Verified {len(files)} files, {len(corrupted)} missing or corrupted")
+    return corrupted
+
+# Re-download the files of an installed version that are missing or corrupted.
+def repair_minecraft_version(version, minecraft_directory, loader="vanilla", loader_version=None):
+    """Repair an installed version and return the repaired files, or None on failure"""
+    try:
+        corrupted = find_corrupted_files(version, minecraft_directory)
+    except (OSError, ValueError) as e:
+        # The version metadata itself is unreadable, so reinstall from scratch
+        logging.warning(f"Cannot read version {version} metadata: {e}")
+        corrupted = []
+
+    # minecraft_launcher_lib skips files that already match their SHA1,
+    # so removing the bad files makes the install fetch only those
+    for path in corrupted:
+        logging.info(f"Repairing {path}")
+        if path.is_file():
+            path.unlink()
+
+    if install_minecraft_version(version, minecraft_directory, loader, loader_version) is None:
+        return None
+    return [str(path.relative_to(minecraft_directory)) for path in corrupted]
+
+# Find the installed version id to launch without touching the network.
+# A pinned loader version only matches installed versions named after it.
+def find_installed_version(version, minecraft_directory, loader="vanilla", loader_version=None):
+    versions_dir = Path(minecraft_directory) / "versions"
+    if loader not in MOD_LOADERS:
+        return version if (versions_dir / version / f"{version}.json").is_file() else None
+
+    # Loader versions are named after the loader and inherit from the vanilla version
+    candidates = []
+    for version_dir in versions_dir.iterdir() if versions_dir.is_dir() else []:
+        if loader not in version_dir.name.lower():
+            continue
+        if loader_version and loader_version not in version_dir.name:
+            continue
+        if get_base_version(version_dir.name, minecraft_directory) == version:
+            candidates.append(version_dir)
+    if not candidates:
+        return None
+    return max(candidates, key=lambda path: path.stat().st_mtime).name
+
+# Check that an installed version can be launched offline and return its version id.
+def check_minecraft_version(version, minecraft_directory, loader="vanilla", loader_version=None):
+    """Return the version id to launch, or None when files are missing"""
+    installed_version = find_installed_version(version, minecraft_directory, loader, loader_version)
+    if installed_version is None:
+        logging.error(f"Minecraft {version} ({loader}) is not installed, go online to install it")
+        return None
+
+    try:
+        missing = find_corrupted_files(installed_version, minecraft_directory, check_hashes=False)
+    except (OSError, ValueError) as e:
+        logging.error(f"Cannot read version {installed_version} metadata: {e}")
+        return None
+    if missing:
+        logging.error(
+            f"Missing file {missing[0].relative_to(minecraft_directory)}"
+            + (f" and {len(missing) - 1} more" if len(missing) > 1 else "")
+            + ", go online to download it"
+        )
+        return None
+
+    logging.info(f"Version {installed_version} is ready offline")
+    return installed_version
+
+# Arguments whose values must never end up in printed commands or logs
+SECRET_ARGUMENTS = ("--accessToken", "--session", "--xuid", "--clientId")
+
+# Replace the values of secret arguments so a command can be shared safely
+def redact_command(command):
+    redacted = list(command)
+    for i in range(len(redacted) - 1):
+        if redacted[i] in SECRET_ARGUMENTS:
+            redacted[i + 1] = "<redacted>"
+    return redacted
+
+# Build the full command that starts the game, without running it
+def build_launch_command(username, version, minecraft_directory, game_dir=None, server_address=None,
+                         game_args=None, jvm_args=None, java_path=None, world=None):
+    # Generate Minecraft launch command using minecraft_launcher_lib
+    options = {
+        "username": username,
+        "uuid": str(uuid.uuid4()),
+        "token": "dummy_token",
+        "gameDirectory": game_dir or minecraft_directory,
+        "jvmArguments": jvm_args or ["-Xmx2G", "-Xms1G"]
+    }
+    if java_path:
+        options["executablePath"] = java_path
+    
+    # Mod loader versions need Rosetta exactly when their vanilla base does
+    base_version = get_base_version(version, minecraft_directory)
+
+    # Join a server straight after the game starts
+    if server_address:
+        if supports_quick_play(base_version):
+            options["quickPlayMultiplayer"] = server_address
+        else:
+            options["server"], options["port"] = split_server_address(server_address)
+        logging.info(f"Joining server {server_address}")
+
+    # Open a singleplayer world straight after the game starts
+    if world:
+        if supports_quick_play(base_version):
+            options["quickPlaySingleplayer"] = world
+            logging.info(f"Opening world {world}")
+        else:
+            logging.warning(f"Minecraft {base_version} cannot open worlds on launch")
+
+    # For older versions that need Rosetta, use x86_64 Java
+    if needs_rosetta(base_version) and is_apple_silicon() and not java_path:
+        # Use x86_64 Java 8 for older Minecraft versions
+        java_8_path = "/Library/Java/JavaVirtualMachines/jdk1.8.0_351.jdk/Contents/Home/bin/java"
+        if Path(java_8_path).exists():
+            options["executablePath"] = java_8_path
+            logging.info(f"Using x86_64 Java 8 for {version}")
+        else:
+            logging.warning(f"x86_64 Java 8 not found, using system Java with Rosetta")
+    
+    command = minecraft_launcher_lib.command.get_minecraft_command(
+        version, minecraft_directory, options
+    )
+
+    # Extra game arguments such as the window size go after the generated ones
+    if game_args:
+        command += game_args
+    
+    # Check if Rosetta is needed for older versions on Apple Silicon
+    if needs_rosetta(base_version):
+        logging.info(f"Launching {version} with Rosetta compatibility")
+        # Prepend arch -x86_64 to the entire command
+        command = ["arch", "-x86_64"] + command
+
+    return command
 
 # Launch Minecraft with log streaming
-def launch_minecraft(username, version, minecraft_directory, game_dir=None):
+def launch_minecraft(username, version, minecraft_directory, game_dir=None, server_address=None,
+                     game_args=None, jvm_args=None, java_path=None, world=None):
     """Launch Minecraft and stream logs to stdout"""
     try:
-        # Generate Minecraft launch command using minecraft_launcher_lib
-        options = {
-            "username": username,
-            "uuid": str(uuid.uuid4()),
-            "token": "dummy_token",
-            "gameDirectory": game_dir or minecraft_directory,
-            "jvmArguments": ["-Xmx2G", "-Xms1G"]
-        }
-        
-        # For older versions that need Rosetta, use x86_64 Java
-        if needs_rosetta(version) and is_apple_silicon():
-            # Use x86_64 Java 8 for older Minecraft versions
-            java_8_path = "/Library/Java/JavaVirtualMachines/jdk1.8.0_351.jdk/Contents/Home/bin/java"
-            if Path(java_8_path).exists():
-                options["executablePath"] = java_8_path
-                logging.info(f"Using x86_64 Java 8 for {version}")
-            else:
-                logging.warning(f"x86_64 Java 8 not found, using system Java with Rosetta")
-        
-        command = minecraft_launcher_lib.command.get_minecraft_command(
-            version, minecraft_directory, options
+        command = build_launch_command(
+            username, version, minecraft_directory, game_dir, server_address,
+            game_args, jvm_args, java_path, world
         )
-        
-        # Check if Rosetta is needed for older versions on Apple Silicon
-        if needs_rosetta(version):
-            logging.info(f"Launching {version} with Rosetta compatibility")
-            # Prepend arch -x86_64 to the entire command
-            command = ["arch", "-x86_64"] + command
 
         logging.info(f"Launching Minecraft {version} for user {username}")
-        logging.info(f"Command: {' '.join(command)}")
+        logging.info(f"Command: {' '.join(redact_command(command))}")
 
         # Launch Minecraft with stdout/stderr capture
         process = subprocess.Popen(
@@ -198,6 +679,28 @@ def launch_minecraft(username, version, minecraft_directory, game_dir=None):
         }), flush=True)
         return 1
 
+# Parse the optional "--server ADDRESS", "--world NAME" and "--java PATH" launch flags and the
+# repeated "--jvm-arg ARG" and "--game-arg ARG" ones.
+def parse_launch_options(args):
+    if len(args) % 2 != 0:
+        raise ValueError("Launch option without a value")
+
+    options = {"server_address": None, "world": None, "java_path": None, "jvm_args": [], "game_args": []}
+    for flag, value in zip(args[::2], args[1::2]):
+        if flag == "--server":
+            options["server_address"] = value
+        elif flag == "--world":
+            options["world"] = value
+        elif flag == "--java":
+            options["java_path"] = value
+        elif flag == "--jvm-arg":
+            options["jvm_args"].append(value)
+        elif flag == "--game-arg":
+            options["game_args"].append(value)
+        else:
+            raise ValueError(f"Unknown launch option: {flag}")
+    return options
+
 # Entry point when called from Rust launcher.
 if __name__ == "__main__":
     if len(sys.argv) < 2:
@@ -207,23 +710,75 @@ if __name__ == "__main__":
 
     command = sys.argv[1]
 
-    if command == "install" and len(sys.argv) == 4:
-        # Install version
+    if command == "install" and len(sys.argv) in (4, 5, 6):
+        # Install version, optionally with a mod loader pinned to a loader version
         version = sys.argv[2]
         minecraft_dir = sys.argv[3]
-        success = install_minecraft_version(version, minecraft_dir)
-        result = {"success": success}
+        loader = sys.argv[4] if len(sys.argv) >= 5 else "vanilla"
+        loader_version = sys.argv[5] if len(sys.argv) == 6 else None
+        installed_version = install_minecraft_version(version, minecraft_dir, loader, loader_version)
+        result = {"success": installed_version is not None, "version": installed_version}
+        if installed_version is None and last_error:
+            result.update(last_error)
         print(json.dumps(result))
-        if not success:
+        if installed_version is None:
+            exit(1)
+    elif command == "repair" and len(sys.argv) in (4, 5, 6):
+        # Verify an installed version and re-download corrupted files
+        version = sys.argv[2]
+        minecraft_dir = sys.argv[3]
+        loader = sys.argv[4] if len(sys.argv) >= 5 else "vanilla"
+        loader_version = sys.argv[5] if len(sys.argv) == 6 else None
+        repaired = repair_minecraft_version(version, minecraft_dir, loader, loader_version)
+        print(json.dumps({"success": repaired is not None, "repaired": repaired or []}))
+        if repaired is None:
+            exit(1)
+    elif command == "check" and len(sys.argv) in (4, 5, 6):
+        # Resolve an installed version for offline launch without downloading anything
+        version = sys.argv[2]
+        minecraft_dir = sys.argv[3]
+        loader = sys.argv[4] if len(sys.argv) >= 5 else "vanilla"
+        loader_version = sys.argv[5] if len(sys.argv) == 6 else None
+        installed_version = check_minecraft_version(version, minecraft_dir, loader, loader_version)
+        print(json.dumps({"success": installed_version is not None, "version": installed_version}))
+        if installed_version is None:
             exit(1)
-    elif command == "launch" and len(sys.argv) == 6:
-        # Launch Minecraft with log streaming
+    elif command == "launch" and len(sys.argv) >= 6:
+        # Launch Minecraft with log streaming, optionally joining a server
         username = sys.argv[2]
         version = sys.argv[3]
         minecraft_dir = sys.argv[4]
         game_dir = sys.argv[5]
-        exit_code = launch_minecraft(username, version, minecraft_dir, game_dir)
+        try:
+            options = parse_launch_options(sys.argv[6:])
+        except ValueError as e:
+            logging.error(str(e))
+            print(json.dumps({"type": "error", "success": False, "message": str(e)}))
+            exit(1)
+        exit_code = launch_minecraft(
+            username, version, minecraft_dir, game_dir,
+            options["server_address"], options["game_args"],
+            options["jvm_args"], options["java_path"], options["world"]
+        )
         exit(exit_code)
+    elif command == "command" and len(sys.argv) >= 6:
+        # Print the launch command with secrets redacted instead of starting the game
+        username = sys.argv[2]
+        version = sys.argv[3]
+        minecraft_dir = sys.argv[4]
+        game_dir = sys.argv[5]
+        try:
+            options = parse_launch_options(sys.argv[6:])
+            launch_command = build_launch_command(
+                username, version, minecraft_dir, game_dir,
+                options["server_address"], options["game_args"],
+                options["jvm_args"], options["java_path"], options["world"]
+            )
+        except Exception as e:
+            logging.error(f"Error building launch command: {e}")
+            print(json.dumps({"success": False, "error": str(e)}))
+            exit(1)
+        print(json.dumps({"success": True, "command": shlex.join(redact_command(launch_command))}))
     elif command == "logs" and len(sys.argv) == 3:
         # Get logs from running process
         pid = int(sys.argv[2])
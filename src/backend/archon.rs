@@ -1,13 +1,24 @@
 //! Thread manager that utilizes tokio for asynchronous task management.
 
+use crate::backend::python::python::{
+    GameEvent, MinecraftLogMessage, is_started_line, parse_log_line,
+};
+use crate::backend::utils::download::{self, DownloadEvent};
+use crate::backend::utils::error::{ErrorKind, TaggedError, error_kind};
+use crate::backend::utils::http;
 use anyhow::Result;
 use log::{debug, error, info, trace, warn};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::sync::{RwLock, mpsc};
 use tokio::task::JoinHandle;
 
+/// How long a game has to keep running to count as started when its log doesn't show it.
+const STARTED_FALLBACK: Duration = Duration::from_secs(30);
+
 /// Message types for inter-task communication.
 #[derive(Debug)]
 pub enum ArchonMessage {
@@ -15,6 +26,8 @@ pub enum ArchonMessage {
     Python {
         operation: String,
         args: Vec<String>,
+        /// Receives the output of long-running operations such as launches.
+        log_tx: Option<mpsc::UnboundedSender<MinecraftLogMessage>>,
         response_tx: Option<tokio::sync::oneshot::Sender<PythonResponse>>,
     },
     /// Log message.
@@ -94,6 +107,18 @@ impl Archon {
         Ok(archon)
     }
 
+    /// Create an Archon whose messages are handled by the caller instead of the script.
+    #[cfg(test)]
+    pub fn stub() -> (Self, mpsc::UnboundedReceiver<ArchonMessage>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let archon = Self {
+            tx,
+            handles: Arc::new(RwLock::new(Vec::new())),
+            running_processes: Arc::new(RwLock::new(HashMap::new())),
+        };
+        (archon, rx)
+    }
+
     /// Main message processing loop.
     async fn message_processor(
         mut rx: mpsc::UnboundedReceiver<ArchonMessage>,
@@ -104,12 +129,25 @@ impl Archon {
                 ArchonMessage::Python {
                     operation,
                     args,
+                    log_tx,
                     response_tx,
                 } => {
                     let result =
-                        Self::handle_python_operation(operation, args, &running_processes).await;
-                    if let Some(tx) = response_tx {
-                        let _ = tx.send(result);
+                        Self::handle_python_operation(operation, args, log_tx, &running_processes);
+                    let Some(mut tx) = response_tx else {
+                        result.await;
+                        continue;
+                    };
+                    // A caller that stops waiting cancels the operation and its script
+                    let result = tokio::select! {
+                        result = result => Some(result),
+                        () = tx.closed() => None,
+                    };
+                    match result {
+                        Some(result) => {
+                            let _ = tx.send(result);
+                        }
+                        None => info!("Python operation cancelled by its caller"),
                     }
                 }
                 ArchonMessage::Log {
@@ -153,6 +191,7 @@ impl Archon {
     async fn handle_python_operation(
         operation: String,
         args: Vec<String>,
+        log_tx: Option<mpsc::UnboundedSender<MinecraftLogMessage>>,
         running_processes: &Arc<RwLock<HashMap<u32, tokio::process::Child>>>,
     ) -> PythonResponse {
         match operation.as_str() {
@@ -169,12 +208,16 @@ impl Archon {
                 let version = &args[1];
                 let minecraft_dir = &args[2];
                 let game_dir = &args[3];
+                // Optional launch flags such as `--server` for the launcher script
+                let launch_options = &args[4..];
 
                 match Self::launch_minecraft_process(
                     username,
                     version,
                     minecraft_dir,
                     game_dir,
+                    launch_options,
+                    log_tx,
                     running_processes,
                 )
                 .await
@@ -191,6 +234,28 @@ impl Archon {
                     },
                 }
             }
+            "launch_command" => {
+                if args.len() < 4 {
+                    return PythonResponse {
+                        success: false,
+                        data: None,
+                        error: Some("Insufficient arguments for launch_command".to_string()),
+                    };
+                }
+
+                match Self::launch_command_process(&args).await {
+                    Ok(data) => PythonResponse {
+                        success: true,
+                        data,
+                        error: None,
+                    },
+                    Err(e) => PythonResponse {
+                        success: false,
+                        data: None,
+                        error: Some(e.to_string()),
+                    },
+                }
+            }
             "install_minecraft" => {
                 if args.len() < 2 {
                     return PythonResponse {
@@ -202,11 +267,92 @@ impl Archon {
 
                 let version = &args[0];
                 let minecraft_dir = &args[1];
+                let loader = args.get(2).map_or("vanilla", String::as_str);
+                let loader_version = args.get(3).map(String::as_str);
 
-                match Self::install_minecraft_process(version, minecraft_dir).await {
-                    Ok(_) => PythonResponse {
+                match Self::install_minecraft_process(
+                    "install",
+                    version,
+                    minecraft_dir,
+                    loader,
+                    loader_version,
+                )
+                .await
+                {
+                    Ok(data) => PythonResponse {
                         success: true,
+                        data,
+                        error: None,
+                    },
+                    Err(e) => PythonResponse {
+                        success: false,
+                        data: error_kind(&e)
+                            .map(|kind| serde_json::json!({ "error_kind": kind.id() })),
+                        error: Some(e.to_string()),
+                    },
+                }
+            }
+            "check_minecraft" => {
+                if args.len() < 2 {
+                    return PythonResponse {
+                        success: false,
                         data: None,
+                        error: Some("Insufficient arguments for check_minecraft".to_string()),
+                    };
+                }
+
+                let version = &args[0];
+                let minecraft_dir = &args[1];
+                let loader = args.get(2).map_or("vanilla", String::as_str);
+                let loader_version = args.get(3).map(String::as_str);
+
+                match Self::install_minecraft_process(
+                    "check",
+                    version,
+                    minecraft_dir,
+                    loader,
+                    loader_version,
+                )
+                .await
+                {
+                    Ok(data) => PythonResponse {
+                        success: true,
+                        data,
+                        error: None,
+                    },
+                    Err(e) => PythonResponse {
+                        success: false,
+                        data: None,
+                        error: Some(e.to_string()),
+                    },
+                }
+            }
+            "repair_minecraft" => {
+                if args.len() < 2 {
+                    return PythonResponse {
+                        success: false,
+                        data: None,
+                        error: Some("Insufficient arguments for repair_minecraft".to_string()),
+                    };
+                }
+
+                let version = &args[0];
+                let minecraft_dir = &args[1];
+                let loader = args.get(2).map_or("vanilla", String::as_str);
+                let loader_version = args.get(3).map(String::as_str);
+
+                match Self::install_minecraft_process(
+                    "repair",
+                    version,
+                    minecraft_dir,
+                    loader,
+                    loader_version,
+                )
+                .await
+                {
+                    Ok(data) => PythonResponse {
+                        success: true,
+                        data,
                         error: None,
                     },
                     Err(e) => PythonResponse {
@@ -230,6 +376,8 @@ impl Archon {
         version: &str,
         minecraft_dir: &str,
         game_dir: &str,
+        launch_options: &[String],
+        log_tx: Option<mpsc::UnboundedSender<MinecraftLogMessage>>,
         running_processes: &Arc<RwLock<HashMap<u32, tokio::process::Child>>>,
     ) -> Result<u32> {
         let python_script = std::env::current_dir()?.join("python").join("launcher.py");
@@ -241,37 +389,264 @@ impl Archon {
             .arg(version)
             .arg(minecraft_dir)
             .arg(game_dir)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped());
+            .args(launch_options);
+
+        // Unread pipes would fill up and stall the game, so only pipe when forwarding
+        if log_tx.is_some() {
+            cmd.stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped());
+        } else {
+            cmd.stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null());
+        }
 
-        let child = cmd.spawn()?;
+        let mut child = cmd.spawn()?;
         let pid = child.id().unwrap_or(0);
+        let started = Arc::new(AtomicBool::new(false));
+
+        // Forward script output so the debug window shows the game log live
+        let stdout_task = log_tx.as_ref().and_then(|log_tx| {
+            Self::forward_output(child.stderr.take(), log_tx.clone(), started.clone(), true);
+            Self::forward_output(child.stdout.take(), log_tx.clone(), started.clone(), false)
+        });
 
         // Store the process for later management
         running_processes.write().await.insert(pid, child);
+        tokio::spawn(Self::monitor_process(
+            pid,
+            running_processes.clone(),
+            stdout_task,
+            started,
+            log_tx,
+        ));
 
         info!("Minecraft launched with PID: {pid}");
         Ok(pid)
     }
 
-    /// Install Minecraft process.
-    async fn install_minecraft_process(version: &str, minecraft_dir: &str) -> Result<()> {
+    /// Watch a launched process until it exits, then stop tracking it.
+    ///
+    /// The launcher script reports the game's exit code itself. If it dies
+    /// without doing so, an exit message is sent on its behalf so the UI
+    /// never waits on a process that is gone.
+    ///
+    /// A game whose log never shows it starting is reported as started once
+    /// it has kept running for `STARTED_FALLBACK`.
+    async fn monitor_process(
+        pid: u32,
+        running_processes: Arc<RwLock<HashMap<u32, tokio::process::Child>>>,
+        stdout_task: Option<JoinHandle<bool>>,
+        started: Arc<AtomicBool>,
+        log_tx: Option<mpsc::UnboundedSender<MinecraftLogMessage>>,
+    ) {
+        let launched_at = tokio::time::Instant::now();
+        let exit_code = loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            if launched_at.elapsed() >= STARTED_FALLBACK
+                && let Some(log_tx) = &log_tx
+                && !started.swap(true, Ordering::Relaxed)
+            {
+                let _ = log_tx.send(MinecraftLogMessage::Event(GameEvent::Started));
+            }
+
+            let mut processes = running_processes.write().await;
+            let Some(child) = processes.get_mut(&pid) else {
+                // Removed on shutdown
+                return;
+            };
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    processes.remove(&pid);
+                    break status.code().unwrap_or(-1);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Failed to check process {pid}: {e}");
+                    processes.remove(&pid);
+                    break -1;
+                }
+            }
+        };
+        info!("Process {pid} exited with code {exit_code}");
+
+        let reported_exit = match stdout_task {
+            Some(task) => task.await.unwrap_or(false),
+            None => false,
+        };
+        if !reported_exit && let Some(log_tx) = log_tx {
+            let _ = log_tx.send(MinecraftLogMessage::Event(GameEvent::exited(exit_code)));
+            let _ = log_tx.send(MinecraftLogMessage::Exit {
+                pid,
+                exit_code,
+                message: format!("Launcher process exited with code {exit_code}"),
+            });
+        }
+    }
+
+    /// Forward lines of a process pipe as log messages.
+    ///
+    /// Stdout carries the script's JSON messages, stderr its plain logging output.
+    /// Game events are sent ahead of the messages they are worked out from.
+    /// The returned task resolves to whether an exit message was forwarded.
+    fn forward_output<R>(
+        pipe: Option<R>,
+        log_tx: mpsc::UnboundedSender<MinecraftLogMessage>,
+        started: Arc<AtomicBool>,
+        is_stderr: bool,
+    ) -> Option<JoinHandle<bool>>
+    where
+        R: tokio::io::AsyncRead + Unpin + Send + 'static,
+    {
+        use tokio::io::{AsyncBufReadExt, BufReader};
+
+        let pipe = pipe?;
+        Some(tokio::spawn(async move {
+            let mut reported_exit = false;
+            let mut lines = BufReader::new(pipe).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let message = if is_stderr {
+                    MinecraftLogMessage::Log {
+                        line: format!("[STDERR] {line}"),
+                        pid: None,
+                    }
+                } else {
+                    parse_log_line(line)
+                };
+                let event = match &message {
+                    MinecraftLogMessage::Log { line, .. }
+                        if is_started_line(line) && !started.swap(true, Ordering::Relaxed) =>
+                    {
+                        Some(GameEvent::Started)
+                    }
+                    MinecraftLogMessage::Exit { exit_code, .. } => {
+                        reported_exit = true;
+                        Some(GameEvent::exited(*exit_code))
+                    }
+                    _ => None,
+                };
+                if let Some(event) = event {
+                    let _ = log_tx.send(MinecraftLogMessage::Event(event));
+                }
+                if log_tx.send(message).is_err() {
+                    break;
+                }
+            }
+            reported_exit
+        }))
+    }
+
+    /// Run the `install`, `repair` or `check` script command, returning the result it reports.
+    ///
+    /// Without `loader_version` the newest mod loader for the version is used.
+    /// Progress the script prints while it runs is sent as [`DownloadEvent::InstallProgress`].
+    async fn install_minecraft_process(
+        command: &str,
+        version: &str,
+        minecraft_dir: &str,
+        loader: &str,
+        loader_version: Option<&str>,
+    ) -> Result<Option<Value>> {
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
         let python_script = std::env::current_dir()?.join("python").join("launcher.py");
 
-        let output = tokio::process::Command::new("python3")
+        let mut child = tokio::process::Command::new("python3")
             .arg(python_script)
-            .arg("install")
+            .arg(command)
             .arg(version)
             .arg(minecraft_dir)
+            .arg(loader)
+            .args(loader_version)
+            .envs(http::proxy_env())
+            .envs(http::mirror_env())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+
+        // The script prints progress lines while it runs and its JSON result as the last line
+        let read_stdout = async {
+            let mut result = None;
+            let Some(stdout) = stdout else {
+                return Ok(result);
+            };
+            let mut lines = BufReader::new(stdout).lines();
+            while let Some(line) = lines.next_line().await? {
+                let Ok(value) = serde_json::from_str::<Value>(&line) else {
+                    continue;
+                };
+                if value.get("type").and_then(Value::as_str) == Some("progress") {
+                    download::emit(DownloadEvent::InstallProgress {
+                        status: value
+                            .get("status")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        done: value.get("done").and_then(Value::as_u64).unwrap_or(0),
+                        total: value.get("total").and_then(Value::as_u64),
+                    });
+                } else {
+                    result = Some(value);
+                }
+            }
+            std::io::Result::Ok(result)
+        };
+        let read_stderr = async {
+            let mut error = String::new();
+            if let Some(mut stderr) = stderr {
+                stderr.read_to_string(&mut error).await?;
+            }
+            std::io::Result::Ok(error)
+        };
+        let (result, error) = tokio::join!(read_stdout, read_stderr);
+        let (result, error) = (result?, error?);
+        let status = child.wait().await?;
+
+        if status.success() {
+            info!("Minecraft {version} ({loader}) {command} finished successfully");
+            Ok(result)
+        } else {
+            let message = format!("Minecraft {command} failed: {error}");
+            // Failures the script recognized come with their kind
+            match result
+                .as_ref()
+                .and_then(|r| r.get("error_kind"))
+                .and_then(Value::as_str)
+                .and_then(ErrorKind::from_id)
+            {
+                Some(kind) => Err(TaggedError::new(kind, message).into()),
+                None => Err(anyhow::anyhow!(message)),
+            }
+        }
+    }
+
+    /// Build the launch command without starting the game.
+    ///
+    /// Takes the same arguments as `launch_minecraft`. Secrets are redacted by the script.
+    async fn launch_command_process(args: &[String]) -> Result<Option<Value>> {
+        let python_script = std::env::current_dir()?.join("python").join("launcher.py");
+
+        let output = tokio::process::Command::new("python3")
+            .arg(python_script)
+            .arg("command")
+            .args(args)
             .output()
             .await?;
 
         if output.status.success() {
-            info!("Minecraft {version} installed successfully");
-            Ok(())
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let result = stdout
+                .lines()
+                .rev()
+                .find_map(|line| serde_json::from_str::<Value>(line).ok());
+            Ok(result)
         } else {
             let error = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow::anyhow!("Installation failed: {error}"))
+            Err(anyhow::anyhow!(
+                "Building the launch command failed: {error}"
+            ))
         }
     }
 
@@ -356,8 +731,19 @@ impl Archon {
         url: String,
         data: Option<Value>,
     ) -> NetworkResponse {
+        let client = match http::client() {
+            Ok(client) => client,
+            Err(e) => {
+                return NetworkResponse {
+                    success: false,
+                    data: None,
+                    error: Some(e.to_string()),
+                };
+            }
+        };
+
         match operation.as_str() {
-            "get" => match reqwest::get(&url).await {
+            "get" => match client.get(&url).send().await {
                 Ok(response) => match response.json::<Value>().await {
                     Ok(json) => NetworkResponse {
                         success: true,
@@ -377,7 +763,6 @@ impl Archon {
                 },
             },
             "post" => {
-                let client = reqwest::Client::new();
                 let mut request = client.post(&url);
 
                 if let Some(json_data) = data {
@@ -425,12 +810,23 @@ impl Archon {
         &self,
         operation: String,
         args: Vec<String>,
+    ) -> Result<PythonResponse> {
+        self.python_operation_with_logs(operation, args, None).await
+    }
+
+    /// Send a Python operation whose output is streamed to `log_tx`, and wait for response.
+    pub async fn python_operation_with_logs(
+        &self,
+        operation: String,
+        args: Vec<String>,
+        log_tx: Option<mpsc::UnboundedSender<MinecraftLogMessage>>,
     ) -> Result<PythonResponse> {
         let (tx, rx) = tokio::sync::oneshot::channel();
 
         self.send(ArchonMessage::Python {
             operation,
             args,
+            log_tx,
             response_tx: Some(tx),
         })
         .await?;
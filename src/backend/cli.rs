@@ -0,0 +1,538 @@
+//! Headless command-line launching, for scripts and CI smoke tests.
+
+use crate::backend::archon::Archon;
+use crate::backend::python::python::{GameEvent, LaunchConfig, MinecraftLogMessage};
+use crate::backend::services::launcher::{check_java_version, jvm_arguments};
+use crate::backend::services::settings::{JvmPreset, LauncherSettings, MIN_MEMORY_MB};
+use crate::backend::utils::download::{DownloadEvent, subscribe};
+use crate::backend::utils::paths::get_launcher_dir;
+use crate::frontend::services::user::UserConfig;
+use anyhow::Result;
+use log::{info, warn};
+use serde::Serialize;
+use std::path::PathBuf;
+use tokio::sync::{broadcast, mpsc};
+
+/// Username used when neither `--username` nor a saved user is available.
+const DEFAULT_USERNAME: &str = "Player";
+
+/// Usage of the headless launch arguments.
+pub const USAGE: &str = "Usage: launcher [--launch <version> [--username <name>] [--memory <mb>] \
+                         [--server <host:port>] [--game-dir <path>] [--dry-run] [--json]]";
+
+/// Arguments of `--launch <version>` and the options that go with it, see [`USAGE`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchArgs {
+    pub version: String,
+    pub username: Option<String>,
+    /// Maximum Java heap in megabytes, overriding the launcher settings.
+    pub memory_mb: Option<u32>,
+    /// Server to join once the game has started.
+    pub server: Option<String>,
+    /// Directory the game runs in, defaults to the shared Minecraft directory.
+    pub game_dir: Option<PathBuf>,
+    /// Print the launch command instead of starting the game.
+    pub dry_run: bool,
+    /// Print newline-delimited JSON events instead of plain text, see [`CliEvent`].
+    pub json: bool,
+}
+
+impl LaunchArgs {
+    /// Parse the command-line arguments, without the program name.
+    ///
+    /// Returns `None` without `--launch`, so the launcher opens its window as usual.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut version = None;
+        let mut username = None;
+        let mut memory_mb = None;
+        let mut server = None;
+        let mut game_dir = None;
+        let mut dry_run = false;
+        let mut json = false;
+        let mut unknown = Vec::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("Missing value for {arg}"))
+            };
+            match arg.as_str() {
+                "--launch" => version = Some(value()?),
+                "--username" => username = Some(value()?),
+                "--memory" => memory_mb = Some(parse_memory(&value()?)?),
+                "--server" => server = Some(parse_server(&value()?)?),
+                "--game-dir" => game_dir = Some(PathBuf::from(value()?)),
+                "--dry-run" => dry_run = true,
+                "--json" => json = true,
+                _ => unknown.push(arg),
+            }
+        }
+
+        match version {
+            // A typo in a launch option must not silently launch with the default instead
+            Some(_) if !unknown.is_empty() => {
+                Err(anyhow::anyhow!("Unknown argument: {}", unknown[0]))
+            }
+            Some(version) => Ok(Some(Self {
+                version,
+                username,
+                memory_mb,
+                server,
+                game_dir,
+                dry_run,
+                json,
+            })),
+            None if username.is_some()
+                || memory_mb.is_some()
+                || server.is_some()
+                || game_dir.is_some()
+                || dry_run
+                || json =>
+            {
+                Err(anyhow::anyhow!("Launch options need --launch <version>"))
+            }
+            None => {
+                // The OS may pass its own arguments, e.g. when opened from a file manager
+                for arg in unknown {
+                    warn!("Ignoring unknown argument: {arg}");
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+fn parse_memory(value: &str) -> Result<u32> {
+    let memory_mb = value
+        .parse::<u32>()
+        .map_err(|_| anyhow::anyhow!("Invalid memory size: {value}"))?;
+    if memory_mb < MIN_MEMORY_MB {
+        return Err(anyhow::anyhow!(
+            "Memory must be at least {MIN_MEMORY_MB} MB, got {memory_mb}"
+        ));
+    }
+    Ok(memory_mb)
+}
+
+/// Check a `host` or `host:port` server address.
+fn parse_server(value: &str) -> Result<String> {
+    let address = value.trim();
+    // Like the launcher script, a port follows a bracketed IPv6 address or a single colon
+    let port = if address.starts_with('[') {
+        address.split_once("]:").map(|(_, port)| port)
+    } else if address.matches(':').count() == 1 {
+        address.split_once(':').map(|(_, port)| port)
+    } else {
+        None
+    };
+    if address.is_empty()
+        || address.contains(char::is_whitespace)
+        || port.is_some_and(|port| port.parse::<u16>().is_err())
+    {
+        return Err(anyhow::anyhow!("Invalid server address: {value}"));
+    }
+    Ok(address.to_string())
+}
+
+/// Event printed as one line of JSON to stdout with `--json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CliEvent {
+    Install {
+        version: String,
+    },
+    DownloadStarted {
+        url: String,
+        total: Option<u64>,
+    },
+    DownloadProgress {
+        url: String,
+        done: u64,
+        total: Option<u64>,
+    },
+    DownloadCompleted {
+        url: String,
+    },
+    DownloadFailed {
+        url: String,
+        error: String,
+    },
+    /// Game files installed so far, counted in files.
+    InstallProgress {
+        status: Option<String>,
+        done: u64,
+        total: Option<u64>,
+    },
+    Installed {
+        version: String,
+    },
+    /// Launch command of a dry run, with secrets redacted.
+    Command {
+        command: String,
+    },
+    Launch {
+        pid: Option<u32>,
+    },
+    Log {
+        line: String,
+    },
+    /// The game finished loading.
+    Started,
+    Exit {
+        exit_code: i32,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl CliEvent {
+    /// Print the event as a single line of JSON.
+    pub fn print(&self) {
+        match serde_json::to_string(self) {
+            Ok(json) => println!("{json}"),
+            Err(e) => warn!("Failed to serialize CLI event: {e}"),
+        }
+    }
+}
+
+impl From<DownloadEvent> for CliEvent {
+    fn from(event: DownloadEvent) -> Self {
+        match event {
+            DownloadEvent::Started { url, total } => Self::DownloadStarted { url, total },
+            DownloadEvent::Progress { url, done, total } => {
+                Self::DownloadProgress { url, done, total }
+            }
+            DownloadEvent::Completed { url } => Self::DownloadCompleted { url },
+            DownloadEvent::Failed { url, error } => Self::DownloadFailed { url, error },
+            DownloadEvent::InstallProgress {
+                status,
+                done,
+                total,
+            } => Self::InstallProgress {
+                status,
+                done,
+                total,
+            },
+        }
+    }
+}
+
+/// Line printed for a download event without `--json`, if it is worth printing.
+///
+/// Byte progress of single files is left out, as it would flood the terminal.
+fn download_event_text(event: &DownloadEvent) -> Option<String> {
+    match event {
+        DownloadEvent::InstallProgress {
+            status,
+            done,
+            total,
+        } => {
+            let status = status.as_deref().unwrap_or("Installing");
+            Some(match total {
+                Some(total) => format!("{status} ({done}/{total})"),
+                None => format!("{status} ({done})"),
+            })
+        }
+        DownloadEvent::Completed { url } => Some(format!("Downloaded {url}")),
+        DownloadEvent::Failed { url, error } => Some(format!("Failed to download {url}: {error}")),
+        DownloadEvent::Started { .. } | DownloadEvent::Progress { .. } => None,
+    }
+}
+
+/// Print the events of every download until the returned task is aborted,
+/// as [`CliEvent`]s with `json` and as plain text otherwise.
+fn print_download_events(json: bool) -> tokio::task::JoinHandle<()> {
+    let mut events = subscribe();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) if json => CliEvent::from(event).print(),
+                Ok(event) => {
+                    if let Some(text) = download_event_text(&event) {
+                        println!("{text}");
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("Missed {missed} download events");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+/// Install a version, launch it, and wait for the game to exit.
+///
+/// Game output is printed to stdout, as [`CliEvent`]s with `--json`.
+/// Returns the exit code of the game.
+/// With `--dry-run` the version is only checked, and the launch command is printed
+/// with secrets redacted instead.
+pub async fn run_launch(archon: &Archon, args: LaunchArgs) -> Result<i32> {
+    let username = match args.username {
+        Some(username) => Some(username),
+        None => UserConfig::load().await.map(|user| user.username),
+    };
+    let username = match username {
+        Some(username) => {
+            UserConfig::validate_username(&username)?;
+            username
+        }
+        // Reserved so nobody picks it as their name, but fine as the built-in fallback
+        None => DEFAULT_USERNAME.to_string(),
+    };
+
+    let minecraft_dir = get_launcher_dir()?.join("minecraft");
+    let game_dir = args.game_dir.unwrap_or_else(|| minecraft_dir.clone());
+    let mut settings = LauncherSettings::load().await;
+    if let Some(memory_mb) = args.memory_mb {
+        settings.max_memory_mb = memory_mb;
+    }
+
+    // A dry run must not download anything
+    let operation = if settings.offline_mode || args.dry_run {
+        "check_minecraft"
+    } else {
+        "install_minecraft"
+    };
+    info!("Preparing Minecraft {}", args.version);
+    // Plain output of a dry run is only the launch command, so it can be captured
+    let show_progress = args.json || !args.dry_run;
+    let downloads = show_progress.then(|| {
+        if args.json {
+            CliEvent::Install {
+                version: args.version.clone(),
+            }
+            .print();
+        } else {
+            println!("Installing Minecraft {}", args.version);
+        }
+        print_download_events(args.json)
+    });
+    let response = archon
+        .python_operation(
+            operation.to_string(),
+            vec![
+                args.version.clone(),
+                minecraft_dir.to_string_lossy().to_string(),
+            ],
+        )
+        .await?;
+    if !response.success {
+        return Err(anyhow::anyhow!(
+            "Failed to install version {}: {}",
+            args.version,
+            response.error.unwrap_or("Unknown error".to_string())
+        ));
+    }
+    if let Some(downloads) = downloads {
+        downloads.abort();
+        if args.json {
+            CliEvent::Installed {
+                version: args.version.clone(),
+            }
+            .print();
+        } else {
+            println!("Installed Minecraft {}", args.version);
+        }
+    }
+
+    let (log_sender, mut log_receiver) = mpsc::unbounded_channel();
+    let launch_config = LaunchConfig {
+        username,
+        version: args.version.clone(),
+        java_path: settings
+            .java_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string()),
+        jvm_args: jvm_arguments(
+            &settings,
+            &JvmPreset::Default,
+            &minecraft_dir,
+            &args.version,
+            &log_sender,
+        )
+        .await,
+        game_args: Vec::new(),
+        access_token: "dummy_token".to_string(),
+        uuid: "00000000-0000-0000-0000-000000000000".to_string(),
+        server_address: args.server,
+        world: None,
+    };
+
+    if args.dry_run {
+        let response = archon
+            .python_operation(
+                "launch_command".to_string(),
+                launch_config.into_launch_args(&minecraft_dir, &game_dir),
+            )
+            .await?;
+        if !response.success {
+            return Err(anyhow::anyhow!(
+                "Failed to build launch command: {}",
+                response.error.unwrap_or("Unknown error".to_string())
+            ));
+        }
+        let command = response
+            .data
+            .as_ref()
+            .and_then(|d| d.get("command"))
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Launcher script returned no command"))?;
+        if args.json {
+            CliEvent::Command {
+                command: command.to_string(),
+            }
+            .print();
+        } else {
+            println!("{command}");
+        }
+        return Ok(0);
+    }
+
+    check_java_version(&settings, &minecraft_dir, &args.version, &args.version).await?;
+    info!("Launching Minecraft {}", args.version);
+    let response = archon
+        .python_operation_with_logs(
+            "launch_minecraft".to_string(),
+            launch_config.into_launch_args(&minecraft_dir, &game_dir),
+            Some(log_sender),
+        )
+        .await?;
+    if !response.success {
+        return Err(anyhow::anyhow!(
+            "Failed to launch Minecraft: {}",
+            response.error.unwrap_or("Unknown error".to_string())
+        ));
+    }
+    if args.json {
+        let pid = response
+            .data
+            .as_ref()
+            .and_then(|d| d.get("pid"))
+            .and_then(serde_json::Value::as_u64)
+            .map(|p| p as u32);
+        CliEvent::Launch { pid }.print();
+    }
+
+    while let Some(message) = log_receiver.recv().await {
+        match message {
+            MinecraftLogMessage::Log { line, .. } if args.json => CliEvent::Log { line }.print(),
+            MinecraftLogMessage::Log { line, .. } => println!("{line}"),
+            MinecraftLogMessage::Exit { exit_code, .. } => {
+                if args.json {
+                    CliEvent::Exit { exit_code }.print();
+                }
+                return Ok(exit_code);
+            }
+            MinecraftLogMessage::Error { message, .. } => {
+                return Err(anyhow::anyhow!(message));
+            }
+            MinecraftLogMessage::Event(GameEvent::Started) if args.json => {
+                CliEvent::Started.print();
+            }
+            MinecraftLogMessage::LaunchResult { .. } | MinecraftLogMessage::Event(_) => {}
+        }
+    }
+    Err(anyhow::anyhow!("Game output ended without an exit code"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<LaunchArgs>> {
+        LaunchArgs::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn parses_launch_options() {
+        let args = parse(&[
+            "--launch",
+            "1.20.1",
+            "--memory",
+            "4096",
+            "--server",
+            "play.example.com:25566",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(args.version, "1.20.1");
+        assert_eq!(args.memory_mb, Some(4096));
+        assert_eq!(args.server.as_deref(), Some("play.example.com:25566"));
+    }
+
+    #[test]
+    fn rejects_invalid_memory() {
+        assert!(parse(&["--launch", "1.20.1", "--memory", "lots"]).is_err());
+        assert!(parse(&["--launch", "1.20.1", "--memory", "-1"]).is_err());
+        let too_little = (MIN_MEMORY_MB - 1).to_string();
+        assert!(parse(&["--launch", "1.20.1", "--memory", &too_little]).is_err());
+        assert!(parse(&["--launch", "1.20.1", "--memory"]).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_servers() {
+        for server in [
+            "",
+            "play example.com",
+            "play.example.com:port",
+            "host:70000",
+        ] {
+            assert!(
+                parse(&["--launch", "1.20.1", "--server", server]).is_err(),
+                "accepted {server:?}"
+            );
+        }
+        let args = parse(&["--launch", "1.20.1", "--server", "[::1]:25565"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(args.server.as_deref(), Some("[::1]:25565"));
+    }
+
+    #[test]
+    fn rejects_unknown_arguments_of_a_launch() {
+        let error = parse(&["--launch", "1.20.1", "--memroy", "4096"]).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown argument: --memroy");
+    }
+
+    #[test]
+    fn ignores_unknown_arguments_without_a_launch() {
+        assert_eq!(parse(&["-psn_0_12345"]).unwrap(), None);
+        assert!(parse(&["--memory", "4096"]).is_err());
+    }
+
+    #[test]
+    fn install_progress_is_a_json_event() {
+        let event = CliEvent::from(DownloadEvent::InstallProgress {
+            status: Some("Download Assets".to_string()),
+            done: 12,
+            total: Some(40),
+        });
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"install_progress","status":"Download Assets","done":12,"total":40}"#
+        );
+    }
+
+    #[test]
+    fn prints_install_progress_as_text() {
+        let event = DownloadEvent::InstallProgress {
+            status: Some("Download Assets".to_string()),
+            done: 12,
+            total: Some(40),
+        };
+        assert_eq!(
+            download_event_text(&event).as_deref(),
+            Some("Download Assets (12/40)")
+        );
+
+        let event = DownloadEvent::Progress {
+            url: "https://example.com/file".to_string(),
+            done: 1,
+            total: None,
+        };
+        assert_eq!(download_event_text(&event), None);
+    }
+}
@@ -3,10 +3,14 @@
 use crate::backend::archon::Archon;
 use crate::backend::python::python::MinecraftLogMessage;
 use crate::backend::services::{
-    Instance, InstanceService, LaunchResult, LauncherService, VersionManifest,
+    CloudConfig, CloudPassword, CloudService, CloudSyncInfo, Instance, InstanceService, JvmPreset,
+    LaunchResult, LauncherService, LauncherSettings, ModInfo, ModLoader, ModpackImport,
+    ModpackSearchResult, NewsItem, NewsService, SavedServer, Screenshot, VersionManifest, cloud,
+    modpack,
 };
 use anyhow::Result;
 use log::{error, info, warn};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{Mutex, mpsc};
 
@@ -21,9 +25,7 @@ pub struct Communicator {
 impl Communicator {
     /// Create a new instance.
     pub async fn new(archon: Arc<Archon>) -> Result<Self> {
-        let mut instance_service = InstanceService::new();
-        instance_service.load_instances().await?;
-        let instance_service = Arc::new(Mutex::new(instance_service));
+        let instance_service = InstanceService::shared().await?;
 
         let launcher_service = LauncherService::new(instance_service.clone(), archon.clone())?;
         let launcher_service = Arc::new(Mutex::new(launcher_service));
@@ -43,10 +45,12 @@ impl Communicator {
         launcher_service.get_instances().await
     }
 
-    /// Create a new instance with the specified version.
-    pub async fn create_instance(&self, version: &str) -> Result<Option<u32>> {
+    /// Create a new instance with the specified version and mod loader.
+    pub async fn create_instance(&self, version: &str, loader: ModLoader) -> Result<Option<u32>> {
         let launcher_service = self.launcher_service.lock().await;
-        launcher_service.create_instance_with_version(version).await
+        launcher_service
+            .create_instance_with_version(version, loader)
+            .await
     }
 
     /// Delete an instance.
@@ -55,6 +59,17 @@ impl Communicator {
         launcher_service.delete_instance(instance_id).await
     }
 
+    /// Clone an instance, reporting copied files through `on_progress`.
+    pub async fn clone_instance<F>(&self, instance_id: u32, on_progress: F) -> Result<Option<u32>>
+    where
+        F: Fn(usize, usize) + Send,
+    {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service
+            .clone_instance(instance_id, on_progress)
+            .await
+    }
+
     /// Rename an instance.
     pub async fn rename_instance(&self, instance_id: u32, new_name: &str) -> Result<bool> {
         let launcher_service = self.launcher_service.lock().await;
@@ -63,6 +78,179 @@ impl Communicator {
             .await
     }
 
+    /// Get the directory of an instance.
+    pub async fn get_instance_directory(&self, instance_id: u32) -> PathBuf {
+        let instance_service = self.instance_service.lock().await;
+        instance_service.get_instance_directory(instance_id)
+    }
+
+    /// Create an instance from a Modrinth modpack (`.mrpack`).
+    pub async fn import_modpack(&self, path: &Path) -> Result<Option<ModpackImport>> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.import_modpack(path).await
+    }
+
+    /// Search Modrinth for modpacks.
+    pub async fn search_modpacks(&self, query: &str) -> Result<Vec<ModpackSearchResult>> {
+        modpack::search_modpacks(query).await
+    }
+
+    /// Download a modpack from Modrinth and create an instance from it.
+    pub async fn install_modrinth_modpack(
+        &self,
+        project_id: &str,
+    ) -> Result<Option<ModpackImport>> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.install_modrinth_modpack(project_id).await
+    }
+
+    /// Export an instance as a zip archive.
+    pub async fn export_instance(
+        &self,
+        instance_id: u32,
+        dest: PathBuf,
+        include_worlds: bool,
+    ) -> Result<()> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service
+            .export_instance(instance_id, dest, include_worlds)
+            .await
+    }
+
+    /// Create a new instance from an exported zip archive.
+    pub async fn import_instance(&self, path: &Path) -> Result<Option<u32>> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.import_instance(path).await
+    }
+
+    /// Register the versions of a vanilla `.minecraft` directory as instances.
+    pub async fn import_existing_installation(&self, minecraft_dir: &Path) -> Result<Vec<u32>> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service
+            .import_existing_installation(minecraft_dir)
+            .await
+    }
+
+    /// Save captured debug console output into the logs folder of an instance.
+    pub async fn export_debug_log(&self, instance_id: u32, contents: &str) -> Result<PathBuf> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service
+            .export_debug_log(instance_id, contents)
+            .await
+    }
+
+    /// Set a custom icon for an instance.
+    pub async fn set_instance_icon(&self, instance_id: u32, source: &Path) -> Result<PathBuf> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.set_instance_icon(instance_id, source).await
+    }
+
+    /// Set the game window size and fullscreen mode of an instance.
+    pub async fn set_window_settings(
+        &self,
+        instance_id: u32,
+        size: Option<(u32, u32)>,
+        fullscreen: bool,
+    ) -> Result<bool> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service
+            .set_window_settings(instance_id, size, fullscreen)
+            .await
+    }
+
+    /// Set the JVM garbage collector preset of an instance.
+    pub async fn set_jvm_preset(&self, instance_id: u32, preset: JvmPreset) -> Result<bool> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.set_jvm_preset(instance_id, preset).await
+    }
+
+    /// Remember a server joined from an instance.
+    pub async fn record_server(&self, instance_id: u32, address: &str) -> Result<bool> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.record_server(instance_id, address).await
+    }
+
+    /// List screenshots of an instance, newest first.
+    pub async fn list_screenshots(&self, instance_id: u32) -> Result<Vec<Screenshot>> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service.list_screenshots(instance_id).await
+    }
+
+    /// List the mods of an instance, including disabled ones.
+    pub async fn list_mods(&self, instance_id: u32) -> Result<Vec<ModInfo>> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service.list_mods(instance_id).await
+    }
+
+    /// Enable or disable a mod of an instance, returning its new file name.
+    pub async fn set_mod_enabled(
+        &self,
+        instance_id: u32,
+        file_name: &str,
+        enabled: bool,
+    ) -> Result<String> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service
+            .set_mod_enabled(instance_id, file_name, enabled)
+            .await
+    }
+
+    /// Newest crash report of an instance written at or after `since`.
+    pub async fn latest_crash_report(
+        &self,
+        instance_id: u32,
+        since: u64,
+    ) -> Result<Option<PathBuf>> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service
+            .latest_crash_report(instance_id, since)
+            .await
+    }
+
+    /// List the singleplayer worlds of an instance.
+    pub async fn list_worlds(&self, instance_id: u32) -> Result<Vec<String>> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service.list_worlds(instance_id).await
+    }
+
+    /// Open the game logs folder of an instance.
+    pub async fn open_logs_folder(&self, instance_id: u32) -> Result<()> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service.open_logs_folder(instance_id).await
+    }
+
+    /// Path of the latest game log of an instance.
+    pub async fn latest_log_path(&self, instance_id: u32) -> PathBuf {
+        let instance_service = self.instance_service.lock().await;
+        instance_service.latest_log_path(instance_id)
+    }
+
+    /// List the multiplayer servers saved in an instance.
+    pub async fn list_servers(&self, instance_id: u32) -> Result<Vec<SavedServer>> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service.list_servers(instance_id).await
+    }
+
+    /// Delete a screenshot of an instance.
+    pub async fn delete_screenshot(&self, instance_id: u32, file_name: &str) -> Result<()> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service
+            .delete_screenshot(instance_id, file_name)
+            .await
+    }
+
+    /// Re-download the missing or corrupted game files of an instance.
+    pub async fn repair_instance(&self, instance_id: u32) -> Result<Vec<String>> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.repair_instance(instance_id).await
+    }
+
+    /// Build the command that would launch an instance, with secrets redacted.
+    pub async fn launch_command(&self, instance_id: u32) -> Result<String> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.launch_command(instance_id).await
+    }
+
     /// Open instance folder in system file explorer.
     pub async fn open_instance_folder(&self, instance_id: u32) -> Result<()> {
         let launcher_service = self.launcher_service.lock().await;
@@ -77,6 +265,124 @@ impl Communicator {
         launcher_service.get_version_manifest().await
     }
 
+    /// Refresh the version manifest if the cache is stale, or always when `force` is set.
+    pub async fn refresh_version_manifest(&self, force: bool) -> Result<VersionManifest> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.update_manifest(force).await
+    }
+
+    /// When the version manifest was last fetched or revalidated.
+    pub async fn manifest_refreshed_at(&self) -> Option<u64> {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.manifest_refreshed_at().await
+    }
+
+    // News
+
+    /// Get the newest Minecraft news articles.
+    pub async fn get_news(&self, limit: usize) -> Result<Vec<NewsItem>> {
+        NewsService::get_news(limit).await
+    }
+
+    // Launcher settings
+
+    /// Load launcher settings, falling back to defaults.
+    pub async fn load_settings(&self) -> LauncherSettings {
+        LauncherSettings::load().await
+    }
+
+    /// Save launcher settings.
+    pub async fn save_settings(&self, settings: &LauncherSettings) -> Result<()> {
+        settings.save().await
+    }
+
+    // Cloud sync
+
+    /// Compare the saves of an instance with the cloud copy.
+    pub async fn cloud_sync_status(&self, instance_id: u32) -> Result<CloudSyncInfo> {
+        let (instance, saves_dir) = self.instance_saves(instance_id).await?;
+        let config = self.cloud_config().await;
+        cloud::sync_status(&config, &instance, &saves_dir).await
+    }
+
+    /// Upload the saves of an instance to the cloud.
+    pub async fn upload_saves(&self, instance_id: u32) -> Result<()> {
+        let (instance, saves_dir) = self.instance_saves(instance_id).await?;
+        let config = self.cloud_config().await;
+        cloud::upload_saves(&config, &instance, &saves_dir).await
+    }
+
+    /// Replace the saves of an instance with the cloud copy.
+    pub async fn download_saves(&self, instance_id: u32) -> Result<()> {
+        let (instance, saves_dir) = self.instance_saves(instance_id).await?;
+        let config = self.cloud_config().await;
+        cloud::download_saves(&config, &instance, &saves_dir).await
+    }
+
+    /// An instance and its saves directory.
+    async fn instance_saves(&self, instance_id: u32) -> Result<(Instance, PathBuf)> {
+        let instance_service = self.instance_service.lock().await;
+        let instance = instance_service
+            .get_instance(instance_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Instance {instance_id} not found"))?;
+        let saves_dir = instance_service
+            .get_instance_directory(instance_id)
+            .join("saves");
+        Ok((instance, saves_dir))
+    }
+
+    /// Compare the instance list and game settings with the cloud copy.
+    pub async fn cloud_configs_status(&self) -> Result<CloudSyncInfo> {
+        self.cloud_service().await?.status().await
+    }
+
+    /// Upload the instance list and game settings to the cloud.
+    pub async fn push_configs(&self) -> Result<()> {
+        self.cloud_service().await?.push().await
+    }
+
+    /// Replace the instance list and game settings with the cloud copy.
+    pub async fn pull_configs(&self) -> Result<()> {
+        let cloud_service = self.cloud_service().await?;
+        // Held until the pulled instances are loaded, so no change is saved over them
+        let mut instance_service = self.instance_service.lock().await;
+        cloud_service.pull().await?;
+        instance_service.load_instances().await
+    }
+
+    async fn cloud_service(&self) -> Result<CloudService> {
+        let instances_config = self
+            .instance_service
+            .lock()
+            .await
+            .get_instances_config_path();
+        CloudService::new(self.cloud_config().await, instances_config)
+    }
+
+    /// Cloud server from the launcher settings, with the password from the user config.
+    async fn cloud_config(&self) -> CloudConfig {
+        let mut config = LauncherSettings::load().await.cloud;
+        // Settings of older versions keep it until a user logs in to move it to
+        if config.password.is_empty() {
+            config.password = self.load_cloud_password().await;
+        }
+        config
+    }
+
+    /// Password of the cloud server saved with the logged in user.
+    pub async fn load_cloud_password(&self) -> String {
+        match CloudPassword::new() {
+            Ok(passwords) => passwords.load().await,
+            Err(_) => String::new(),
+        }
+    }
+
+    /// Save the password of the cloud server with the logged in user.
+    pub async fn save_cloud_password(&self, password: &str) -> Result<()> {
+        CloudPassword::new()?.save(password).await
+    }
+
     // Launch management
 
     /// Install and launch a Minecraft instance.
@@ -84,14 +390,22 @@ impl Communicator {
         &self,
         instance_id: u32,
         version: &str,
+        server_address: Option<String>,
+        world: Option<String>,
         log_sender: mpsc::UnboundedSender<MinecraftLogMessage>,
     ) -> Result<LaunchResult> {
         let launcher_service = self.launcher_service.lock().await;
         launcher_service
-            .install_and_launch_instance(instance_id, version, log_sender)
+            .install_and_launch_instance(instance_id, version, server_address, world, log_sender)
             .await
     }
 
+    /// Cancel the install running for an instance, returning whether there was one.
+    pub async fn cancel_install(&self, instance_id: u32) -> bool {
+        let launcher_service = self.launcher_service.lock().await;
+        launcher_service.cancel_install(instance_id)
+    }
+
     /// Install Minecraft dependencies.
     pub async fn install_dependencies(&self) -> Result<()> {
         // Dependencies are installed during service initialization
@@ -1,6 +1,7 @@
 //! Backend of the application.
 
 pub mod archon;
+pub mod cli;
 pub mod communicator;
 pub mod python;
 pub mod services;
@@ -36,6 +36,48 @@ pub enum MinecraftLogMessage {
         success: bool,
         message: String,
     },
+    /// Stage of the launch, worked out by the launcher.
+    Event(GameEvent),
+}
+
+/// Stage of a game launch, so the UI doesn't have to read the game log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEvent {
+    /// Game files are being downloaded and checked.
+    Downloading,
+    /// The game process is being started.
+    Launching,
+    /// The game has finished loading, or has kept running for a while.
+    Started,
+    /// The game exited normally.
+    Stopped,
+    /// The game exited with an error.
+    Crashed { exit_code: i32 },
+}
+
+impl GameEvent {
+    /// Event for a game that exited with `exit_code`.
+    #[must_use]
+    pub const fn exited(exit_code: i32) -> Self {
+        if exit_code == 0 {
+            Self::Stopped
+        } else {
+            Self::Crashed { exit_code }
+        }
+    }
+}
+
+/// Log fragments that only appear once the game is about to open its window.
+///
+/// Logger output isn't translated, and these come from the game itself, so
+/// they show up in every locale and with mod loaders.
+const STARTED_MARKERS: [&str; 3] = ["Setting user:", "Backend library:", "LWJGL Version:"];
+
+/// Check whether a game log line shows that the game has started.
+#[must_use]
+pub fn is_started_line(line: &str) -> bool {
+    STARTED_MARKERS.iter().any(|marker| line.contains(marker))
+        || (line.contains("Created:") && line.contains("atlas"))
 }
 
 /// Configuration for launching Minecraft.
@@ -48,6 +90,39 @@ pub struct LaunchConfig {
     pub game_args: Vec<String>,
     pub access_token: String,
     pub uuid: String,
+    /// Server to join once the game has started, as `host` or `host:port`.
+    pub server_address: Option<String>,
+    /// Singleplayer world to open once the game has started, by folder name.
+    pub world: Option<String>,
+}
+
+impl LaunchConfig {
+    /// Arguments of the `launch_minecraft` operation for this configuration.
+    #[must_use]
+    pub fn into_launch_args(self, minecraft_dir: &Path, game_dir: &Path) -> Vec<String> {
+        let mut args = vec![
+            self.username,
+            self.version,
+            minecraft_dir.to_string_lossy().to_string(),
+            game_dir.to_string_lossy().to_string(),
+        ];
+        if let Some(server_address) = self.server_address {
+            args.extend(["--server".to_string(), server_address]);
+        }
+        if let Some(world) = self.world {
+            args.extend(["--world".to_string(), world]);
+        }
+        if let Some(java_path) = self.java_path {
+            args.extend(["--java".to_string(), java_path]);
+        }
+        for arg in self.jvm_args {
+            args.extend(["--jvm-arg".to_string(), arg]);
+        }
+        for arg in self.game_args {
+            args.extend(["--game-arg".to_string(), arg]);
+        }
+        args
+    }
 }
 
 /// Embedded Python bridge for Minecraft operations.
@@ -153,87 +228,7 @@ impl EmbeddedPythonBridge {
         // Process messages in the main task to avoid Send issues
         tokio::spawn(async move {
             while let Some(line) = rx.recv().await {
-                if let Ok(json_msg) = serde_json::from_str::<serde_json::Value>(&line) {
-                    match json_msg.get("type").and_then(|t| t.as_str()) {
-                        Some("launch_result") => {
-                            let success = json_msg
-                                .get("success")
-                                .and_then(|s| s.as_bool())
-                                .unwrap_or(false);
-                            let pid = json_msg
-                                .get("pid")
-                                .and_then(|p| p.as_u64())
-                                .map(|p| p as u32);
-                            let message = json_msg
-                                .get("message")
-                                .and_then(|m| m.as_str())
-                                .unwrap_or("")
-                                .to_string();
-
-                            log_callback(MinecraftLogMessage::LaunchResult {
-                                success,
-                                pid,
-                                message,
-                            });
-                        }
-                        Some("log") => {
-                            let log_line = json_msg
-                                .get("line")
-                                .and_then(|l| l.as_str())
-                                .unwrap_or("")
-                                .to_string();
-                            let pid = json_msg
-                                .get("pid")
-                                .and_then(|p| p.as_u64())
-                                .map(|p| p as u32);
-
-                            log_callback(MinecraftLogMessage::Log {
-                                line: log_line,
-                                pid,
-                            });
-                        }
-                        Some("exit") => {
-                            let pid = json_msg
-                                .get("pid")
-                                .and_then(serde_json::Value::as_i64)
-                                .unwrap_or(0) as u32;
-                            let exit_code = json_msg
-                                .get("exit_code")
-                                .and_then(serde_json::Value::as_i64)
-                                .unwrap_or(0) as i32;
-                            let message = json_msg
-                                .get("message")
-                                .and_then(|m| m.as_str())
-                                .unwrap_or("")
-                                .to_string();
-
-                            log_callback(MinecraftLogMessage::Exit {
-                                pid,
-                                exit_code,
-                                message,
-                            });
-                        }
-                        Some("error") => {
-                            let message = json_msg
-                                .get("message")
-                                .and_then(|m| m.as_str())
-                                .unwrap_or("")
-                                .to_string();
-
-                            log_callback(MinecraftLogMessage::Error {
-                                success: false,
-                                message,
-                            });
-                        }
-                        _ => {
-                            // Unknown message type, log as regular log line
-                            log_callback(MinecraftLogMessage::Log { line, pid: None });
-                        }
-                    }
-                } else {
-                    // Not JSON, treat as regular log line
-                    log_callback(MinecraftLogMessage::Log { line, pid: None });
-                }
+                log_callback(parse_log_line(line));
             }
         });
 
@@ -261,3 +256,245 @@ impl Default for EmbeddedPythonBridge {
         })
     }
 }
+
+/// Parse a line printed by the launcher script into a log message.
+///
+/// Lines that are not JSON messages are passed through as plain log lines.
+#[must_use]
+pub fn parse_log_line(line: String) -> MinecraftLogMessage {
+    let Ok(json_msg) = serde_json::from_str::<serde_json::Value>(&line) else {
+        return MinecraftLogMessage::Log { line, pid: None };
+    };
+
+    let message = json_msg
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or("")
+        .to_string();
+    let pid = json_msg
+        .get("pid")
+        .and_then(|p| p.as_u64())
+        .map(|p| p as u32);
+
+    match json_msg.get("type").and_then(|t| t.as_str()) {
+        Some("launch_result") => MinecraftLogMessage::LaunchResult {
+            success: json_msg
+                .get("success")
+                .and_then(|s| s.as_bool())
+                .unwrap_or(false),
+            pid,
+            message,
+        },
+        Some("log") => MinecraftLogMessage::Log {
+            line: json_msg
+                .get("line")
+                .and_then(|l| l.as_str())
+                .unwrap_or("")
+                .to_string(),
+            pid,
+        },
+        Some("exit") => MinecraftLogMessage::Exit {
+            pid: pid.unwrap_or(0),
+            exit_code: json_msg
+                .get("exit_code")
+                .and_then(serde_json::Value::as_i64)
+                .unwrap_or(0) as i32,
+            message,
+        },
+        Some("error") => MinecraftLogMessage::Error {
+            success: false,
+            message,
+        },
+        // Unknown message type, log as regular log line
+        _ => MinecraftLogMessage::Log { line, pid: None },
+    }
+}
+
+/// Game log line split into its level, thread and message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameLogLine {
+    /// "FATAL", "ERROR", "WARN", "INFO", "DEBUG" or "TRACE".
+    pub level: &'static str,
+    /// Time of day the line was logged, like "12:34:56".
+    pub time: Option<String>,
+    pub thread: Option<String>,
+    pub message: String,
+}
+
+impl GameLogLine {
+    /// Parse a game log line.
+    ///
+    /// Understands `[HH:MM:SS] [thread/LEVEL]: message` from 1.7 onwards and
+    /// `YYYY-MM-DD HH:MM:SS [LEVEL] [logger] message` from older versions.
+    /// Other lines, such as stack traces, keep their text and get a level from their content.
+    #[must_use]
+    pub fn parse(line: &str) -> Self {
+        Self::parse_modern(line)
+            .or_else(|| Self::parse_legacy(line))
+            .unwrap_or_else(|| Self {
+                level: Self::guess_level(line),
+                time: None,
+                thread: None,
+                message: line.to_string(),
+            })
+    }
+
+    fn parse_modern(line: &str) -> Option<Self> {
+        // The time is missing in lines the launcher adds itself
+        let (time, rest) = match line
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once("] "))
+        {
+            Some((time, rest)) if time.bytes().all(|b| b.is_ascii_digit() || b == b':') => {
+                (Some(time.to_string()), rest)
+            }
+            _ => (None, line),
+        };
+        let (header, message) = rest.strip_prefix('[')?.split_once(']')?;
+        let (thread, level) = header.rsplit_once('/')?;
+        Some(Self {
+            level: Self::normalize_level(level)?,
+            time,
+            thread: Some(thread.to_string()),
+            message: message.trim_start_matches(':').trim_start().to_string(),
+        })
+    }
+
+    fn parse_legacy(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(3, ' ');
+        let date = parts.next()?;
+        let time = parts.next()?;
+        if !date.contains('-') || !time.contains(':') {
+            return None;
+        }
+        let (level, message) = parts.next()?.strip_prefix('[')?.split_once(']')?;
+        let message = message.trim_start();
+        let (thread, message) = match message
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+        {
+            Some((thread, message)) => (Some(thread.to_string()), message.trim_start()),
+            None => (None, message),
+        };
+        Some(Self {
+            level: Self::normalize_level(level)?,
+            time: Some(time.to_string()),
+            thread,
+            message: message.to_string(),
+        })
+    }
+
+    /// Level names of Log4j and of the `java.util.logging` used before 1.7.
+    fn normalize_level(level: &str) -> Option<&'static str> {
+        match level {
+            "FATAL" => Some("FATAL"),
+            "ERROR" | "SEVERE" => Some("ERROR"),
+            "WARN" | "WARNING" => Some("WARN"),
+            "INFO" => Some("INFO"),
+            "DEBUG" | "FINE" => Some("DEBUG"),
+            "TRACE" | "FINER" | "FINEST" => Some("TRACE"),
+            _ => None,
+        }
+    }
+
+    fn guess_level(line: &str) -> &'static str {
+        if line.contains("Exception") || line.starts_with("\tat ") {
+            "ERROR"
+        } else if line.starts_with("[STDERR]") {
+            "WARN"
+        } else {
+            "INFO"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modern_line() {
+        let line = GameLogLine::parse("[12:34:56] [Render thread/INFO]: Setting user: Steve");
+        assert_eq!(line.level, "INFO");
+        assert_eq!(line.time.as_deref(), Some("12:34:56"));
+        assert_eq!(line.thread.as_deref(), Some("Render thread"));
+        assert_eq!(line.message, "Setting user: Steve");
+    }
+
+    #[test]
+    fn parses_modern_line_without_time() {
+        let line = GameLogLine::parse("[main/WARN]: Ambiguous mixin target");
+        assert_eq!(line.level, "WARN");
+        assert_eq!(line.time, None);
+        assert_eq!(line.thread.as_deref(), Some("main"));
+        assert_eq!(line.message, "Ambiguous mixin target");
+    }
+
+    #[test]
+    fn parses_modern_thread_with_slash() {
+        let line = GameLogLine::parse("[12:34:56] [Worker-Main-1/ERROR]: Failed to load");
+        assert_eq!(line.level, "ERROR");
+        assert_eq!(line.thread.as_deref(), Some("Worker-Main-1"));
+
+        let line = GameLogLine::parse("[12:34:56] [pool/io/DEBUG]: Reading chunk");
+        assert_eq!(line.level, "DEBUG");
+        assert_eq!(line.thread.as_deref(), Some("pool/io"));
+    }
+
+    #[test]
+    fn parses_legacy_line() {
+        let line =
+            GameLogLine::parse("2013-06-28 16:05:12 [INFO] [Minecraft-Client] Setting user: Steve");
+        assert_eq!(line.level, "INFO");
+        assert_eq!(line.time.as_deref(), Some("16:05:12"));
+        assert_eq!(line.thread.as_deref(), Some("Minecraft-Client"));
+        assert_eq!(line.message, "Setting user: Steve");
+    }
+
+    #[test]
+    fn parses_legacy_line_without_logger() {
+        let line = GameLogLine::parse("2013-06-28 16:05:12 [SEVERE] Unable to launch");
+        assert_eq!(line.level, "ERROR");
+        assert_eq!(line.thread, None);
+        assert_eq!(line.message, "Unable to launch");
+    }
+
+    #[test]
+    fn normalizes_java_util_logging_levels() {
+        assert_eq!(
+            GameLogLine::parse("2013-06-28 16:05:12 [WARNING] x").level,
+            "WARN"
+        );
+        assert_eq!(
+            GameLogLine::parse("2013-06-28 16:05:12 [FINE] x").level,
+            "DEBUG"
+        );
+        assert_eq!(
+            GameLogLine::parse("2013-06-28 16:05:12 [FINEST] x").level,
+            "TRACE"
+        );
+    }
+
+    #[test]
+    fn guesses_level_of_other_lines() {
+        let line = GameLogLine::parse("java.lang.NullPointerException: oops");
+        assert_eq!(line.level, "ERROR");
+        assert_eq!(line.thread, None);
+        assert_eq!(line.message, "java.lang.NullPointerException: oops");
+
+        assert_eq!(
+            GameLogLine::parse("\tat net.minecraft.client.Main.main(Main.java:1)").level,
+            "ERROR"
+        );
+        assert_eq!(GameLogLine::parse("[STDERR] something").level, "WARN");
+        assert_eq!(GameLogLine::parse("Loading natives").level, "INFO");
+    }
+
+    #[test]
+    fn keeps_unknown_levels_as_plain_text() {
+        let line = GameLogLine::parse("[12:34:56] [main/NOTICE]: hello");
+        assert_eq!(line.level, "INFO");
+        assert_eq!(line.thread, None);
+        assert_eq!(line.message, "[12:34:56] [main/NOTICE]: hello");
+    }
+}
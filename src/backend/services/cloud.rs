@@ -0,0 +1,669 @@
+//! Cloud sync of instance saves and settings through a WebDAV server.
+
+use crate::backend::services::instance::Instance;
+use crate::backend::services::tracker::VisitTracker;
+use crate::backend::utils::archive::{collect_files, extract_zip, write_zip};
+use crate::backend::utils::download::{FileHashes, download_file};
+use crate::backend::utils::http;
+use crate::backend::utils::paths::{get_cache_dir, get_launcher_dir};
+use anyhow::Result;
+use base64::{Engine as _, engine::general_purpose};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::fs as async_fs;
+
+/// File in the launcher directory that remembers the state of the last sync per instance.
+const SYNC_STATE_FILE: &str = "cloud_sync.json";
+
+/// Sync state key of the instance list and game settings, next to the sync keys of saves.
+const CONFIGS_RECORD: &str = "configs";
+
+/// Files and folders of an instance that hold game settings rather than worlds.
+const INSTANCE_CONFIG_FILES: [&str; 2] = ["options.txt", "config"];
+
+/// WebDAV server that stores instance saves.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloudConfig {
+    /// Folder URL the saves archives are stored in.
+    pub url: String,
+    pub username: String,
+    /// Kept in the user config, so it is never written to the launcher settings.
+    /// Settings of older versions still have it until it is moved with [`CloudPassword::take_from`].
+    #[serde(skip_serializing)]
+    pub password: String,
+}
+
+impl CloudConfig {
+    /// Whether a server has been set up.
+    #[must_use]
+    pub fn is_configured(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+
+    /// Check that the server URL can be used.
+    pub fn validate(&self) -> Result<()> {
+        let url = self.url.trim();
+        if !url.is_empty() && !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(anyhow::anyhow!(
+                "Cloud URL must start with https:// or http://"
+            ));
+        }
+        Ok(())
+    }
+
+    /// URL of the saves archive of an instance.
+    fn saves_url(&self, instance: &Instance) -> String {
+        format!(
+            "{}/{}",
+            self.url.trim().trim_end_matches('/'),
+            saves_archive_name(instance)
+        )
+    }
+
+    /// URL of the archive with the instance list and game settings.
+    fn configs_url(&self) -> String {
+        format!(
+            "{}/launcher-configs.zip",
+            self.url.trim().trim_end_matches('/')
+        )
+    }
+
+    /// HTTP client that authenticates every request.
+    fn client(&self) -> Result<reqwest::Client> {
+        if !self.is_configured() {
+            return Err(anyhow::anyhow!("Cloud sync is not set up"));
+        }
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        if !self.username.is_empty() {
+            let credentials =
+                general_purpose::STANDARD.encode(format!("{}:{}", self.username, self.password));
+            let mut value =
+                reqwest::header::HeaderValue::from_str(&format!("Basic {credentials}"))?;
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+
+        Ok(http::client_builder()?
+            .user_agent("DreamLauncher")
+            .default_headers(headers)
+            .build()?)
+    }
+}
+
+/// Password of the cloud server, saved in the user config next to the username.
+pub struct CloudPassword {
+    user_config: PathBuf,
+}
+
+impl CloudPassword {
+    /// Key of the password in the user config.
+    const KEY: &str = "cloud_password";
+
+    pub fn new() -> Result<Self> {
+        Ok(Self::at(get_cache_dir()?.join("user_config.json")))
+    }
+
+    fn at(user_config: PathBuf) -> Self {
+        Self { user_config }
+    }
+
+    /// Saved password, empty when there is none or no user is logged in.
+    pub async fn load(&self) -> String {
+        self.read_user()
+            .await
+            .ok()
+            .and_then(|user| user.get(Self::KEY)?.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    /// Save the password with the logged in user.
+    pub async fn save(&self, password: &str) -> Result<()> {
+        let mut user = self
+            .read_user()
+            .await
+            .map_err(|_| anyhow::anyhow!("Log in to save the cloud password"))?;
+        if user
+            .get(Self::KEY)
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            == password
+        {
+            return Ok(());
+        }
+
+        if password.is_empty() {
+            user.remove(Self::KEY);
+        } else {
+            user.insert(Self::KEY.to_string(), password.into());
+        }
+        async_fs::write(&self.user_config, serde_json::to_string(&user)?).await?;
+        Ok(())
+    }
+
+    /// Move the password that settings of older versions still have into the user config.
+    ///
+    /// A password already saved with the user is newer, so it is kept.
+    pub async fn take_from(&self, config: &mut CloudConfig) -> Result<()> {
+        if self.load().await.is_empty() {
+            self.save(&config.password).await?;
+        }
+        config.password.clear();
+        Ok(())
+    }
+
+    /// Fields of the user config, kept as they are so the username is not lost.
+    async fn read_user(&self) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let json = async_fs::read_to_string(&self.user_config).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// How the local saves of an instance compare to the cloud copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncStatus {
+    /// Nothing has been uploaded yet.
+    NotSynced,
+    UpToDate,
+    /// Saves changed locally since the last sync.
+    LocalChanges,
+    /// The cloud copy changed since the last sync, e.g. from another computer.
+    RemoteChanges,
+    /// Both sides changed, so either upload or download loses changes.
+    Conflict,
+}
+
+impl SyncStatus {
+    /// Human-readable status.
+    #[must_use]
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            Self::NotSynced => "Not synced",
+            Self::UpToDate => "Up to date",
+            Self::LocalChanges => "Local changes",
+            Self::RemoteChanges => "Newer in cloud",
+            Self::Conflict => "Conflict",
+        }
+    }
+}
+
+/// Sync state of an instance's saves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CloudSyncInfo {
+    pub status: SyncStatus,
+    /// Time of the last upload or download in seconds since the Unix epoch.
+    pub last_synced: Option<u64>,
+}
+
+/// What both sides looked like right after the last sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncRecord {
+    synced_at: u64,
+    /// Newest modification time in the saves directory.
+    local_modified: u64,
+    /// `ETag` or `Last-Modified` of the cloud copy.
+    remote_tag: Option<String>,
+}
+
+/// Compare the local saves of an instance with the cloud copy.
+pub async fn sync_status(
+    config: &CloudConfig,
+    instance: &Instance,
+    saves_dir: &Path,
+) -> Result<CloudSyncInfo> {
+    let client = config.client()?;
+    let remote_tag = remote_tag(&client, &config.saves_url(instance)).await?;
+    let local_modified = local_modified(saves_dir).await?;
+    let record = load_records().await.remove(&instance.sync_key());
+    Ok(compare(record, local_modified, remote_tag))
+}
+
+/// Work out what changed on either side since the last sync.
+fn compare(
+    record: Option<SyncRecord>,
+    local_modified: u64,
+    remote_tag: Option<String>,
+) -> CloudSyncInfo {
+    let status = match (&record, &remote_tag) {
+        (None, None) => SyncStatus::NotSynced,
+        // Uploaded from another computer
+        (None, Some(_)) if local_modified == 0 => SyncStatus::RemoteChanges,
+        (None, Some(_)) => SyncStatus::Conflict,
+        (Some(record), _) => {
+            let local_changed = local_modified > record.local_modified;
+            let remote_changed = remote_tag != record.remote_tag;
+            match (local_changed, remote_changed) {
+                (false, false) => SyncStatus::UpToDate,
+                (true, false) => SyncStatus::LocalChanges,
+                (false, true) => SyncStatus::RemoteChanges,
+                (true, true) => SyncStatus::Conflict,
+            }
+        }
+    };
+
+    CloudSyncInfo {
+        status,
+        last_synced: record.map(|record| record.synced_at),
+    }
+}
+
+/// Upload the saves of an instance, replacing the cloud copy.
+pub async fn upload_saves(
+    config: &CloudConfig,
+    instance: &Instance,
+    saves_dir: &Path,
+) -> Result<()> {
+    let client = config.client()?;
+    let url = config.saves_url(instance);
+
+    let files = collect_files(saves_dir).await?;
+    let file_count = files.len();
+    let archive_path = cache_archive_path(&saves_archive_name(instance))?;
+    upload_archive(&client, &url, &archive_path, saves_dir, files).await?;
+
+    let record = SyncRecord {
+        synced_at: VisitTracker::current_timestamp(),
+        local_modified: local_modified(saves_dir).await?,
+        remote_tag: remote_tag(&client, &url).await?,
+    };
+    save_record(&instance.sync_key(), record).await?;
+
+    info!(
+        "Uploaded {file_count} save files of instance {}",
+        instance.id
+    );
+    Ok(())
+}
+
+/// Download the cloud copy of an instance's saves, replacing the local ones.
+///
+/// Local saves are only swapped out once the archive has been fully downloaded and extracted.
+pub async fn download_saves(
+    config: &CloudConfig,
+    instance: &Instance,
+    saves_dir: &Path,
+) -> Result<()> {
+    let client = config.client()?;
+    let url = config.saves_url(instance);
+    let remote_tag = remote_tag(&client, &url)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No saves of this instance in the cloud"))?;
+
+    let archive_path = cache_archive_path(&saves_archive_name(instance))?;
+    download_file(&client, &url, &archive_path, &FileHashes::default()).await?;
+
+    let staging_dir = saves_dir.with_file_name("saves.download");
+    let backup_dir = saves_dir.with_file_name("saves.old");
+    for dir in [&staging_dir, &backup_dir] {
+        if dir.exists() {
+            async_fs::remove_dir_all(dir).await?;
+        }
+    }
+
+    let (archive, staging) = (archive_path.clone(), staging_dir.clone());
+    let extracted = tokio::task::spawn_blocking(move || extract_zip(&archive, &staging)).await?;
+    if let Err(e) = async_fs::remove_file(&archive_path).await {
+        warn!(
+            "Failed to remove saves archive {}: {e}",
+            archive_path.display()
+        );
+    }
+    extracted?;
+    // An empty archive has no entries to create the directory
+    async_fs::create_dir_all(&staging_dir).await?;
+
+    if saves_dir.exists() {
+        async_fs::rename(saves_dir, &backup_dir).await?;
+    }
+    async_fs::rename(&staging_dir, saves_dir).await?;
+    if backup_dir.exists() {
+        async_fs::remove_dir_all(&backup_dir).await?;
+    }
+
+    let record = SyncRecord {
+        synced_at: VisitTracker::current_timestamp(),
+        local_modified: local_modified(saves_dir).await?,
+        remote_tag: Some(remote_tag),
+    };
+    save_record(&instance.sync_key(), record).await?;
+
+    info!("Downloaded saves of instance {}", instance.id);
+    Ok(())
+}
+
+/// Syncs the instance list and the game settings of every instance, such as
+/// `options.txt` and mod configs, so a setup can be mirrored on another computer.
+///
+/// Neither the launcher settings nor the user config with the cloud password are part of the upload.
+pub struct CloudService {
+    config: CloudConfig,
+    /// Launcher directory, which synced paths are relative to.
+    root: PathBuf,
+    /// File the instance list is stored in.
+    instances_config: PathBuf,
+}
+
+impl CloudService {
+    pub fn new(config: CloudConfig, instances_config: PathBuf) -> Result<Self> {
+        Ok(Self {
+            config,
+            root: get_launcher_dir()?,
+            instances_config,
+        })
+    }
+
+    /// Compare the local instance list and game settings with the cloud copy.
+    pub async fn status(&self) -> Result<CloudSyncInfo> {
+        let client = self.config.client()?;
+        let remote_tag = remote_tag(&client, &self.config.configs_url()).await?;
+        let local_modified = newest_modified(self.config_files().await?).await?;
+        let record = load_records().await.remove(CONFIGS_RECORD);
+        Ok(compare(record, local_modified, remote_tag))
+    }
+
+    /// Upload the instance list and game settings, replacing the cloud copy.
+    pub async fn push(&self) -> Result<()> {
+        let client = self.config.client()?;
+        let url = self.config.configs_url();
+
+        let files = self.config_files().await?;
+        let file_count = files.len();
+        let archive_path = cache_archive_path("launcher-configs.zip")?;
+        upload_archive(&client, &url, &archive_path, &self.root, files).await?;
+
+        let record = SyncRecord {
+            synced_at: VisitTracker::current_timestamp(),
+            local_modified: newest_modified(self.config_files().await?).await?,
+            remote_tag: remote_tag(&client, &url).await?,
+        };
+        save_record(CONFIGS_RECORD, record).await?;
+
+        info!("Uploaded {file_count} launcher config files");
+        Ok(())
+    }
+
+    /// Download the cloud copy of the instance list and game settings.
+    ///
+    /// Files from the cloud replace the local ones, local files the cloud does not have are kept.
+    /// Instances loaded before the pull have to be reloaded from disk.
+    pub async fn pull(&self) -> Result<()> {
+        let client = self.config.client()?;
+        let url = self.config.configs_url();
+        let remote_tag = remote_tag(&client, &url)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No launcher settings in the cloud"))?;
+
+        let archive_path = cache_archive_path("launcher-configs.zip")?;
+        download_file(&client, &url, &archive_path, &FileHashes::default()).await?;
+
+        let staging_dir = archive_path.with_extension("download");
+        if staging_dir.exists() {
+            async_fs::remove_dir_all(&staging_dir).await?;
+        }
+        let (archive, staging) = (archive_path.clone(), staging_dir.clone());
+        let extracted =
+            tokio::task::spawn_blocking(move || extract_zip(&archive, &staging)).await?;
+        if let Err(e) = async_fs::remove_file(&archive_path).await {
+            warn!(
+                "Failed to remove configs archive {}: {e}",
+                archive_path.display()
+            );
+        }
+        extracted?;
+
+        // Only known settings files are copied, whatever else the archive contains
+        let mut file_count = 0;
+        for file in collect_files(&staging_dir).await? {
+            let relative_path = file.strip_prefix(&staging_dir)?;
+            let target = self.root.join(relative_path);
+            if !self.is_config_file(&target) {
+                warn!("Skipping unexpected file in cloud configs: {relative_path:?}");
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                async_fs::create_dir_all(parent).await?;
+            }
+            async_fs::copy(&file, &target).await?;
+            file_count += 1;
+        }
+        async_fs::remove_dir_all(&staging_dir).await?;
+
+        let record = SyncRecord {
+            synced_at: VisitTracker::current_timestamp(),
+            local_modified: newest_modified(self.config_files().await?).await?,
+            remote_tag: Some(remote_tag),
+        };
+        save_record(CONFIGS_RECORD, record).await?;
+
+        info!("Downloaded {file_count} launcher config files");
+        Ok(())
+    }
+
+    /// Local files that are synced, the instance list first.
+    async fn config_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        if self.instances_config.is_file() {
+            files.push(self.instances_config.clone());
+        }
+
+        let instances_dir = self.root.join("instances");
+        if !instances_dir.exists() {
+            return Ok(files);
+        }
+        let mut entries = async_fs::read_dir(&instances_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_dir() {
+                continue;
+            }
+            for name in INSTANCE_CONFIG_FILES {
+                let path = entry.path().join(name);
+                if path.is_dir() {
+                    files.extend(collect_files(&path).await?);
+                } else if path.is_file() {
+                    files.push(path);
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// Whether a path in the launcher directory is one of the synced files.
+    fn is_config_file(&self, path: &Path) -> bool {
+        if path == self.instances_config {
+            return true;
+        }
+        let Ok(relative_path) = path.strip_prefix(self.root.join("instances")) else {
+            return false;
+        };
+        // `<instance folder>/options.txt` or `<instance folder>/config/...`
+        let mut components = relative_path.components().skip(1);
+        let first = components.next().map(|c| c.as_os_str().to_string_lossy());
+        match first.as_deref() {
+            Some("options.txt") => components.next().is_none(),
+            Some("config") => components.next().is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// Zip files relative to `root` and upload the archive to `url`.
+///
+/// The archive is uploaded under a temporary name and moved into place once complete,
+/// so an interrupted upload never replaces the cloud copy and the next attempt starts over.
+async fn upload_archive(
+    client: &reqwest::Client,
+    url: &str,
+    archive_path: &Path,
+    root: &Path,
+    files: Vec<PathBuf>,
+) -> Result<()> {
+    let partial_url = format!("{url}.part");
+
+    if let Some(parent) = archive_path.parent() {
+        async_fs::create_dir_all(parent).await?;
+    }
+    let (archive, root) = (archive_path.to_path_buf(), root.to_path_buf());
+    tokio::task::spawn_blocking(move || write_zip(&archive, &root, &files)).await??;
+
+    let result = async {
+        let body = async_fs::File::open(archive_path).await?;
+        client
+            .put(&partial_url)
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        client
+            .request(reqwest::Method::from_bytes(b"MOVE")?, &partial_url)
+            .header("Destination", url)
+            .header("Overwrite", "T")
+            .send()
+            .await?
+            .error_for_status()?;
+        anyhow::Ok(())
+    }
+    .await;
+    if let Err(e) = async_fs::remove_file(archive_path).await {
+        warn!("Failed to remove archive {}: {e}", archive_path.display());
+    }
+    result
+}
+
+/// `ETag` or `Last-Modified` of a cloud file, or `None` if it does not exist.
+async fn remote_tag(client: &reqwest::Client, url: &str) -> Result<Option<String>> {
+    let response = client.head(url).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let response = response.error_for_status()?;
+
+    let headers = response.headers();
+    let tag = headers
+        .get(reqwest::header::ETAG)
+        .or_else(|| headers.get(reqwest::header::LAST_MODIFIED))
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    Ok(Some(tag.unwrap_or_default()))
+}
+
+/// Newest modification time in a saves directory in seconds since the Unix epoch.
+///
+/// The directory itself counts too, so deleting a world is noticed. Missing saves give 0.
+async fn local_modified(saves_dir: &Path) -> Result<u64> {
+    if !saves_dir.exists() {
+        return Ok(0);
+    }
+
+    let mut paths = collect_files(saves_dir).await?;
+    paths.push(saves_dir.to_path_buf());
+    newest_modified(paths).await
+}
+
+/// Newest modification time of some files in seconds since the Unix epoch, 0 for none.
+async fn newest_modified(paths: Vec<PathBuf>) -> Result<u64> {
+    let mut newest = 0;
+    for path in paths {
+        let modified = async_fs::metadata(&path)
+            .await?
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        newest = newest.max(modified);
+    }
+    Ok(newest)
+}
+
+/// File name of the saves archive of an instance, in the cloud and in the cache.
+fn saves_archive_name(instance: &Instance) -> String {
+    format!("instance-{}-saves.zip", instance.sync_key())
+}
+
+fn cache_archive_path(file_name: &str) -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join("cloud").join(file_name))
+}
+
+/// Sync state by [`Instance::sync_key`] for saves, or [`CONFIGS_RECORD`].
+async fn load_records() -> HashMap<String, SyncRecord> {
+    let Ok(path) = get_launcher_dir().map(|dir| dir.join(SYNC_STATE_FILE)) else {
+        return HashMap::new();
+    };
+    let Ok(json) = async_fs::read_to_string(&path).await else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!("Ignoring invalid cloud sync state {}: {e}", path.display());
+        HashMap::new()
+    })
+}
+
+async fn save_record(key: &str, record: SyncRecord) -> Result<()> {
+    let mut records = load_records().await;
+    records.insert(key.to_string(), record);
+
+    let path = get_launcher_dir()?.join(SYNC_STATE_FILE);
+    async_fs::write(&path, serde_json::to_string_pretty(&records)?).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_is_not_written_to_settings() {
+        let config = CloudConfig {
+            url: "https://dav.example.com/saves".to_string(),
+            username: "steve".to_string(),
+            password: "hunter2".to_string(),
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("hunter2"));
+
+        // Settings saved by older versions still have it, so it can be moved to the user config
+        let old = r#"{"url": "https://dav.example.com/saves", "username": "steve", "password": "hunter2"}"#;
+        let loaded: CloudConfig = serde_json::from_str(old).unwrap();
+        assert_eq!(loaded.username, "steve");
+        assert_eq!(loaded.password, "hunter2");
+    }
+
+    #[tokio::test]
+    async fn old_password_moves_to_the_user_config() {
+        let dir = std::env::temp_dir().join(format!(
+            "dream-launcher-cloud-password-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let user_config = dir.join("user_config.json");
+        let passwords = CloudPassword::at(user_config.clone());
+
+        // Without a logged in user there is nowhere to move it yet
+        let mut config = CloudConfig {
+            password: "hunter2".to_string(),
+            ..CloudConfig::default()
+        };
+        assert!(passwords.take_from(&mut config).await.is_err());
+        assert_eq!(config.password, "hunter2");
+
+        std::fs::write(&user_config, r#"{"username": "steve"}"#).unwrap();
+        passwords.take_from(&mut config).await.unwrap();
+        assert!(config.password.is_empty());
+        assert_eq!(passwords.load().await, "hunter2");
+        let user: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&user_config).unwrap()).unwrap();
+        assert_eq!(user["username"], "steve");
+
+        // A password saved since then is not replaced by the old one
+        passwords.save("correct horse").await.unwrap();
+        let mut config = CloudConfig {
+            password: "hunter2".to_string(),
+            ..CloudConfig::default()
+        };
+        passwords.take_from(&mut config).await.unwrap();
+        assert_eq!(passwords.load().await, "correct horse");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,13 +1,109 @@
 //! Instance management service.
 
-use crate::backend::utils::paths::{get_cache_dir, get_launcher_dir};
+use crate::backend::services::mods::{DISABLED_SUFFIX, ModInfo, is_mod_file, read_mod_info};
+use crate::backend::services::servers::{SavedServer, read_servers_dat};
+use crate::backend::services::settings::JvmPreset;
+use crate::backend::services::tracker::VisitTracker;
+use crate::backend::utils::archive::{apply_unix_mode, collect_files, write_zip_with_entries};
+use crate::backend::utils::image::downscale_png;
+use crate::backend::utils::paths::{get_launcher_dir, open_path};
 use anyhow::Result;
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs as async_fs;
+use tokio::sync::Mutex;
+
+/// Maximum number of instances a user can have.
+pub const MAX_INSTANCES: usize = 100;
+
+/// Instance subdirectories carried over when cloning an instance.
+const CLONED_DIRECTORIES: [&str; 4] = ["mods", "config", "resourcepacks", "saves"];
+
+/// Game files shared by all versions, copied from an imported installation.
+const IMPORTED_GAME_DIRECTORIES: [&str; 2] = ["libraries", "assets"];
+
+/// User content of an imported installation moved into the instance of the last used version.
+const IMPORTED_USER_CONTENT: [&str; 6] = [
+    "saves",
+    "resourcepacks",
+    "shaderpacks",
+    "screenshots",
+    "options.txt",
+    "servers.dat",
+];
+
+/// Instance subdirectories left out of exports because the game regenerates them.
+const EXPORT_SKIPPED_DIRECTORIES: [&str; 6] = [
+    "versions",
+    "libraries",
+    "assets",
+    "natives",
+    "logs",
+    "crash-reports",
+];
+
+/// Instance subdirectory with the worlds, only exported when asked for.
+const WORLDS_DIRECTORY: &str = "saves";
+
+/// Name of the manifest stored at the root of exported instances.
+const EXPORT_MANIFEST: &str = "instance.json";
+
+/// Largest width or height of a custom instance icon, in pixels.
+const MAX_ICON_SIZE: u32 = 128;
+
+/// Number of recently joined servers remembered per instance.
+const MAX_RECENT_SERVERS: usize = 3;
+
+/// Mod loader installed on top of the vanilla version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModLoader {
+    #[default]
+    Vanilla,
+    Forge,
+    NeoForge,
+    Fabric,
+    Quilt,
+}
+
+impl ModLoader {
+    /// All supported loaders.
+    pub const ALL: [Self; 5] = [
+        Self::Vanilla,
+        Self::Forge,
+        Self::NeoForge,
+        Self::Fabric,
+        Self::Quilt,
+    ];
+
+    /// Identifier understood by the Python launcher.
+    #[must_use]
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::Vanilla => "vanilla",
+            Self::Forge => "forge",
+            Self::NeoForge => "neoforge",
+            Self::Fabric => "fabric",
+            Self::Quilt => "quilt",
+        }
+    }
+
+    /// Human-readable loader name.
+    #[must_use]
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            Self::Vanilla => "Vanilla",
+            Self::Forge => "Forge",
+            Self::NeoForge => "NeoForge",
+            Self::Fabric => "Fabric",
+            Self::Quilt => "Quilt",
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Instance {
@@ -16,6 +112,80 @@ pub struct Instance {
     pub color: String,
     pub level: u32,
     pub version: String,
+    #[serde(default)]
+    pub loader: ModLoader,
+    /// Mod loader version to install, the newest one for the game version if unset.
+    #[serde(default)]
+    pub loader_version: Option<String>,
+    /// Servers joined from this instance, most recent first.
+    #[serde(default)]
+    pub recent_servers: Vec<String>,
+    /// Custom icon copied into the instance directory.
+    #[serde(default)]
+    pub icon_path: Option<PathBuf>,
+    /// Time of the last successful launch in seconds since the Unix epoch.
+    #[serde(default)]
+    pub last_played: Option<u64>,
+    /// Game window width; only applied together with `window_height`.
+    #[serde(default)]
+    pub window_width: Option<u32>,
+    /// Game window height; only applied together with `window_width`.
+    #[serde(default)]
+    pub window_height: Option<u32>,
+    /// Start the game in fullscreen instead of a sized window.
+    #[serde(default)]
+    pub fullscreen: bool,
+    /// Garbage collector flags added when launching.
+    #[serde(default)]
+    pub jvm_preset: JvmPreset,
+    /// Names the cloud copy of the saves, so a new instance that reuses the id of a
+    /// deleted one doesn't pick up its saves. Empty for instances created before it existed.
+    #[serde(default)]
+    pub sync_id: String,
+}
+
+/// Maximum length of an instance name in characters.
+pub const MAX_NAME_LENGTH: usize = 8;
+
+/// Smallest game window size that can be configured.
+pub const MIN_WINDOW_WIDTH: u32 = 640;
+pub const MIN_WINDOW_HEIGHT: u32 = 480;
+
+/// Largest game window dimension that can be configured.
+const MAX_WINDOW_SIZE: u32 = 16384;
+
+/// Check that a game window size is usable.
+pub fn validate_window_size(width: u32, height: u32) -> Result<()> {
+    if width < MIN_WINDOW_WIDTH || height < MIN_WINDOW_HEIGHT {
+        return Err(anyhow::anyhow!(
+            "Window size must be at least {MIN_WINDOW_WIDTH}x{MIN_WINDOW_HEIGHT}"
+        ));
+    }
+    if width > MAX_WINDOW_SIZE || height > MAX_WINDOW_SIZE {
+        return Err(anyhow::anyhow!(
+            "Window size must be at most {MAX_WINDOW_SIZE}x{MAX_WINDOW_SIZE}"
+        ));
+    }
+    Ok(())
+}
+
+/// Screenshot taken in an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    pub path: PathBuf,
+    /// Modification time in seconds since the Unix epoch.
+    pub modified: u64,
+}
+
+/// Manifest describing an exported instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstanceManifest {
+    name: String,
+    version: String,
+    #[serde(default)]
+    loader: ModLoader,
+    #[serde(default)]
+    loader_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,14 +206,121 @@ impl Instance {
             color,
             level: 28, // Default level
             version,
+            loader: ModLoader::Vanilla,
+            loader_version: None,
+            recent_servers: Vec::new(),
+            icon_path: None,
+            last_played: None,
+            window_width: None,
+            window_height: None,
+            fullscreen: false,
+            jvm_preset: JvmPreset::Default,
+            sync_id: new_sync_id(),
+        }
+    }
+
+    /// Key of the cloud copy of the saves.
+    ///
+    /// Older instances keep using their id, so saves they already uploaded are still found.
+    #[must_use]
+    pub fn sync_key(&self) -> String {
+        if self.sync_id.is_empty() {
+            self.id.to_string()
+        } else {
+            self.sync_id.clone()
+        }
+    }
+
+    /// Whether the game version can join a world or server on startup through quick play.
+    ///
+    /// Quick play arrived in snapshot 23w14a and release 1.20. Unknown version formats are
+    /// assumed to be new enough, matching the launch script.
+    #[must_use]
+    pub fn supports_quick_play(&self) -> bool {
+        let version = self.version.as_str();
+
+        // Snapshots are named like 23w14a
+        if let Some((year, rest)) = version.split_once('w')
+            && let (Ok(year), Some(Ok(week))) =
+                (year.parse::<u32>(), rest.get(..2).map(str::parse::<u32>))
+        {
+            return (year, week) >= (23, 14);
+        }
+
+        // Releases and pre-releases are named like 1.20.1 or 1.20-pre1
+        let release = version.split(['-', ' ']).next().unwrap_or(version);
+        let parts: Result<Vec<u32>, _> = release.split('.').map(str::parse).collect();
+        parts.map_or(true, |parts| parts >= vec![1, 20])
+    }
+
+    /// Game arguments for the window size or fullscreen mode.
+    #[must_use]
+    pub fn window_arguments(&self) -> Vec<String> {
+        if self.fullscreen {
+            return vec!["--fullscreen".to_string()];
+        }
+
+        match (self.window_width, self.window_height) {
+            (Some(width), Some(height)) => match validate_window_size(width, height) {
+                Ok(()) => vec![
+                    "--width".to_string(),
+                    width.to_string(),
+                    "--height".to_string(),
+                    height.to_string(),
+                ],
+                Err(e) => {
+                    warn!("Ignoring window size of instance {}: {e}", self.id);
+                    Vec::new()
+                }
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Name for a duplicate of this instance, shortened to fit the name limit.
+    #[must_use]
+    pub fn copy_name(&self) -> String {
+        const SUFFIX: &str = " copy";
+        let base: String = self
+            .name
+            .chars()
+            .take(MAX_NAME_LENGTH - SUFFIX.len())
+            .collect();
+        format!("{}{SUFFIX}", base.trim_end())
+    }
+
+    #[must_use]
+    pub fn new_with_loader(id: u32, version: String, loader: ModLoader) -> Self {
+        Self {
+            loader,
+            ..Self::new_with_version(id, version)
         }
     }
 }
 
+/// Random id for the cloud copy of a new instance's saves.
+fn new_sync_id() -> String {
+    use std::hash::{BuildHasher, RandomState};
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    // Every `RandomState` is seeded differently, so ids differ even within a nanosecond
+    format!("{:016x}", RandomState::new().hash_one(nanos))
+}
+
+/// Held while the instances file is written.
+static SAVE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// The service every communicator works on, so changes can't overwrite each other.
+static SHARED: tokio::sync::OnceCell<Arc<Mutex<InstanceService>>> =
+    tokio::sync::OnceCell::const_new();
+
 #[derive(Clone)]
 pub struct InstanceService {
     instances: HashMap<u32, Instance>,
     next_id: u32,
+    /// Directory the instances and their config are kept in.
+    launcher_dir: PathBuf,
 }
 
 impl InstanceService {
@@ -52,9 +329,34 @@ impl InstanceService {
         Self {
             instances: HashMap::new(),
             next_id: 1,
+            launcher_dir: get_launcher_dir().unwrap_or_else(|_| PathBuf::from("Dream Launcher")),
+        }
+    }
+
+    /// Service keeping its instances in `launcher_dir` instead of the launcher directory.
+    #[cfg(test)]
+    fn with_launcher_dir(launcher_dir: PathBuf) -> Self {
+        Self {
+            launcher_dir,
+            ..Self::new()
         }
     }
 
+    /// The process-wide service, loading the instances from disk on first use.
+    ///
+    /// Holding its lock from reading an instance to saving it keeps concurrent
+    /// changes from different windows and tasks from getting lost.
+    pub async fn shared() -> Result<Arc<Mutex<Self>>> {
+        SHARED
+            .get_or_try_init(|| async {
+                let mut service = Self::new();
+                service.load_instances().await?;
+                Ok(Arc::new(Mutex::new(service)))
+            })
+            .await
+            .cloned()
+    }
+
     /// Load instances from disk.
     pub async fn load_instances(&mut self) -> Result<()> {
         let config_path = self.get_instances_config_path();
@@ -103,12 +405,19 @@ impl InstanceService {
         self.instances.get(&id)
     }
 
-    /// Create a new instance with the specified version.
-    pub async fn create_instance_with_version(&mut self, version: &str) -> Result<Option<u32>> {
-        info!("create_instance_with_version called with version: {version}");
+    /// Create a new instance with the specified version and mod loader.
+    pub async fn create_instance_with_version(
+        &mut self,
+        version: &str,
+        loader: ModLoader,
+    ) -> Result<Option<u32>> {
+        info!(
+            "create_instance_with_version called with version: {version}, loader: {}",
+            loader.id()
+        );
 
-        // Check if we can create more instances (max 14)
-        if self.instances.len() >= 14 {
+        // Check if we can create more instances
+        if self.instances.len() >= MAX_INSTANCES {
             warn!(
                 "Cannot create more instances, limit reached: {}",
                 self.instances.len()
@@ -116,21 +425,19 @@ impl InstanceService {
             return Ok(None);
         }
 
-        let current_id = self.next_id;
-        let new_instance = Instance::new_with_version(current_id, version.to_string());
+        let current_id = self.free_id(version)?;
+        let new_instance = Instance::new_with_loader(current_id, version.to_string(), loader);
         let instance_id = new_instance.id;
 
         info!("Creating instance {instance_id} with version: {version}");
         self.instances.insert(instance_id, new_instance);
-        self.next_id = current_id + 1;
+        self.next_id = self.next_id.max(current_id.saturating_add(1));
 
         // Create instance directories
         let folder_name = self
             .generate_folder_name_for_version(version, instance_id)
             .unwrap_or_else(|| format!("instance_{instance_id}"));
-        let instance_dir = get_launcher_dir()
-            .unwrap_or_else(|_| PathBuf::from("Dream Launcher"))
-            .join(format!("instances/{folder_name}"));
+        let instance_dir = self.launcher_dir.join(format!("instances/{folder_name}"));
 
         if let Err(e) = self.create_instance_directories_with_path(&instance_dir) {
             warn!("Failed to create directories for instance {instance_id}: {e}");
@@ -147,9 +454,7 @@ impl InstanceService {
             let folder_name = self
                 .generate_folder_name_for_version(&instance.version, id)
                 .unwrap_or_else(|| format!("instance_{id}"));
-            get_launcher_dir()
-                .unwrap_or_else(|_| PathBuf::from("Dream Launcher"))
-                .join(format!("instances/{folder_name}"))
+            self.launcher_dir.join(format!("instances/{folder_name}"))
         } else {
             return Ok(false);
         };
@@ -166,12 +471,302 @@ impl InstanceService {
                 }
             }
 
-            self.save_instances().await?;
+            // Deleting the last instance is the only way to save an empty list
+            self.write_instances(true).await?;
         }
 
         Ok(removed)
     }
 
+    /// Start cloning an instance, copying its metadata and user content into a new instance.
+    ///
+    /// The files are copied by [`PendingClone::copy_files`] without holding the service,
+    /// then [`Self::finish_clone`] registers the new instance. Returns `None` if the
+    /// instance doesn't exist or the instance limit is reached.
+    pub fn start_clone(&self, id: u32) -> Option<PendingClone> {
+        let Some(source) = self.instances.get(&id).cloned() else {
+            warn!("Cannot duplicate instance {id}: not found");
+            return None;
+        };
+
+        // Check if we can create more instances
+        if self.instances.len() >= MAX_INSTANCES {
+            warn!(
+                "Cannot duplicate instance {id}, limit reached: {}",
+                self.instances.len()
+            );
+            return None;
+        }
+
+        let sync_id = new_sync_id();
+        let source_dir = self.get_instance_directory(id);
+        // Named after the clone, so cloning an instance twice at once can't mix the copies
+        let staging_dir = source_dir.with_file_name(format!(".copy-{sync_id}"));
+        Some(PendingClone {
+            source,
+            source_dir,
+            staging_dir,
+            sync_id,
+            copied_files: 0,
+            has_icon: false,
+        })
+    }
+
+    /// Register a clone whose files were copied by [`PendingClone::copy_files`].
+    ///
+    /// Files are copied into a staging directory first, so the new instance only appears
+    /// once the copy finished and a failed copy leaves nothing behind.
+    pub async fn finish_clone(&mut self, clone: PendingClone) -> Result<Option<u32>> {
+        let id = clone.source.id;
+        // Instances may have been created while the files were copied
+        if self.instances.len() >= MAX_INSTANCES {
+            warn!(
+                "Cannot duplicate instance {id}, limit reached: {}",
+                self.instances.len()
+            );
+            clone.discard().await;
+            return Ok(None);
+        }
+
+        let new_id = match self.free_id(&clone.source.version) {
+            Ok(id) => id,
+            Err(e) => {
+                clone.discard().await;
+                return Err(e);
+            }
+        };
+        let cloned = Instance {
+            id: new_id,
+            name: clone.source.copy_name(),
+            last_played: None,
+            icon_path: None,
+            sync_id: clone.sync_id.clone(),
+            ..clone.source.clone()
+        };
+        self.instances.insert(new_id, cloned);
+        let target_dir = self.get_instance_directory(new_id);
+        info!(
+            "Duplicating instance {id} into {new_id}: {:?} -> {target_dir:?}",
+            clone.source_dir
+        );
+
+        if let Err(e) = self
+            .move_clone_into_place(new_id, &clone.staging_dir, &target_dir, clone.has_icon)
+            .await
+        {
+            self.instances.remove(&new_id);
+            clone.discard().await;
+            remove_partial_directory(&target_dir).await;
+            return Err(e);
+        }
+
+        info!(
+            "Duplicated instance {id} into {new_id} ({} files)",
+            clone.copied_files
+        );
+        Ok(Some(new_id))
+    }
+
+    /// Move a copied instance into place and save the instance list with it.
+    async fn move_clone_into_place(
+        &mut self,
+        new_id: u32,
+        staging_dir: &Path,
+        target_dir: &Path,
+        has_icon: bool,
+    ) -> Result<()> {
+        // Left behind by a deleted instance whose id is reused
+        if target_dir.exists() {
+            warn!("Replacing leftover instance directory {target_dir:?}");
+            async_fs::remove_dir_all(target_dir).await?;
+        }
+        async_fs::rename(staging_dir, target_dir).await?;
+        self.create_instance_directories_with_path(target_dir)?;
+
+        if has_icon && let Some(cloned) = self.instances.get_mut(&new_id) {
+            cloned.icon_path = Some(target_dir.join("icon.png"));
+        }
+        self.next_id = self.next_id.max(new_id.saturating_add(1));
+        self.save_instances().await
+    }
+
+    /// Export an instance's files and a manifest into a zip archive.
+    ///
+    /// Worlds can be large and personal, so they are left out unless `include_worlds` is set.
+    pub async fn export_instance(
+        &self,
+        id: u32,
+        dest: PathBuf,
+        include_worlds: bool,
+    ) -> Result<()> {
+        let instance = self
+            .instances
+            .get(&id)
+            .ok_or_else(|| anyhow::anyhow!("Instance {id} not found"))?;
+        let manifest = serde_json::to_vec_pretty(&InstanceManifest {
+            name: instance.name.clone(),
+            version: instance.version.clone(),
+            loader: instance.loader,
+            loader_version: instance.loader_version.clone(),
+        })?;
+
+        let instance_dir = self.get_instance_directory(id);
+        let files: Vec<PathBuf> = collect_files(&instance_dir)
+            .await?
+            .into_iter()
+            .filter(|file| {
+                let top_level = file
+                    .strip_prefix(&instance_dir)
+                    .ok()
+                    .and_then(|relative| relative.components().next());
+                !top_level.is_some_and(|component| {
+                    EXPORT_SKIPPED_DIRECTORIES
+                        .iter()
+                        .any(|skipped| component.as_os_str() == *skipped)
+                        || (!include_worlds && component.as_os_str() == WORLDS_DIRECTORY)
+                })
+            })
+            .collect();
+
+        let file_count = files.len();
+        let archive_path = dest.clone();
+        tokio::task::spawn_blocking(move || {
+            write_zip_with_entries(
+                &archive_path,
+                &instance_dir,
+                &files,
+                &[(EXPORT_MANIFEST, &manifest)],
+            )
+        })
+        .await??;
+
+        info!("Exported instance {id} to {dest:?} ({file_count} files)");
+        Ok(())
+    }
+
+    /// Create a new instance from an archive written by `export_instance`.
+    pub async fn import_instance(&mut self, archive_path: &Path) -> Result<Option<u32>> {
+        let manifest: InstanceManifest = {
+            let mut archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+            let entry = archive.by_name(EXPORT_MANIFEST).map_err(|_| {
+                anyhow::anyhow!("{} is not an exported instance", archive_path.display())
+            })?;
+            serde_json::from_reader(entry)?
+        };
+
+        let Some(id) = self
+            .create_instance_with_version(&manifest.version, manifest.loader)
+            .await?
+        else {
+            return Ok(None);
+        };
+        if let Some(instance) = self.instances.get_mut(&id) {
+            instance.name = manifest.name.chars().take(MAX_NAME_LENGTH).collect();
+            instance.loader_version = manifest.loader_version;
+        }
+        self.save_instances().await?;
+
+        let instance_dir = self.get_instance_directory(id);
+        let source = archive_path.to_path_buf();
+        let extracted =
+            tokio::task::spawn_blocking(move || extract_export_archive(&source, &instance_dir))
+                .await?;
+        if let Err(e) = extracted {
+            // Don't leave a half-imported instance behind
+            self.delete_instance(id).await?;
+            return Err(e);
+        }
+
+        let icon_path = self.get_instance_directory(id).join("icon.png");
+        if icon_path.exists()
+            && let Some(instance) = self.instances.get_mut(&id)
+        {
+            instance.icon_path = Some(icon_path);
+            self.save_instances().await?;
+        }
+
+        info!("Imported instance {id} from {archive_path:?}");
+        Ok(Some(id))
+    }
+
+    /// Register the versions installed by the vanilla launcher in a `.minecraft` directory.
+    ///
+    /// Game files are copied into the shared Minecraft directory so they are not downloaded again,
+    /// and anything still missing is downloaded by the install step of the first launch.
+    /// Worlds and other user content are copied into the instance of the last used version.
+    /// Returns the ids of the new instances.
+    pub async fn import_existing_installation(&mut self, minecraft_dir: &Path) -> Result<Vec<u32>> {
+        let versions_dir = minecraft_dir.join("versions");
+        if !versions_dir.is_dir() {
+            return Err(anyhow::anyhow!(
+                "{} is not a Minecraft installation",
+                minecraft_dir.display()
+            ));
+        }
+        let shared_dir = self.launcher_dir.join("minecraft");
+
+        let mut versions = Vec::new();
+        let mut entries = async_fs::read_dir(&versions_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let id = entry.file_name().to_string_lossy().to_string();
+            let Ok(json) = async_fs::read_to_string(entry.path().join(format!("{id}.json"))).await
+            else {
+                continue;
+            };
+            match serde_json::from_str::<InstalledVersion>(&json) {
+                Ok(installed) => match installed.instance_version() {
+                    Some((version, loader, loader_version)) => {
+                        versions.push((id, version, loader, loader_version));
+                    }
+                    None => warn!("Skipping version {id}: unsupported mod loader"),
+                },
+                Err(e) => warn!("Skipping version {id}: {e}"),
+            }
+        }
+        versions.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+        let last_used = last_used_version(minecraft_dir).await;
+
+        let mut imported = Vec::new();
+        let mut content_target = None;
+        for (id, version, loader, loader_version) in versions {
+            let Some(instance_id) = self.create_instance_with_version(&version, loader).await?
+            else {
+                warn!("Instance limit reached, not importing version {id}");
+                break;
+            };
+            // Without it the first launch would install the newest loader instead
+            if loader_version.is_some() {
+                self.set_loader_version(instance_id, loader_version).await?;
+            }
+            copy_missing_files(
+                &versions_dir.join(&id),
+                &shared_dir.join("versions").join(&id),
+            )
+            .await?;
+            if last_used.as_deref() == Some(id.as_str()) {
+                content_target = Some(instance_id);
+            }
+            imported.push(instance_id);
+        }
+
+        let Some(content_target) = content_target.or_else(|| imported.last().copied()) else {
+            return Ok(imported);
+        };
+        for name in IMPORTED_GAME_DIRECTORIES {
+            copy_missing_files(&minecraft_dir.join(name), &shared_dir.join(name)).await?;
+        }
+        let instance_dir = self.get_instance_directory(content_target);
+        for name in IMPORTED_USER_CONTENT {
+            copy_missing_files(&minecraft_dir.join(name), &instance_dir.join(name)).await?;
+        }
+
+        info!(
+            "Imported {} versions from {minecraft_dir:?}, user content went to instance {content_target}",
+            imported.len()
+        );
+        Ok(imported)
+    }
+
     /// Rename an instance
     pub async fn rename_instance(&mut self, id: u32, new_name: &str) -> Result<bool> {
         let renamed = if let Some(instance) = self.instances.get_mut(&id) {
@@ -188,26 +783,297 @@ impl InstanceService {
         Ok(renamed)
     }
 
+    /// Copy a PNG into the instance directory and use it as the instance icon.
+    pub async fn set_instance_icon(&mut self, id: u32, source: &Path) -> Result<PathBuf> {
+        if !self.instances.contains_key(&id) {
+            return Err(anyhow::anyhow!("Instance {id} not found"));
+        }
+
+        let data = async_fs::read(source).await?;
+        let icon = tokio::task::spawn_blocking(move || downscale_png(&data, MAX_ICON_SIZE))
+            .await?
+            .map_err(|e| anyhow::anyhow!("{} is not a valid PNG: {e}", source.display()))?;
+
+        let icon_path = self.get_instance_directory(id).join("icon.png");
+        if let Some(parent) = icon_path.parent() {
+            async_fs::create_dir_all(parent).await?;
+        }
+        async_fs::write(&icon_path, icon).await?;
+
+        if let Some(instance) = self.instances.get_mut(&id) {
+            instance.icon_path = Some(icon_path.clone());
+        }
+        self.save_instances().await?;
+
+        info!("Set icon of instance {id} to {icon_path:?}");
+        Ok(icon_path)
+    }
+
+    /// Move a server to the front of an instance's recent servers.
+    pub async fn record_server(&mut self, id: u32, address: &str) -> Result<bool> {
+        let Some(instance) = self.instances.get_mut(&id) else {
+            return Ok(false);
+        };
+
+        instance.recent_servers.retain(|server| server != address);
+        instance.recent_servers.insert(0, address.to_string());
+        instance.recent_servers.truncate(MAX_RECENT_SERVERS);
+
+        self.save_instances().await?;
+        Ok(true)
+    }
+
+    /// Set the game window size and fullscreen mode of an instance.
+    ///
+    /// The size is either fully set or cleared to use the game default.
+    pub async fn set_window_settings(
+        &mut self,
+        id: u32,
+        size: Option<(u32, u32)>,
+        fullscreen: bool,
+    ) -> Result<bool> {
+        if let Some((width, height)) = size {
+            validate_window_size(width, height)?;
+        }
+        let Some(instance) = self.instances.get_mut(&id) else {
+            return Ok(false);
+        };
+
+        instance.window_width = size.map(|(width, _)| width);
+        instance.window_height = size.map(|(_, height)| height);
+        instance.fullscreen = fullscreen;
+
+        self.save_instances().await?;
+        Ok(true)
+    }
+
+    /// Pin the mod loader version of an instance, or install the newest one if `None`.
+    pub async fn set_loader_version(
+        &mut self,
+        id: u32,
+        loader_version: Option<String>,
+    ) -> Result<bool> {
+        let Some(instance) = self.instances.get_mut(&id) else {
+            return Ok(false);
+        };
+
+        instance.loader_version = loader_version;
+        self.save_instances().await?;
+        Ok(true)
+    }
+
+    /// Set the JVM garbage collector preset of an instance.
+    pub async fn set_jvm_preset(&mut self, id: u32, preset: JvmPreset) -> Result<bool> {
+        // Reject custom arguments that would fail to split at launch
+        preset.arguments()?;
+        let Some(instance) = self.instances.get_mut(&id) else {
+            return Ok(false);
+        };
+
+        instance.jvm_preset = preset;
+        self.save_instances().await?;
+        Ok(true)
+    }
+
+    /// Record that an instance was just launched.
+    pub async fn record_launch(&mut self, id: u32) -> Result<bool> {
+        let Some(instance) = self.instances.get_mut(&id) else {
+            return Ok(false);
+        };
+
+        instance.last_played = Some(VisitTracker::current_timestamp());
+        self.save_instances().await?;
+        Ok(true)
+    }
+
+    /// Newest `crash-*.txt` report of an instance written at or after `since`, in seconds since the Unix epoch.
+    pub async fn latest_crash_report(
+        &self,
+        instance_id: u32,
+        since: u64,
+    ) -> Result<Option<PathBuf>> {
+        let reports_dir = self
+            .get_instance_directory(instance_id)
+            .join("crash-reports");
+        if !reports_dir.exists() {
+            return Ok(None);
+        }
+
+        let mut latest: Option<(u64, PathBuf)> = None;
+        let mut entries = async_fs::read_dir(&reports_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let is_report = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with("crash-") && name.ends_with(".txt"));
+            let metadata = entry.metadata().await?;
+            if !is_report || !metadata.is_file() {
+                continue;
+            }
+
+            let modified = metadata
+                .modified()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            if modified >= since && latest.as_ref().is_none_or(|(newest, _)| modified > *newest) {
+                latest = Some((modified, entry.path()));
+            }
+        }
+
+        Ok(latest.map(|(_, path)| path))
+    }
+
+    /// List screenshots of an instance, newest first.
+    pub async fn list_screenshots(&self, instance_id: u32) -> Result<Vec<Screenshot>> {
+        let screenshots_dir = self.get_instance_directory(instance_id).join("screenshots");
+        if !screenshots_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut screenshots = Vec::new();
+        let mut entries = async_fs::read_dir(&screenshots_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let is_png = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+            let metadata = entry.metadata().await?;
+            if !is_png || !metadata.is_file() {
+                continue;
+            }
+
+            let modified = metadata
+                .modified()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            screenshots.push(Screenshot { path, modified });
+        }
+
+        screenshots.sort_by_key(|screenshot| std::cmp::Reverse(screenshot.modified));
+        Ok(screenshots)
+    }
+
+    /// List the mods of an instance by name, including disabled ones.
+    pub async fn list_mods(&self, instance_id: u32) -> Result<Vec<ModInfo>> {
+        let mods_dir = self.get_instance_directory(instance_id).join("mods");
+        if !mods_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths = Vec::new();
+        let mut entries = async_fs::read_dir(&mods_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let is_mod = entry.file_name().to_str().is_some_and(is_mod_file);
+            if is_mod && entry.metadata().await?.is_file() {
+                paths.push(entry.path());
+            }
+        }
+
+        let mut mods = tokio::task::spawn_blocking(move || {
+            paths
+                .iter()
+                .map(|path| read_mod_info(path))
+                .collect::<Result<Vec<_>>>()
+        })
+        .await??;
+        mods.sort_by_key(|info| info.name.to_lowercase());
+        Ok(mods)
+    }
+
+    /// Enable or disable a mod by adding or removing the `.disabled` suffix of its jar.
+    ///
+    /// Returns the new file name of the mod.
+    pub async fn set_mod_enabled(
+        &self,
+        instance_id: u32,
+        file_name: &str,
+        enabled: bool,
+    ) -> Result<String> {
+        if file_name.contains(['/', '\\']) || !is_mod_file(file_name) {
+            return Err(anyhow::anyhow!("Invalid mod name: {file_name}"));
+        }
+
+        let base_name = file_name.strip_suffix(DISABLED_SUFFIX).unwrap_or(file_name);
+        let new_name = if enabled {
+            base_name.to_string()
+        } else {
+            format!("{base_name}{DISABLED_SUFFIX}")
+        };
+        if new_name == file_name {
+            return Ok(new_name);
+        }
+
+        let mods_dir = self.get_instance_directory(instance_id).join("mods");
+        let target = mods_dir.join(&new_name);
+        if target.exists() {
+            return Err(anyhow::anyhow!("{new_name} already exists"));
+        }
+        async_fs::rename(mods_dir.join(file_name), &target).await?;
+
+        info!("Renamed mod {file_name} to {new_name}");
+        Ok(new_name)
+    }
+
+    /// List the singleplayer worlds of an instance by folder name.
+    pub async fn list_worlds(&self, instance_id: u32) -> Result<Vec<String>> {
+        let saves_dir = self.get_instance_directory(instance_id).join("saves");
+        if !saves_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut worlds = Vec::new();
+        let mut entries = async_fs::read_dir(&saves_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            // Only folders with level data are playable worlds
+            if !entry.path().join("level.dat").is_file() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str() {
+                worlds.push(name.to_string());
+            }
+        }
+
+        worlds.sort_by_key(|name| name.to_lowercase());
+        Ok(worlds)
+    }
+
+    /// List the multiplayer servers saved in the game of an instance.
+    pub async fn list_servers(&self, instance_id: u32) -> Result<Vec<SavedServer>> {
+        let path = self.get_instance_directory(instance_id).join("servers.dat");
+        read_servers_dat(&path).await
+    }
+
+    /// Delete a screenshot of an instance by file name.
+    pub async fn delete_screenshot(&self, instance_id: u32, file_name: &str) -> Result<()> {
+        if file_name.contains(['/', '\\']) || file_name == ".." {
+            return Err(anyhow::anyhow!("Invalid screenshot name: {file_name}"));
+        }
+
+        let path = self
+            .get_instance_directory(instance_id)
+            .join("screenshots")
+            .join(file_name);
+        async_fs::remove_file(&path).await?;
+
+        info!("Deleted screenshot {path:?}");
+        Ok(())
+    }
+
     /// Get the directory for a specific instance.
     pub fn get_instance_directory(&self, instance_id: u32) -> PathBuf {
         if let Some(instance) = self.instances.get(&instance_id) {
             let folder_name = self
                 .generate_folder_name_for_version(&instance.version, instance_id)
                 .unwrap_or_else(|| format!("instance_{instance_id}"));
-            get_launcher_dir()
-                .unwrap_or_else(|_| PathBuf::from("Dream Launcher"))
-                .join(format!("instances/{folder_name}"))
+            self.launcher_dir.join(format!("instances/{folder_name}"))
         } else {
-            get_launcher_dir()
-                .unwrap_or_else(|_| PathBuf::from("Dream Launcher"))
+            self.launcher_dir
                 .join(format!("instances/instance_{instance_id}"))
         }
     }
 
     /// Open instance folder in system file explorer.
     pub async fn open_instance_folder(&self, instance_id: u32) -> Result<()> {
-        use std::process::Command;
-
         let instance_dir = self.get_instance_directory(instance_id);
 
         // Ensure directory exists
@@ -215,17 +1081,50 @@ impl InstanceService {
             self.create_instance_directories(instance_id)?;
         }
 
-        // Open in Finder on macOS
-        let output = Command::new("open").arg(&instance_dir).output()?;
-
-        if !output.status.success() {
-            return Err(anyhow::anyhow!("Failed to open instance folder"));
-        }
+        open_path(&instance_dir)?;
 
         info!("Opened instance {instance_id} folder: {instance_dir:?}");
         Ok(())
     }
 
+    /// Open the game logs folder of an instance in the system file explorer.
+    pub async fn open_logs_folder(&self, instance_id: u32) -> Result<()> {
+        let logs_dir = self.get_instance_directory(instance_id).join("logs");
+        async_fs::create_dir_all(&logs_dir).await?;
+        open_path(&logs_dir)?;
+
+        info!("Opened logs folder of instance {instance_id}: {logs_dir:?}");
+        Ok(())
+    }
+
+    /// Path of the `logs/latest.log` the game of an instance writes to.
+    #[must_use]
+    pub fn latest_log_path(&self, instance_id: u32) -> PathBuf {
+        self.get_instance_directory(instance_id)
+            .join("logs")
+            .join("latest.log")
+    }
+
+    /// Save captured debug console output into the instance's logs folder and reveal it.
+    pub async fn export_debug_log(&self, instance_id: u32, contents: &str) -> Result<PathBuf> {
+        if !self.instances.contains_key(&instance_id) {
+            return Err(anyhow::anyhow!("Instance {instance_id} not found"));
+        }
+
+        let logs_dir = self.get_instance_directory(instance_id).join("logs");
+        async_fs::create_dir_all(&logs_dir).await?;
+        let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+        let path = logs_dir.join(format!("launcher-export-{timestamp}.log"));
+        async_fs::write(&path, contents).await?;
+
+        if let Err(e) = open_path(&logs_dir) {
+            warn!("Failed to open logs folder of instance {instance_id}: {e:#}");
+        }
+
+        info!("Saved debug log of instance {instance_id} to {path:?}");
+        Ok(path)
+    }
+
     /// Generate folder name for a version.
     fn generate_folder_name_for_version(&self, version: &str, instance_id: u32) -> Option<String> {
         let mut count = 1;
@@ -245,6 +1144,27 @@ impl InstanceService {
         }
     }
 
+    /// Smallest id that is free for a new instance of `version`.
+    ///
+    /// Ids of deleted instances are reused so ids and colors stay small and
+    /// predictable. An id below another instance of the same version is
+    /// skipped, since its folder name would be the one that instance uses.
+    fn free_id(&self, version: &str) -> Result<u32> {
+        let highest_of_version = self
+            .instances
+            .iter()
+            .filter(|(_, instance)| instance.version == version)
+            .map(|(&id, _)| id)
+            .max();
+        let start = match highest_of_version {
+            Some(id) => id.checked_add(1),
+            None => Some(1),
+        };
+        start
+            .and_then(|start| (start..=u32::MAX).find(|id| !self.instances.contains_key(id)))
+            .ok_or_else(|| anyhow::anyhow!("No free instance id left for version {version}"))
+    }
+
     /// Create all necessary directories for an instance.
     fn create_instance_directories(&self, instance_id: u32) -> std::io::Result<PathBuf> {
         let instance_dir = self.get_instance_directory(instance_id);
@@ -253,7 +1173,7 @@ impl InstanceService {
     }
 
     /// Create directories with a specific path.
-    fn create_instance_directories_with_path(&self, instance_dir: &PathBuf) -> std::io::Result<()> {
+    fn create_instance_directories_with_path(&self, instance_dir: &Path) -> std::io::Result<()> {
         // Create the main instance directory
         fs::create_dir_all(instance_dir)?;
 
@@ -271,34 +1191,667 @@ impl InstanceService {
     }
 
     /// Get the path to the instances configuration file.
-    fn get_instances_config_path(&self) -> PathBuf {
-        get_cache_dir()
-            .unwrap_or_else(|_| PathBuf::from("Dream Launcher/cache"))
-            .join("launcher.json")
+    pub fn get_instances_config_path(&self) -> PathBuf {
+        self.launcher_dir.join("cache").join("launcher.json")
     }
 
     /// Save instances data to disk.
+    ///
+    /// Saves go through a temporary file that replaces the old one,
+    /// so an interrupted write never leaves a truncated file behind.
+    /// An empty list never replaces saved instances; only deleting the
+    /// last instance may do that.
     async fn save_instances(&self) -> Result<()> {
+        self.write_instances(false).await
+    }
+
+    async fn write_instances(&self, allow_empty: bool) -> Result<()> {
         let config_path = self.get_instances_config_path();
         let data = InstancesData {
             instances: self.instances.clone(),
             next_id: self.next_id,
         };
+        let json = serde_json::to_string_pretty(&data)?;
 
         // Ensure parent directory exists
         if let Some(parent) = config_path.parent() {
             async_fs::create_dir_all(parent).await?;
         }
 
-        let json = serde_json::to_string_pretty(&data)?;
-        async_fs::write(config_path, json).await?;
+        // Writers share the temporary file, so only one may use it at a time
+        let _guard = SAVE_LOCK.lock().await;
+        if data.instances.is_empty() && !allow_empty && has_saved_instances(&config_path).await {
+            return Err(anyhow::anyhow!(
+                "Refusing to replace the saved instances with an empty list"
+            ));
+        }
+
+        let temp_path = config_path.with_extension("json.tmp");
+        async_fs::write(&temp_path, json).await?;
+        async_fs::rename(&temp_path, &config_path).await?;
 
         Ok(())
     }
 }
 
+/// Whether an instances file lists at least one instance.
+async fn has_saved_instances(config_path: &Path) -> bool {
+    async_fs::read_to_string(config_path)
+        .await
+        .ok()
+        .and_then(|json| serde_json::from_str::<InstancesData>(&json).ok())
+        .is_some_and(|data| !data.instances.is_empty())
+}
+
 impl Default for InstanceService {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// Fields of an installed version JSON used to import it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledVersion {
+    id: String,
+    inherits_from: Option<String>,
+    #[serde(default)]
+    libraries: Vec<InstalledLibrary>,
+}
+
+/// Library of an installed version, named `group:artifact:version`.
+#[derive(Debug, Deserialize)]
+struct InstalledLibrary {
+    name: String,
+}
+
+/// Minecraft version, mod loader and loader version of an imported version.
+type ImportedVersion = (String, ModLoader, Option<String>);
+
+impl InstalledVersion {
+    /// Minecraft version, mod loader and loader version of an instance running this version.
+    fn instance_version(&self) -> Option<ImportedVersion> {
+        let Some(base) = &self.inherits_from else {
+            return Some((self.id.clone(), ModLoader::Vanilla, None));
+        };
+        let id = self.id.to_lowercase();
+        let loader = if id.contains("neoforge") {
+            ModLoader::NeoForge
+        } else if id.contains("forge") {
+            ModLoader::Forge
+        } else if id.contains("fabric") {
+            ModLoader::Fabric
+        } else if id.contains("quilt") {
+            ModLoader::Quilt
+        } else {
+            return None;
+        };
+        Some((base.clone(), loader, self.loader_version(loader, base)))
+    }
+
+    /// Version of the mod loader, from its library or else from the version id
+    /// like `fabric-loader-0.15.11-1.20.1` or `1.20.1-forge-47.2.0`.
+    fn loader_version(&self, loader: ModLoader, base: &str) -> Option<String> {
+        let (library, marker) = match loader {
+            ModLoader::Vanilla => return None,
+            ModLoader::Fabric => ("net.fabricmc:fabric-loader:", "fabric-loader-"),
+            ModLoader::Quilt => ("org.quiltmc:quilt-loader:", "quilt-loader-"),
+            ModLoader::Forge => ("net.minecraftforge:forge:", "forge"),
+            ModLoader::NeoForge => ("net.neoforged:neoforge:", "neoforge-"),
+        };
+        let version = self
+            .libraries
+            .iter()
+            .find_map(|lib| lib.name.strip_prefix(library))
+            .and_then(|version| version.split(':').next())
+            .or_else(|| {
+                let start = self.id.to_lowercase().find(marker)? + marker.len();
+                Some(self.id[start..].trim_start_matches('-'))
+            })?;
+
+        // Forge versions carry the Minecraft version around the loader version
+        let version = version.strip_prefix(&format!("{base}-")).unwrap_or(version);
+        let version = version.strip_suffix(&format!("-{base}")).unwrap_or(version);
+        (!version.is_empty()).then(|| version.to_string())
+    }
+}
+
+/// Version the vanilla launcher used most recently, from its profiles.
+async fn last_used_version(minecraft_dir: &Path) -> Option<String> {
+    let json = async_fs::read_to_string(minecraft_dir.join("launcher_profiles.json"))
+        .await
+        .ok()?;
+    let profiles: serde_json::Value = serde_json::from_str(&json).ok()?;
+    profiles
+        .get("profiles")?
+        .as_object()?
+        .values()
+        .filter_map(|profile| {
+            // Profiles following the latest release name no installed version
+            let version = profile.get("lastVersionId")?.as_str()?;
+            let last_used = profile.get("lastUsed")?.as_str()?;
+            Some((last_used, version))
+        })
+        .max()
+        .map(|(_, version)| version.to_string())
+}
+
+/// Copy a file or directory, keeping files that already exist at the target.
+async fn copy_missing_files(source: &Path, target: &Path) -> Result<()> {
+    if source.is_file() {
+        if !target.exists() {
+            if let Some(parent) = target.parent() {
+                async_fs::create_dir_all(parent).await?;
+            }
+            async_fs::copy(source, target).await?;
+        }
+        return Ok(());
+    }
+
+    for file in collect_files(source).await? {
+        let destination = target.join(file.strip_prefix(source)?);
+        if destination.exists() {
+            continue;
+        }
+        if let Some(parent) = destination.parent() {
+            async_fs::create_dir_all(parent).await?;
+        }
+        async_fs::copy(&file, &destination).await?;
+    }
+    Ok(())
+}
+
+/// Copy the user content and icon of an instance into `target_dir`.
+///
+/// Returns the number of copied files and whether the icon was copied.
+async fn copy_instance_files<F>(
+    source: &Instance,
+    source_dir: &Path,
+    target_dir: &Path,
+    on_progress: F,
+) -> Result<(usize, bool)>
+where
+    F: Fn(usize, usize) + Send,
+{
+    async_fs::create_dir_all(target_dir).await?;
+
+    // Collect files up front so progress can be reported against a known total
+    let mut files = Vec::new();
+    for name in CLONED_DIRECTORIES {
+        files.extend(collect_files(&source_dir.join(name)).await?);
+    }
+
+    // The icon lives in the instance directory, so the clone needs its own copy
+    let has_icon = match &source.icon_path {
+        Some(icon) if icon.exists() => {
+            async_fs::copy(icon, target_dir.join("icon.png")).await?;
+            true
+        }
+        _ => false,
+    };
+
+    let total = files.len();
+    for (index, file) in files.iter().enumerate() {
+        let relative = file.strip_prefix(source_dir)?;
+        let destination = target_dir.join(relative);
+        if let Some(parent) = destination.parent() {
+            async_fs::create_dir_all(parent).await?;
+        }
+        async_fs::copy(file, &destination).await?;
+        on_progress(index + 1, total);
+    }
+
+    Ok((total, has_icon))
+}
+
+/// An instance being cloned, created by [`InstanceService::start_clone`].
+pub struct PendingClone {
+    source: Instance,
+    source_dir: PathBuf,
+    staging_dir: PathBuf,
+    sync_id: String,
+    copied_files: usize,
+    has_icon: bool,
+}
+
+impl PendingClone {
+    /// Copy the user content of the source instance, reporting copied files through `on_progress`.
+    pub async fn copy_files<F>(&mut self, on_progress: F) -> Result<()>
+    where
+        F: Fn(usize, usize) + Send,
+    {
+        let copied = copy_instance_files(
+            &self.source,
+            &self.source_dir,
+            &self.staging_dir,
+            on_progress,
+        )
+        .await?;
+        (self.copied_files, self.has_icon) = copied;
+        Ok(())
+    }
+
+    /// Remove the files copied so far.
+    pub async fn discard(self) {
+        remove_partial_directory(&self.staging_dir).await;
+    }
+}
+
+/// Remove a directory left by a failed operation, logging rather than failing.
+async fn remove_partial_directory(dir: &Path) {
+    if dir.exists()
+        && let Err(e) = async_fs::remove_dir_all(dir).await
+    {
+        warn!("Failed to remove partial directory {dir:?}: {e}");
+    }
+}
+
+/// Extract an exported instance archive, skipping the manifest.
+fn extract_export_archive(archive_path: &Path, instance_dir: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.name() == EXPORT_MANIFEST {
+            continue;
+        }
+        let Some(relative_path) = entry.enclosed_name() else {
+            warn!("Skipping unsafe archive entry: {}", entry.name());
+            continue;
+        };
+
+        let target = instance_dir.join(relative_path);
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut fs::File::create(&target)?)?;
+        apply_unix_mode(&target, entry.unix_mode())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_changes_are_all_saved() {
+        let launcher_dir =
+            std::env::temp_dir().join(format!("dream-launcher-instances-{}", std::process::id()));
+        let mut service = InstanceService::with_launcher_dir(launcher_dir.clone());
+        service.load_instances().await.unwrap();
+        let service = Arc::new(Mutex::new(service));
+
+        let mut tasks = Vec::new();
+        for i in 0..8 {
+            let service = service.clone();
+            tasks.push(tokio::spawn(async move {
+                let id = service
+                    .lock()
+                    .await
+                    .create_instance_with_version(&format!("1.20.{i}"), ModLoader::Vanilla)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                tokio::task::yield_now().await;
+                let renamed = service
+                    .lock()
+                    .await
+                    .rename_instance(id, &format!("Test{i}"))
+                    .await
+                    .unwrap();
+                assert!(renamed);
+            }));
+        }
+        let default_instance = service.clone();
+        tasks.push(tokio::spawn(async move {
+            let deleted = default_instance
+                .lock()
+                .await
+                .delete_instance(1)
+                .await
+                .unwrap();
+            assert!(deleted);
+        }));
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let mut reloaded = InstanceService::with_launcher_dir(launcher_dir.clone());
+        reloaded.load_instances().await.unwrap();
+        let summary = |service: &InstanceService| {
+            service
+                .get_instances_sorted()
+                .into_iter()
+                .map(|instance| (instance.id, instance.name, instance.version))
+                .collect::<Vec<_>>()
+        };
+        let saved = summary(&reloaded);
+        assert_eq!(saved, summary(&*service.lock().await));
+        assert_eq!(saved.len(), 8);
+        for i in 0..8 {
+            assert!(saved.iter().any(|(_, name, version)| {
+                *name == format!("Test{i}") && *version == format!("1.20.{i}")
+            }));
+        }
+
+        std::fs::remove_dir_all(&launcher_dir).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_saves_leave_a_valid_file() {
+        let launcher_dir =
+            std::env::temp_dir().join(format!("dream-launcher-saves-{}", std::process::id()));
+        let mut service = InstanceService::with_launcher_dir(launcher_dir.clone());
+        service.load_instances().await.unwrap();
+
+        // Services with different instances all writing the same file at once
+        let mut writers = Vec::new();
+        for i in 0..16 {
+            let mut writer = service.clone();
+            writer
+                .instances
+                .insert(2, Instance::new_with_version(2, format!("1.20.{i}")));
+            writers.push(tokio::spawn(async move {
+                writer.save_instances().await.unwrap();
+                writer
+            }));
+        }
+        let mut versions = Vec::new();
+        for writer in writers {
+            versions.push(writer.await.unwrap().instances[&2].version.clone());
+        }
+
+        service.load_instances().await.unwrap();
+        assert_eq!(service.instances.len(), 2);
+        assert!(versions.contains(&service.instances[&2].version));
+        assert!(
+            !service
+                .get_instances_config_path()
+                .with_extension("json.tmp")
+                .exists()
+        );
+
+        std::fs::remove_dir_all(&launcher_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn empty_list_only_replaces_saved_instances_after_a_delete() {
+        let launcher_dir =
+            std::env::temp_dir().join(format!("dream-launcher-empty-{}", std::process::id()));
+        let mut service = InstanceService::with_launcher_dir(launcher_dir.clone());
+        service.load_instances().await.unwrap();
+
+        let unloaded = InstanceService::with_launcher_dir(launcher_dir.clone());
+        assert!(unloaded.save_instances().await.is_err());
+        let mut reloaded = InstanceService::with_launcher_dir(launcher_dir.clone());
+        reloaded.load_instances().await.unwrap();
+        assert_eq!(reloaded.instances.len(), 1);
+
+        assert!(service.delete_instance(1).await.unwrap());
+        reloaded.load_instances().await.unwrap();
+        assert!(reloaded.instances.is_empty());
+
+        std::fs::remove_dir_all(&launcher_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn exported_instance_imports_as_a_copy() {
+        let launcher_dir =
+            std::env::temp_dir().join(format!("dream-launcher-export-{}", std::process::id()));
+        let mut service = InstanceService::with_launcher_dir(launcher_dir.clone());
+        service.load_instances().await.unwrap();
+
+        let id = service
+            .create_instance_with_version("1.20.1", ModLoader::Fabric)
+            .await
+            .unwrap()
+            .unwrap();
+        service.rename_instance(id, "Modded").await.unwrap();
+        service
+            .set_loader_version(id, Some("0.15.11".to_string()))
+            .await
+            .unwrap();
+        let instance_dir = service.get_instance_directory(id);
+        for (file, contents) in [
+            ("mods/sodium.jar", "mod"),
+            ("config/sodium.json", "{}"),
+            ("saves/World/level.dat", "world"),
+            ("versions/1.20.1/1.20.1.jar", "jar"),
+        ] {
+            let path = instance_dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        let archive = launcher_dir.join("export.zip");
+        service
+            .export_instance(id, archive.clone(), true)
+            .await
+            .unwrap();
+        let imported = service.import_instance(&archive).await.unwrap().unwrap();
+
+        assert_ne!(imported, id);
+        let original = service.get_instance(id).unwrap();
+        let copy = service.get_instance(imported).unwrap();
+        assert_eq!(copy.name, original.name);
+        assert_eq!(copy.version, original.version);
+        assert_eq!(copy.loader, original.loader);
+        assert_eq!(copy.loader_version, original.loader_version);
+
+        let copy_dir = service.get_instance_directory(imported);
+        let read = |file: &str| std::fs::read_to_string(copy_dir.join(file)).unwrap();
+        assert_eq!(read("mods/sodium.jar"), "mod");
+        assert_eq!(read("config/sodium.json"), "{}");
+        assert_eq!(read("saves/World/level.dat"), "world");
+        assert!(!copy_dir.join("versions/1.20.1/1.20.1.jar").exists());
+
+        std::fs::remove_dir_all(&launcher_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn imports_installed_versions_with_their_loader() {
+        let root =
+            std::env::temp_dir().join(format!("dream-launcher-import-{}", std::process::id()));
+        let minecraft_dir = root.join(".minecraft");
+        let fabric = "fabric-loader-0.15.11-1.20.1";
+        for (id, json) in [
+            ("1.20.1", r#"{"id": "1.20.1"}"#.to_string()),
+            (
+                fabric,
+                format!(
+                    r#"{{"id": "{fabric}", "inheritsFrom": "1.20.1",
+                    "libraries": [{{"name": "net.fabricmc:fabric-loader:0.15.11"}}]}}"#
+                ),
+            ),
+        ] {
+            let version_dir = minecraft_dir.join("versions").join(id);
+            std::fs::create_dir_all(&version_dir).unwrap();
+            std::fs::write(version_dir.join(format!("{id}.json")), json).unwrap();
+        }
+
+        let launcher_dir = root.join("launcher");
+        let mut service = InstanceService::with_launcher_dir(launcher_dir.clone());
+        service.load_instances().await.unwrap();
+        let imported = service
+            .import_existing_installation(&minecraft_dir)
+            .await
+            .unwrap();
+
+        let loaders: Vec<_> = imported
+            .iter()
+            .map(|&id| {
+                let instance = service.get_instance(id).unwrap();
+                (instance.loader, instance.loader_version.clone())
+            })
+            .collect();
+        assert_eq!(
+            loaders,
+            [
+                (ModLoader::Vanilla, None),
+                (ModLoader::Fabric, Some("0.15.11".to_string()))
+            ]
+        );
+        let shared_versions = launcher_dir.join("minecraft").join("versions");
+        assert!(
+            shared_versions
+                .join(fabric)
+                .join(format!("{fabric}.json"))
+                .is_file()
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn reads_loader_versions_from_version_ids() {
+        let loader_version = |id: &str, loader| {
+            let installed = InstalledVersion {
+                id: id.to_string(),
+                inherits_from: Some("1.20.1".to_string()),
+                libraries: Vec::new(),
+            };
+            installed.loader_version(loader, "1.20.1")
+        };
+        assert_eq!(
+            loader_version("fabric-loader-0.15.11-1.20.1", ModLoader::Fabric).as_deref(),
+            Some("0.15.11")
+        );
+        assert_eq!(
+            loader_version("quilt-loader-0.26.0-1.20.1", ModLoader::Quilt).as_deref(),
+            Some("0.26.0")
+        );
+        assert_eq!(
+            loader_version("1.20.1-forge-47.2.0", ModLoader::Forge).as_deref(),
+            Some("47.2.0")
+        );
+        assert_eq!(
+            loader_version("neoforge-20.4.80-beta", ModLoader::NeoForge).as_deref(),
+            Some("20.4.80-beta")
+        );
+        assert_eq!(loader_version("my-forge", ModLoader::Forge), None);
+    }
+
+    #[test]
+    fn prefers_the_loader_library_version() {
+        let installed: InstalledVersion = serde_json::from_str(
+            r#"{"id": "Forge 1.20.1", "inheritsFrom": "1.20.1",
+            "libraries": [{"name": "net.minecraftforge:forge:1.20.1-47.2.0:client"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            installed.instance_version(),
+            Some((
+                "1.20.1".to_string(),
+                ModLoader::Forge,
+                Some("47.2.0".to_string())
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn clone_copies_files_while_the_service_is_free() {
+        let launcher_dir =
+            std::env::temp_dir().join(format!("dream-launcher-clone-{}", std::process::id()));
+        let service = Arc::new(Mutex::new(InstanceService::with_launcher_dir(
+            launcher_dir.clone(),
+        )));
+        service.lock().await.load_instances().await.unwrap();
+        let world = service
+            .lock()
+            .await
+            .get_instance_directory(1)
+            .join("saves/World/level.dat");
+        std::fs::create_dir_all(world.parent().unwrap()).unwrap();
+        std::fs::write(&world, "world").unwrap();
+
+        let mut clone = service.lock().await.start_clone(1).unwrap();
+        clone
+            .copy_files(|_, _| {
+                // Other changes go through while the files are copied
+                assert!(service.try_lock().is_ok());
+            })
+            .await
+            .unwrap();
+        let new_id = service
+            .lock()
+            .await
+            .finish_clone(clone)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let service = service.lock().await;
+        assert_ne!(new_id, 1);
+        let copy = service
+            .get_instance_directory(new_id)
+            .join("saves/World/level.dat");
+        assert_eq!(std::fs::read_to_string(copy).unwrap(), "world");
+        assert_ne!(
+            service.get_instance(new_id).unwrap().sync_key(),
+            service.get_instance(1).unwrap().sync_key()
+        );
+
+        std::fs::remove_dir_all(&launcher_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reused_id_gets_its_own_sync_key() {
+        let launcher_dir =
+            std::env::temp_dir().join(format!("dream-launcher-sync-key-{}", std::process::id()));
+        let mut service = InstanceService::with_launcher_dir(launcher_dir.clone());
+        service.load_instances().await.unwrap();
+
+        let first = service
+            .create_instance_with_version("1.20.1", ModLoader::Vanilla)
+            .await
+            .unwrap()
+            .unwrap();
+        let first_key = service.get_instance(first).unwrap().sync_key();
+        assert!(service.delete_instance(first).await.unwrap());
+
+        let second = service
+            .create_instance_with_version("1.20.1", ModLoader::Vanilla)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second, first);
+        assert_ne!(service.get_instance(second).unwrap().sync_key(), first_key);
+
+        std::fs::remove_dir_all(&launcher_dir).unwrap();
+    }
+
+    #[test]
+    fn free_ids_follow_instances_of_the_same_version() {
+        let mut service = InstanceService::new();
+        for (id, version) in [(1, "1.20.1"), (3, "1.21.8")] {
+            service
+                .instances
+                .insert(id, Instance::new_with_version(id, version.to_string()));
+        }
+        assert_eq!(service.free_id("1.20.1").unwrap(), 2);
+        assert_eq!(service.free_id("1.21.8").unwrap(), 4);
+        assert_eq!(service.free_id("1.19.4").unwrap(), 2);
+
+        // Every id after the highest instance of a version is used up
+        service.instances.insert(
+            u32::MAX,
+            Instance::new_with_version(u32::MAX, "1.21.8".to_string()),
+        );
+        assert!(service.free_id("1.21.8").is_err());
+        assert_eq!(service.free_id("1.20.1").unwrap(), 2);
+    }
+
+    #[test]
+    fn instances_without_sync_id_keep_their_id_as_key() {
+        let json =
+            r#"{"id": 3, "name": "Old", "color": "38FF10", "level": 28, "version": "1.20.1"}"#;
+        let instance: Instance = serde_json::from_str(json).unwrap();
+        assert_eq!(instance.sync_key(), "3");
+    }
+}
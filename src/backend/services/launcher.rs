@@ -1,14 +1,135 @@
 //! Launcher service for managing Minecraft installation and launch.
 
-use crate::backend::archon::Archon;
-use crate::backend::python::python::{LaunchConfig, MinecraftLogMessage};
-use crate::backend::services::instance::{Instance, InstanceService};
-use crate::backend::utils::paths::get_launcher_dir;
+use crate::backend::archon::{Archon, PythonResponse};
+use crate::backend::python::python::{GameEvent, LaunchConfig, MinecraftLogMessage};
+use crate::backend::services::instance::{Instance, InstanceService, MAX_NAME_LENGTH, ModLoader};
+use crate::backend::services::modpack::{
+    ModpackImport, MrpackIndex, download_modpack, install_mrpack,
+};
+use crate::backend::services::settings::{JvmPreset, LauncherSettings};
+use crate::backend::utils::error::{ErrorKind, TaggedError};
+use crate::backend::utils::http;
+use crate::backend::utils::java::{JavaVersionMismatch, java_major_version};
+use crate::backend::utils::paths::{get_cache_dir, get_launcher_dir};
 use anyhow::Result;
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::{Mutex, mpsc};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex, OwnedMutexGuard, mpsc, watch};
+
+const VERSION_MANIFEST_URL: &str =
+    "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+/// How long a cached version manifest is used before it is revalidated.
+const MANIFEST_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// One lock per installed Minecraft version, so two instances of the same version
+/// don't download into the shared directories at the same time.
+///
+/// Mod loaders install on top of the vanilla jar, libraries and assets, so
+/// they share the lock of their Minecraft version.
+static INSTALL_LOCKS: LazyLock<std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>> =
+    LazyLock::new(Default::default);
+
+/// Held while a version is installed, removing its lock once nobody else waits for it.
+struct InstallGuard {
+    version: String,
+    guard: OwnedMutexGuard<()>,
+}
+
+impl Drop for InstallGuard {
+    fn drop(&mut self) {
+        let mut locks = INSTALL_LOCKS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Waiters clone the lock out of the map, so only the map and this guard own it otherwise
+        if Arc::strong_count(OwnedMutexGuard::mutex(&self.guard)) == 2 {
+            locks.remove(&self.version);
+        }
+    }
+}
+
+/// Wait until no other install of a Minecraft version is running, and hold it off until
+/// the returned guard is dropped.
+async fn lock_install(version: &str) -> InstallGuard {
+    let lock = INSTALL_LOCKS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .entry(version.to_string())
+        .or_default()
+        .clone();
+    InstallGuard {
+        version: version.to_string(),
+        guard: lock.lock_owned().await,
+    }
+}
+
+/// Cancel switches of the installs in progress, by instance.
+static INSTALL_CANCELS: LazyLock<std::sync::Mutex<HashMap<u32, watch::Sender<bool>>>> =
+    LazyLock::new(Default::default);
+
+/// An install that can be cancelled, registered until it is dropped.
+struct CancellableInstall {
+    instance_id: u32,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl CancellableInstall {
+    fn register(instance_id: u32) -> Self {
+        let (tx, cancelled) = watch::channel(false);
+        INSTALL_CANCELS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(instance_id, tx);
+        Self {
+            instance_id,
+            cancelled,
+        }
+    }
+
+    /// Wait until the install is cancelled.
+    async fn cancelled(&mut self) {
+        if self
+            .cancelled
+            .wait_for(|cancelled| *cancelled)
+            .await
+            .is_err()
+        {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+impl Drop for CancellableInstall {
+    fn drop(&mut self) {
+        INSTALL_CANCELS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&self.instance_id);
+    }
+}
+
+/// The user cancelled the install before the game was launched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallCancelled {
+    pub version: String,
+}
+
+impl fmt::Display for InstallCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Installation of Minecraft {} was cancelled",
+            self.version
+        )
+    }
+}
+
+impl std::error::Error for InstallCancelled {}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionManifest {
@@ -36,6 +157,22 @@ pub struct VersionInfo {
     pub compliance_level: u32,
 }
 
+/// Version manifest stored on disk with the data needed to revalidate it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedManifest {
+    /// Seconds since the Unix epoch when the manifest was last fetched or revalidated.
+    fetched_at: u64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    manifest: VersionManifest,
+}
+
+impl CachedManifest {
+    fn is_fresh(&self) -> bool {
+        now_secs().saturating_sub(self.fetched_at) < MANIFEST_TTL.as_secs()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LaunchResult {
     pub success: bool,
@@ -59,20 +196,9 @@ impl LauncherService {
         })
     }
 
-    /// Fetch the Minecraft version manifest.
+    /// Fetch the Minecraft version manifest, bypassing the cache TTL.
     pub async fn fetch_version_manifest(&self) -> Result<VersionManifest> {
-        let url = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
-        let response = reqwest::get(url).await?;
-        let manifest: VersionManifest = response.json().await?;
-
-        // Cache the manifest
-        {
-            let mut cached_manifest = self.version_manifest.lock().await;
-            *cached_manifest = Some(manifest.clone());
-        }
-
-        info!("Fetched {} versions from manifest", manifest.versions.len());
-        Ok(manifest)
+        self.update_manifest(true).await
     }
 
     /// Get cached version manifest or fetch if not available.
@@ -84,8 +210,96 @@ impl LauncherService {
             }
         }
 
-        // Fetch if not cached
-        self.fetch_version_manifest().await
+        self.update_manifest(false).await
+    }
+
+    /// Refresh the version manifest if the cache is stale, or always when `force` is set.
+    ///
+    /// Stale manifests are revalidated with a conditional request, so an
+    /// unchanged manifest only costs a 304 response. If the network is
+    /// unavailable the stale cache is used instead.
+    pub async fn update_manifest(&self, force: bool) -> Result<VersionManifest> {
+        let cached = load_cached_manifest().await;
+        if LauncherSettings::load().await.offline_mode {
+            let cached = cached.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Version manifest is not cached, turn off offline mode to download it"
+                )
+            })?;
+            info!("Offline mode, using cached version manifest");
+            return self.set_manifest(cached.manifest).await;
+        }
+        if !force && let Some(cached) = cached.as_ref().filter(|cached| cached.is_fresh()) {
+            return self.set_manifest(cached.manifest.clone()).await;
+        }
+
+        let client = http::client()?;
+        let mut request = client.get(VERSION_MANIFEST_URL);
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response,
+            Err(e) => {
+                let Some(cached) = cached else {
+                    return Err(e.into());
+                };
+                warn!("Failed to refresh version manifest, using cached copy: {e}");
+                return self.set_manifest(cached.manifest).await;
+            }
+        };
+
+        let header = |name: reqwest::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+        let cached = match cached {
+            Some(mut cached) if response.status() == reqwest::StatusCode::NOT_MODIFIED => {
+                info!("Version manifest not modified");
+                cached.fetched_at = now_secs();
+                cached.etag = etag.or(cached.etag);
+                cached.last_modified = last_modified.or(cached.last_modified);
+                cached
+            }
+            _ => {
+                let manifest: VersionManifest = response.json().await?;
+                info!("Fetched {} versions from manifest", manifest.versions.len());
+                CachedManifest {
+                    fetched_at: now_secs(),
+                    etag,
+                    last_modified,
+                    manifest,
+                }
+            }
+        };
+
+        if let Err(e) = save_cached_manifest(&cached).await {
+            warn!("Failed to save version manifest cache: {e}");
+        }
+        self.set_manifest(cached.manifest).await
+    }
+
+    /// Seconds since the Unix epoch when the cached manifest was last fetched or revalidated.
+    pub async fn manifest_refreshed_at(&self) -> Option<u64> {
+        load_cached_manifest().await.map(|cached| cached.fetched_at)
+    }
+
+    async fn set_manifest(&self, manifest: VersionManifest) -> Result<VersionManifest> {
+        let mut cached_manifest = self.version_manifest.lock().await;
+        *cached_manifest = Some(manifest.clone());
+        Ok(manifest)
     }
 
     /// Get available Minecraft versions.
@@ -94,11 +308,25 @@ impl LauncherService {
         Ok(manifest.versions)
     }
 
+    /// Cancel the install running for an instance, returning whether there was one.
+    ///
+    /// Once the game has been launched it is stopped like any other process instead.
+    pub fn cancel_install(&self, instance_id: u32) -> bool {
+        let cancels = INSTALL_CANCELS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        cancels
+            .get(&instance_id)
+            .is_some_and(|cancel| cancel.send(true).is_ok())
+    }
+
     /// Install and launch a Minecraft instance.
     pub async fn install_and_launch_instance(
         &self,
         instance_id: u32,
         version: &str,
+        server_address: Option<String>,
+        world: Option<String>,
         log_sender: mpsc::UnboundedSender<MinecraftLogMessage>,
     ) -> Result<LaunchResult> {
         info!("Starting installation and launch for instance {instance_id} with version {version}");
@@ -138,38 +366,78 @@ impl LauncherService {
 
         // Get Python operations from Archon
         let archon = self.archon.clone();
+        let settings = LauncherSettings::load().await;
 
-        // First install the version using Archon
-        info!("Installing Minecraft version {version} through Archon");
+        // First install the version using Archon, or only check local files when offline
+        let (operation, action) = if settings.offline_mode {
+            ("check_minecraft", "Checking")
+        } else {
+            ("install_minecraft", "Installing")
+        };
+        info!(
+            "{action} Minecraft version {version} ({}) through Archon",
+            instance.loader.id()
+        );
         if let Err(e) = log_sender.send(MinecraftLogMessage::LaunchResult {
             success: true,
-            message: format!("Installing Minecraft {version}"),
+            message: match instance.loader {
+                ModLoader::Vanilla => format!("{action} Minecraft {version}"),
+                loader => format!("{action} {} for Minecraft {version}", loader.display_name()),
+            },
             pid: None,
         }) {
             warn!("Failed to send installation status: {e}");
         }
+        if !settings.offline_mode {
+            let _ = log_sender.send(MinecraftLogMessage::Event(GameEvent::Downloading));
+        }
 
-        match archon
-            .python_operation(
-                "install_minecraft".to_string(),
-                vec![
-                    version.to_string(),
-                    minecraft_dir.to_string_lossy().to_string(),
-                ],
-            )
-            .await
-        {
+        // Dropping the install stops the script, so cancelling doesn't wait for it
+        let mut cancel = CancellableInstall::register(instance_id);
+        let install = install_version(&archon, operation, &instance, version, &minecraft_dir);
+        let response = tokio::select! {
+            install = install => install,
+            () = cancel.cancelled() => {
+                info!("Installation of {version} for instance {instance_id} was cancelled");
+                return Err(InstallCancelled {
+                    version: version.to_string(),
+                }
+                .into());
+            }
+        };
+        drop(cancel);
+
+        // Mod loaders are launched through their own installed version id
+        let launch_version = match response {
             Ok(response) => {
                 if !response.success {
+                    let kind = response
+                        .data
+                        .as_ref()
+                        .and_then(|d| d.get("error_kind"))
+                        .and_then(serde_json::Value::as_str)
+                        .and_then(ErrorKind::from_id);
                     let error_msg = response.error.unwrap_or("Unknown error".to_string());
                     error!("Version installation failed: {error_msg}");
-                    return Ok(LaunchResult {
-                        success: false,
-                        message: format!("Failed to install version {version}: {error_msg}"),
-                        pid: None,
-                    });
+                    let message = format!("Failed to install version {version}: {error_msg}");
+                    // A known kind lets the UI say what to do about it
+                    return match kind {
+                        Some(kind) => Err(TaggedError::new(kind, message).into()),
+                        None => Ok(LaunchResult {
+                            success: false,
+                            message,
+                            pid: None,
+                        }),
+                    };
                 }
                 info!("Version {version} installed successfully");
+                response
+                    .data
+                    .as_ref()
+                    .and_then(|d| d.get("version"))
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or(version)
+                    .to_string()
             }
             Err(e) => {
                 error!("Failed to install version through Archon: {e}");
@@ -179,30 +447,40 @@ impl LauncherService {
                     pid: None,
                 });
             }
-        }
+        };
+
+        // A custom Java that is too old would only crash the game after it starts
+        check_java_version(&settings, &minecraft_dir, version, &launch_version).await?;
 
         // Create launch configuration
-        let launch_config = LaunchConfig {
-            username: "Player".to_string(),
-            version: version.to_string(),
-            java_path: None,
-            jvm_args: vec!["-Xmx2G".to_string(), "-Xms1G".to_string()],
-            game_args: vec![],
-            access_token: "dummy_token".to_string(),
-            uuid: "00000000-0000-0000-0000-000000000000".to_string(),
-        };
+        let launch_config = instance_launch_config(
+            &settings,
+            &instance,
+            &minecraft_dir,
+            &launch_version,
+            server_address,
+            world,
+            &log_sender,
+        )
+        .await;
 
         // Launch through Archon
         info!("Launching Minecraft through Archon");
-        let launch_args = vec![
-            launch_config.username,
-            launch_config.version,
-            minecraft_dir.to_string_lossy().to_string(),
-            instance_dir.to_string_lossy().to_string(),
-        ];
+        let _ = log_sender.send(MinecraftLogMessage::Event(GameEvent::Launching));
+        if let Some(server_address) = &launch_config.server_address {
+            info!("Joining server {server_address} on launch");
+        }
+        if let Some(world) = &launch_config.world {
+            info!("Opening world {world} on launch");
+        }
+        let launch_args = launch_config.into_launch_args(&minecraft_dir, &instance_dir);
 
         match archon
-            .python_operation("launch_minecraft".to_string(), launch_args)
+            .python_operation_with_logs(
+                "launch_minecraft".to_string(),
+                launch_args,
+                Some(log_sender),
+            )
             .await
         {
             Ok(response) => {
@@ -214,6 +492,11 @@ impl LauncherService {
                         .and_then(serde_json::Value::as_u64)
                         .map(|p| p as u32);
                     info!("Minecraft launched successfully");
+
+                    let mut instance_service = self.instance_service.lock().await;
+                    if let Err(e) = instance_service.record_launch(instance_id).await {
+                        warn!("Failed to record launch of instance {instance_id}: {e}");
+                    }
                     Ok(LaunchResult {
                         success: true,
                         message: "Minecraft launched successfully".to_string(),
@@ -240,21 +523,145 @@ impl LauncherService {
         }
     }
 
+    /// Re-download the missing or corrupted game files of an instance.
+    ///
+    /// Returns the repaired files relative to the shared Minecraft directory.
+    pub async fn repair_instance(&self, instance_id: u32) -> Result<Vec<String>> {
+        let instance = {
+            let instance_service = self.instance_service.lock().await;
+            instance_service
+                .get_instance(instance_id)
+                .ok_or_else(|| anyhow::anyhow!("Instance {instance_id} not found"))?
+                .clone()
+        };
+        let minecraft_dir = get_launcher_dir()?.join("minecraft");
+
+        info!(
+            "Repairing Minecraft {} ({}) for instance {instance_id}",
+            instance.version,
+            instance.loader.id()
+        );
+        let response = self
+            .archon
+            .python_operation(
+                "repair_minecraft".to_string(),
+                install_arguments(&instance, &instance.version, &minecraft_dir),
+            )
+            .await?;
+        if !response.success {
+            let error_msg = response.error.unwrap_or("Unknown error".to_string());
+            return Err(anyhow::anyhow!("Failed to repair instance: {error_msg}"));
+        }
+
+        let repaired: Vec<String> = response
+            .data
+            .as_ref()
+            .and_then(|d| d.get("repaired"))
+            .and_then(|r| serde_json::from_value(r.clone()).ok())
+            .unwrap_or_default();
+        info!(
+            "Repaired {} files for instance {instance_id}",
+            repaired.len()
+        );
+        Ok(repaired)
+    }
+
+    /// Build the command that would launch an instance, without starting the game.
+    ///
+    /// Nothing is downloaded, so the version has to be installed already.
+    /// Secrets such as the access token are redacted.
+    pub async fn launch_command(&self, instance_id: u32) -> Result<String> {
+        let (instance, instance_dir) = {
+            let instance_service = self.instance_service.lock().await;
+            let instance = instance_service
+                .get_instance(instance_id)
+                .ok_or_else(|| anyhow::anyhow!("Instance {instance_id} not found"))?
+                .clone();
+            (
+                instance,
+                instance_service.get_instance_directory(instance_id),
+            )
+        };
+        let minecraft_dir = get_launcher_dir()?.join("minecraft");
+        let settings = LauncherSettings::load().await;
+
+        let response = self
+            .archon
+            .python_operation(
+                "check_minecraft".to_string(),
+                install_arguments(&instance, &instance.version, &minecraft_dir),
+            )
+            .await?;
+        if !response.success {
+            return Err(anyhow::anyhow!(
+                "Minecraft {} is not installed yet, launch it once first",
+                instance.version
+            ));
+        }
+        let launch_version = response
+            .data
+            .as_ref()
+            .and_then(|d| d.get("version"))
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or(&instance.version)
+            .to_string();
+
+        // JVM preset warnings only matter for a real launch
+        let (log_sender, _log_receiver) = mpsc::unbounded_channel();
+        let launch_config = instance_launch_config(
+            &settings,
+            &instance,
+            &minecraft_dir,
+            &launch_version,
+            None,
+            None,
+            &log_sender,
+        )
+        .await;
+
+        let response = self
+            .archon
+            .python_operation(
+                "launch_command".to_string(),
+                launch_config.into_launch_args(&minecraft_dir, &instance_dir),
+            )
+            .await?;
+        if !response.success {
+            let error_msg = response.error.unwrap_or("Unknown error".to_string());
+            return Err(anyhow::anyhow!(
+                "Failed to build launch command: {error_msg}"
+            ));
+        }
+        response
+            .data
+            .as_ref()
+            .and_then(|d| d.get("command"))
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Launcher script returned no command"))
+    }
+
     /// Check if a specific version is available.
     pub async fn is_version_available(&self, version: &str) -> Result<bool> {
         let versions = self.get_available_versions().await?;
         Ok(versions.iter().any(|v| v.id == version))
     }
 
-    /// Create a new instance with a specific version.
-    pub async fn create_instance_with_version(&self, version: &str) -> Result<Option<u32>> {
+    /// Create a new instance with a specific version and mod loader.
+    pub async fn create_instance_with_version(
+        &self,
+        version: &str,
+        loader: ModLoader,
+    ) -> Result<Option<u32>> {
         // Verify version exists
         if !self.is_version_available(version).await? {
             return Err(anyhow::anyhow!("Version {} is not available", version));
         }
 
         let mut instance_service = self.instance_service.lock().await;
-        instance_service.create_instance_with_version(version).await
+        instance_service
+            .create_instance_with_version(version, loader)
+            .await
     }
 
     /// Get all instances.
@@ -269,6 +676,25 @@ impl LauncherService {
         instance_service.delete_instance(instance_id).await
     }
 
+    /// Clone an instance.
+    pub async fn clone_instance<F>(&self, instance_id: u32, on_progress: F) -> Result<Option<u32>>
+    where
+        F: Fn(usize, usize) + Send,
+    {
+        let Some(mut clone) = self.instance_service.lock().await.start_clone(instance_id) else {
+            return Ok(None);
+        };
+
+        // Copying can take a while, so other instance changes don't wait for it
+        if let Err(e) = clone.copy_files(on_progress).await {
+            clone.discard().await;
+            return Err(e);
+        }
+
+        let mut instance_service = self.instance_service.lock().await;
+        instance_service.finish_clone(clone).await
+    }
+
     /// Rename an instance.
     pub async fn rename_instance(&self, instance_id: u32, new_name: &str) -> Result<bool> {
         let mut instance_service = self.instance_service.lock().await;
@@ -277,9 +703,589 @@ impl LauncherService {
             .await
     }
 
+    /// Create an instance from a Modrinth modpack (`.mrpack`).
+    pub async fn import_modpack(&self, path: &Path) -> Result<Option<ModpackImport>> {
+        let index = MrpackIndex::read(path)?;
+        let version = index.minecraft_version()?.to_string();
+        let (loader, loader_version) = index.loader()?;
+
+        let (instance_id, instance_dir) = {
+            let mut instance_service = self.instance_service.lock().await;
+            let Some(instance_id) = instance_service
+                .create_instance_with_version(&version, loader)
+                .await?
+            else {
+                return Ok(None);
+            };
+            instance_service
+                .rename_instance(instance_id, &index.name)
+                .await?;
+            // Newer loader versions can break the pack's mods
+            instance_service
+                .set_loader_version(instance_id, loader_version)
+                .await?;
+            (
+                instance_id,
+                instance_service.get_instance_directory(instance_id),
+            )
+        };
+
+        match install_mrpack(&index, path, &instance_dir).await {
+            Ok(skipped) => Ok(Some(ModpackImport {
+                instance_id,
+                name: index.name.chars().take(MAX_NAME_LENGTH).collect(),
+                skipped,
+            })),
+            Err(e) => {
+                // Don't leave a half-installed instance behind
+                let mut instance_service = self.instance_service.lock().await;
+                instance_service.delete_instance(instance_id).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Download a modpack from Modrinth and create an instance from it.
+    pub async fn install_modrinth_modpack(
+        &self,
+        project_id: &str,
+    ) -> Result<Option<ModpackImport>> {
+        let downloads_dir = get_cache_dir()?.join("modpacks");
+        let archive = download_modpack(project_id, &downloads_dir).await?;
+
+        let result = self.import_modpack(&archive).await;
+        if let Err(e) = tokio::fs::remove_file(&archive).await {
+            warn!("Failed to remove downloaded modpack {archive:?}: {e}");
+        }
+        result
+    }
+
+    /// Export an instance as a zip archive.
+    pub async fn export_instance(
+        &self,
+        instance_id: u32,
+        dest: PathBuf,
+        include_worlds: bool,
+    ) -> Result<()> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service
+            .export_instance(instance_id, dest, include_worlds)
+            .await
+    }
+
+    /// Create a new instance from an exported zip archive.
+    pub async fn import_instance(&self, path: &Path) -> Result<Option<u32>> {
+        let mut instance_service = self.instance_service.lock().await;
+        instance_service.import_instance(path).await
+    }
+
+    /// Register the versions of a vanilla `.minecraft` directory as instances.
+    pub async fn import_existing_installation(&self, minecraft_dir: &Path) -> Result<Vec<u32>> {
+        let mut instance_service = self.instance_service.lock().await;
+        instance_service
+            .import_existing_installation(minecraft_dir)
+            .await
+    }
+
+    /// Save captured debug console output into the logs folder of an instance.
+    pub async fn export_debug_log(&self, instance_id: u32, contents: &str) -> Result<PathBuf> {
+        let instance_service = self.instance_service.lock().await;
+        instance_service
+            .export_debug_log(instance_id, contents)
+            .await
+    }
+
+    /// Set a custom icon for an instance.
+    pub async fn set_instance_icon(&self, instance_id: u32, source: &Path) -> Result<PathBuf> {
+        let mut instance_service = self.instance_service.lock().await;
+        instance_service.set_instance_icon(instance_id, source).await
+    }
+
+    /// Set the game window size and fullscreen mode of an instance.
+    pub async fn set_window_settings(
+        &self,
+        instance_id: u32,
+        size: Option<(u32, u32)>,
+        fullscreen: bool,
+    ) -> Result<bool> {
+        let mut instance_service = self.instance_service.lock().await;
+        instance_service
+            .set_window_settings(instance_id, size, fullscreen)
+            .await
+    }
+
+    /// Set the JVM garbage collector preset of an instance.
+    pub async fn set_jvm_preset(&self, instance_id: u32, preset: JvmPreset) -> Result<bool> {
+        let mut instance_service = self.instance_service.lock().await;
+        instance_service.set_jvm_preset(instance_id, preset).await
+    }
+
+    /// Remember a server joined from an instance.
+    pub async fn record_server(&self, instance_id: u32, address: &str) -> Result<bool> {
+        let mut instance_service = self.instance_service.lock().await;
+        instance_service.record_server(instance_id, address).await
+    }
+
     /// Open instance folder.
     pub async fn open_instance_folder(&self, instance_id: u32) -> Result<()> {
         let instance_service = self.instance_service.lock().await;
         instance_service.open_instance_folder(instance_id).await
     }
 }
+
+/// Launch configuration of an instance running the given installed version id.
+async fn instance_launch_config(
+    settings: &LauncherSettings,
+    instance: &Instance,
+    minecraft_dir: &Path,
+    launch_version: &str,
+    server_address: Option<String>,
+    world: Option<String>,
+    log_sender: &mpsc::UnboundedSender<MinecraftLogMessage>,
+) -> LaunchConfig {
+    LaunchConfig {
+        username: "Player".to_string(),
+        version: launch_version.to_string(),
+        java_path: settings
+            .java_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string()),
+        jvm_args: jvm_arguments(
+            settings,
+            &instance.jvm_preset,
+            minecraft_dir,
+            launch_version,
+            log_sender,
+        )
+        .await,
+        game_args: instance.window_arguments(),
+        access_token: "dummy_token".to_string(),
+        uuid: "00000000-0000-0000-0000-000000000000".to_string(),
+        server_address,
+        world,
+    }
+}
+
+/// Heap arguments from the launcher settings followed by the preset flags of the instance.
+///
+/// Presets the Java runtime is too old for are skipped with a warning in the game log.
+/// The runtime is the custom Java executable if one is set, or else the one the game version asks for.
+pub async fn jvm_arguments(
+    settings: &LauncherSettings,
+    preset: &JvmPreset,
+    minecraft_dir: &Path,
+    version: &str,
+    log_sender: &mpsc::UnboundedSender<MinecraftLogMessage>,
+) -> Vec<String> {
+    let mut args = settings.jvm_arguments();
+
+    let warning = match preset.min_java_version() {
+        Some(required) => {
+            let java = match &settings.java_path {
+                Some(java_path) => java_major_version(java_path)
+                    .await
+                    .map_err(|e| warn!("Failed to read Java version of {java_path:?}: {e}"))
+                    .ok(),
+                None => required_java_version(minecraft_dir, version),
+            };
+            match java {
+                Some(java) if java < required => Some(format!(
+                    "{} preset needs Java {required}+, but Minecraft {version} runs on Java {java}",
+                    preset.display_name()
+                )),
+                _ => None,
+            }
+        }
+        None => None,
+    };
+    if let Some(warning) = warning {
+        warn!("{warning}, using default JVM flags");
+        let line = format!("[Launcher/WARN]: {warning}, using default JVM flags");
+        if let Err(e) = log_sender.send(MinecraftLogMessage::Log { line, pid: None }) {
+            warn!("Failed to send JVM preset warning: {e}");
+        }
+        return args;
+    }
+
+    match preset.arguments() {
+        Ok(preset_args) => args.extend(preset_args),
+        Err(e) => warn!("Ignoring invalid JVM arguments: {e}"),
+    }
+    args
+}
+
+/// Arguments of the install, check and repair script operations for an instance.
+fn install_arguments(instance: &Instance, version: &str, minecraft_dir: &Path) -> Vec<String> {
+    let mut args = vec![
+        version.to_string(),
+        minecraft_dir.to_string_lossy().to_string(),
+        instance.loader.id().to_string(),
+    ];
+    args.extend(instance.loader_version.clone());
+    args
+}
+
+/// Run an install or check operation of the launcher script for an instance.
+///
+/// A second install of the same Minecraft version waits for the first one and
+/// then finds the shared files already downloaded.
+async fn install_version(
+    archon: &Archon,
+    operation: &str,
+    instance: &Instance,
+    version: &str,
+    minecraft_dir: &Path,
+) -> Result<PythonResponse> {
+    let _guard = lock_install(version).await;
+
+    // Forge and Fabric installers refuse to install into a directory without profiles
+    if let Err(e) = write_launcher_profiles(minecraft_dir, version).await {
+        warn!("Failed to write launcher profiles: {e}");
+    }
+
+    archon
+        .python_operation(
+            operation.to_string(),
+            install_arguments(instance, version, minecraft_dir),
+        )
+        .await
+}
+
+/// Fail with [`JavaVersionMismatch`] if the custom Java executable is older than the game version needs.
+///
+/// Without a custom executable the game runs on the Java it asks for, so there is nothing to check.
+/// `launch_version` is the installed version id, which differs from `version` for mod loaders.
+pub async fn check_java_version(
+    settings: &LauncherSettings,
+    minecraft_dir: &Path,
+    version: &str,
+    launch_version: &str,
+) -> Result<()> {
+    let Some(java_path) = &settings.java_path else {
+        return Ok(());
+    };
+    let Some(required) = required_java_version(minecraft_dir, launch_version) else {
+        return Ok(());
+    };
+
+    let found = match java_major_version(java_path).await {
+        Ok(found) => found,
+        Err(e) => {
+            warn!("Failed to read Java version of {java_path:?}: {e}");
+            return Ok(());
+        }
+    };
+    if found < required {
+        return Err(JavaVersionMismatch {
+            version: version.to_string(),
+            required,
+            found,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Java major version an installed game version runs on, following `inheritsFrom`.
+fn required_java_version(minecraft_dir: &Path, version: &str) -> Option<u32> {
+    let mut version = version.to_string();
+    // Loader versions inherit from the vanilla version, which is only a level or two deep
+    for _ in 0..4 {
+        let path = minecraft_dir
+            .join("versions")
+            .join(&version)
+            .join(format!("{version}.json"));
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+
+        if let Some(major) = json
+            .pointer("/javaVersion/majorVersion")
+            .and_then(serde_json::Value::as_u64)
+        {
+            return u32::try_from(major).ok();
+        }
+        version = json.get("inheritsFrom")?.as_str()?.to_string();
+    }
+    None
+}
+
+/// Name of the profile the launcher keeps in `launcher_profiles.json`.
+const LAUNCHER_PROFILE: &str = "DreamLauncher";
+
+/// Held while `launcher_profiles.json` is updated, which installs of every version share.
+static PROFILES_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Create or update the vanilla-style `launcher_profiles.json` that mod loader installers expect.
+///
+/// Profiles written by other launchers or installers are kept; only missing keys are added
+/// and the launcher profile is pointed at `version`. The file is replaced in one step,
+/// so an installer never reads it half-written.
+pub async fn write_launcher_profiles(minecraft_dir: &Path, version: &str) -> Result<()> {
+    let _guard = PROFILES_LOCK.lock().await;
+    let path = minecraft_dir.join("launcher_profiles.json");
+    let mut root = match tokio::fs::read_to_string(&path).await {
+        Ok(json) => serde_json::from_str::<serde_json::Value>(&json)
+            .ok()
+            .filter(serde_json::Value::is_object)
+            .unwrap_or_else(|| {
+                warn!("Replacing invalid {path:?}");
+                serde_json::json!({})
+            }),
+        Err(_) => serde_json::json!({}),
+    };
+
+    let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let root_map = root
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Launcher profiles must be a JSON object"))?;
+    for (key, default) in [
+        ("profiles", serde_json::json!({})),
+        ("authenticationDatabase", serde_json::json!({})),
+        ("settings", serde_json::json!({})),
+        ("clientToken", serde_json::json!("0".repeat(32))),
+        ("version", serde_json::json!(3)),
+    ] {
+        root_map.entry(key).or_insert(default);
+    }
+
+    let profiles = root_map
+        .get_mut("profiles")
+        .and_then(serde_json::Value::as_object_mut)
+        .ok_or_else(|| anyhow::anyhow!("Launcher profiles have no profiles object"))?;
+    let profile = profiles
+        .entry(LAUNCHER_PROFILE)
+        .or_insert_with(|| serde_json::json!({ "created": now }));
+    if let Some(profile) = profile.as_object_mut() {
+        profile.insert("name".to_string(), serde_json::json!(LAUNCHER_PROFILE));
+        profile.insert("type".to_string(), serde_json::json!("custom"));
+        profile.insert("lastVersionId".to_string(), serde_json::json!(version));
+        profile.insert("lastUsed".to_string(), serde_json::json!(now));
+    }
+
+    let selected_exists = root_map
+        .get("selectedProfile")
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(|selected| root_map["profiles"].get(selected).is_some());
+    if !selected_exists {
+        root_map.insert(
+            "selectedProfile".to_string(),
+            serde_json::json!(LAUNCHER_PROFILE),
+        );
+    }
+
+    tokio::fs::create_dir_all(minecraft_dir).await?;
+    let temp_path = path.with_extension("json.tmp");
+    tokio::fs::write(&temp_path, serde_json::to_string_pretty(&root)?).await?;
+    tokio::fs::rename(&temp_path, &path).await?;
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn manifest_cache_path() -> PathBuf {
+    get_cache_dir()
+        .unwrap_or_else(|_| PathBuf::from("Dream Launcher/cache"))
+        .join("version_manifest_v2.json")
+}
+
+async fn load_cached_manifest() -> Option<CachedManifest> {
+    let json = tokio::fs::read_to_string(manifest_cache_path()).await.ok()?;
+    serde_json::from_str(&json)
+        .map_err(|e| warn!("Ignoring invalid version manifest cache: {e}"))
+        .ok()
+}
+
+async fn save_cached_manifest(cached: &CachedManifest) -> Result<()> {
+    let path = manifest_cache_path();
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, serde_json::to_string(cached)?).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::archon::ArchonMessage;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answer install operations like the script, which only downloads versions that are missing.
+    fn stub_archon(downloads: Arc<AtomicUsize>) -> Archon {
+        let (archon, mut messages) = Archon::stub();
+        tokio::spawn(async move {
+            let installed = Arc::new(std::sync::Mutex::new(HashSet::new()));
+            while let Some(message) = messages.recv().await {
+                let ArchonMessage::Python {
+                    args, response_tx, ..
+                } = message
+                else {
+                    continue;
+                };
+                let installed = installed.clone();
+                let downloads = downloads.clone();
+                tokio::spawn(async move {
+                    let version = args[0].clone();
+                    if !installed.lock().unwrap().contains(&version) {
+                        // Give the other install a chance to run while this one is downloading
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        downloads.fetch_add(1, Ordering::SeqCst);
+                        installed.lock().unwrap().insert(version.clone());
+                    }
+                    if let Some(response_tx) = response_tx {
+                        let _ = response_tx.send(PythonResponse {
+                            success: true,
+                            data: Some(serde_json::json!({ "version": version })),
+                            error: None,
+                        });
+                    }
+                });
+            }
+        });
+        archon
+    }
+
+    fn temp_minecraft_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dream-launcher-{name}-{}", std::process::id()))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn concurrent_installs_of_a_version_download_once() {
+        let downloads = Arc::new(AtomicUsize::new(0));
+        let archon = Arc::new(stub_archon(downloads.clone()));
+        let minecraft_dir = temp_minecraft_dir("concurrent-install");
+
+        let installs: Vec<_> = (1..=2)
+            .map(|id| {
+                let archon = archon.clone();
+                let minecraft_dir = minecraft_dir.clone();
+                tokio::spawn(async move {
+                    let instance = Instance::new_with_version(id, "test-concurrent".to_string());
+                    install_version(
+                        &archon,
+                        "install_minecraft",
+                        &instance,
+                        "test-concurrent",
+                        &minecraft_dir,
+                    )
+                    .await
+                })
+            })
+            .collect();
+        for install in installs {
+            assert!(install.await.unwrap().unwrap().success);
+        }
+
+        assert_eq!(downloads.load(Ordering::SeqCst), 1);
+        std::fs::remove_dir_all(&minecraft_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn loaders_of_a_version_wait_for_each_other() {
+        let archon = stub_archon(Arc::new(AtomicUsize::new(0)));
+        let minecraft_dir = temp_minecraft_dir("loader-install");
+
+        let vanilla = lock_install("test-shared-version").await;
+        let fabric =
+            Instance::new_with_loader(1, "test-shared-version".to_string(), ModLoader::Fabric);
+        let install = tokio::time::timeout(
+            Duration::from_millis(200),
+            install_version(
+                &archon,
+                "install_minecraft",
+                &fabric,
+                "test-shared-version",
+                &minecraft_dir,
+            ),
+        )
+        .await;
+        assert!(install.is_err());
+
+        drop(vanilla);
+        let install = install_version(
+            &archon,
+            "install_minecraft",
+            &fabric,
+            "test-shared-version",
+            &minecraft_dir,
+        )
+        .await;
+        assert!(install.unwrap().success);
+        std::fs::remove_dir_all(&minecraft_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn writes_launcher_profiles_installers_accept() {
+        let minecraft_dir = temp_minecraft_dir("profiles");
+        std::fs::create_dir_all(&minecraft_dir).unwrap();
+        let path = minecraft_dir.join("launcher_profiles.json");
+        std::fs::write(
+            &path,
+            r#"{"profiles": {"forge": {"name": "forge", "lastVersionId": "1.20.1-forge-47.2.0"}}}"#,
+        )
+        .unwrap();
+
+        write_launcher_profiles(&minecraft_dir, "1.20.1")
+            .await
+            .unwrap();
+
+        let root: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        for key in [
+            "profiles",
+            "authenticationDatabase",
+            "settings",
+            "clientToken",
+            "version",
+            "selectedProfile",
+        ] {
+            assert!(root.get(key).is_some(), "missing {key}");
+        }
+        assert!(root["profiles"].is_object());
+        assert!(root["authenticationDatabase"].is_object());
+        assert_eq!(root["selectedProfile"], LAUNCHER_PROFILE);
+        assert_eq!(
+            root["profiles"][LAUNCHER_PROFILE]["lastVersionId"],
+            "1.20.1"
+        );
+        assert_eq!(
+            root["profiles"]["forge"]["lastVersionId"],
+            "1.20.1-forge-47.2.0"
+        );
+        assert!(!path.with_extension("json.tmp").exists());
+
+        std::fs::remove_dir_all(&minecraft_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn installs_of_other_versions_do_not_wait() {
+        let _guard = lock_install("test-held-version").await;
+        let other =
+            tokio::time::timeout(Duration::from_secs(1), lock_install("test-other-version")).await;
+        assert!(other.is_ok());
+    }
+
+    #[tokio::test]
+    async fn released_install_locks_are_removed() {
+        let guard = lock_install("test-released-version").await;
+        let waiter = tokio::spawn(lock_install("test-released-version"));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        drop(guard);
+        let contains = || {
+            INSTALL_LOCKS
+                .lock()
+                .unwrap()
+                .contains_key("test-released-version")
+        };
+        // The waiter still needs the lock
+        assert!(contains());
+        drop(waiter.await.unwrap());
+        assert!(!contains());
+    }
+}
@@ -1,10 +1,29 @@
 //! Core services.
 
+pub mod cloud;
 pub mod instance;
 pub mod launcher;
+pub mod modpack;
+pub mod mods;
+pub mod news;
+pub mod servers;
+pub mod settings;
+pub mod status;
 pub mod tracker;
 pub mod updater;
 
-pub use instance::{Instance, InstanceService};
+pub use cloud::{CloudConfig, CloudPassword, CloudService, CloudSyncInfo, SyncStatus};
+pub use instance::{
+    Instance, InstanceService, MAX_INSTANCES, MAX_NAME_LENGTH, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH,
+    ModLoader, Screenshot, validate_window_size,
+};
 pub use launcher::{LaunchResult, LauncherService, VersionInfo, VersionManifest};
+pub use modpack::{ModpackImport, ModpackSearchResult, SkippedFile};
+pub use mods::ModInfo;
+pub use news::{NewsItem, NewsService};
+pub use servers::SavedServer;
+pub use settings::{
+    DEFAULT_ACCENT_COLOR, DownloadMirror, JvmPreset, LauncherSettings, MIN_MEMORY_MB, VersionFilter,
+};
+pub use status::{MojangService, StatusService};
 pub use tracker::VisitTracker;
@@ -0,0 +1,354 @@
+//! Modrinth modpack (`.mrpack`) import.
+
+use crate::backend::services::instance::ModLoader;
+use crate::backend::utils::archive::apply_unix_mode;
+use crate::backend::utils::download::{FileHashes, download_file, verify_files};
+use crate::backend::utils::http;
+use anyhow::Result;
+use log::{info, warn};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Hosts Modrinth allows pack files to be downloaded from.
+const ALLOWED_HOSTS: [&str; 4] = [
+    "cdn.modrinth.com",
+    "github.com",
+    "raw.githubusercontent.com",
+    "gitlab.com",
+];
+
+/// Modrinth API base URL.
+const MODRINTH_API: &str = "https://api.modrinth.com/v2";
+
+/// User agent Modrinth asks API clients to identify themselves with.
+const MODRINTH_USER_AGENT: &str = concat!("FrogdreamStudios/launcher/", env!("CARGO_PKG_VERSION"));
+
+/// Mod loaders of Modrinth versions that can be installed.
+const SUPPORTED_LOADERS: [&str; 4] = ["forge", "neoforge", "fabric", "quilt"];
+
+/// Archive folders whose contents are copied into the instance.
+const OVERRIDE_DIRECTORIES: [&str; 2] = ["overrides/", "client-overrides/"];
+
+/// Contents of `modrinth.index.json`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackIndex {
+    pub format_version: u32,
+    pub game: String,
+    pub version_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub files: Vec<MrpackFile>,
+    #[serde(default)]
+    pub dependencies: HashMap<String, String>,
+}
+
+/// File entry of a modpack index.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackFile {
+    pub path: String,
+    #[serde(default)]
+    pub hashes: HashMap<String, String>,
+    pub env: Option<MrpackEnv>,
+    #[serde(default)]
+    pub downloads: Vec<String>,
+    #[serde(default)]
+    pub file_size: u64,
+}
+
+/// Side requirements of a modpack file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MrpackEnv {
+    pub client: String,
+    pub server: String,
+}
+
+/// File that was not installed from a modpack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Modpack listed by a Modrinth search.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModpackSearchResult {
+    pub project_id: String,
+    pub title: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+    pub downloads: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    hits: Vec<ModpackSearchResult>,
+}
+
+/// Version of a Modrinth project.
+#[derive(Debug, Deserialize)]
+struct ModrinthVersion {
+    name: String,
+    #[serde(default)]
+    loaders: Vec<String>,
+    files: Vec<ModrinthVersionFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModrinthVersionFile {
+    url: String,
+    filename: String,
+    #[serde(default)]
+    primary: bool,
+    #[serde(default)]
+    hashes: HashMap<String, String>,
+}
+
+/// Result of importing a modpack.
+#[derive(Debug, Clone)]
+pub struct ModpackImport {
+    pub instance_id: u32,
+    pub name: String,
+    pub skipped: Vec<SkippedFile>,
+}
+
+impl MrpackIndex {
+    /// Read the index from a `.mrpack` archive.
+    pub fn read(path: &Path) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+        let entry = archive
+            .by_name("modrinth.index.json")
+            .map_err(|_| anyhow::anyhow!("{} has no modrinth.index.json", path.display()))?;
+        let index: Self = serde_json::from_reader(entry)?;
+
+        if index.format_version != 1 {
+            return Err(anyhow::anyhow!(
+                "Unsupported modpack format version: {}",
+                index.format_version
+            ));
+        }
+        if index.game != "minecraft" {
+            return Err(anyhow::anyhow!("Unsupported modpack game: {}", index.game));
+        }
+
+        Ok(index)
+    }
+
+    /// Minecraft version the pack is built for.
+    pub fn minecraft_version(&self) -> Result<&str> {
+        self.dependencies
+            .get("minecraft")
+            .map(String::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Modpack does not specify a Minecraft version"))
+    }
+
+    /// Mod loader the pack requires and the loader version it is pinned to.
+    pub fn loader(&self) -> Result<(ModLoader, Option<String>)> {
+        for (dependency, version) in &self.dependencies {
+            let loader = match dependency.as_str() {
+                "minecraft" => continue,
+                "forge" => ModLoader::Forge,
+                "neoforge" => ModLoader::NeoForge,
+                "fabric-loader" => ModLoader::Fabric,
+                "quilt-loader" => ModLoader::Quilt,
+                other => {
+                    return Err(anyhow::anyhow!("Unsupported modpack dependency: {other}"));
+                }
+            };
+            return Ok((loader, Some(version.clone())));
+        }
+        Ok((ModLoader::Vanilla, None))
+    }
+}
+
+/// Search Modrinth for modpacks.
+pub async fn search_modpacks(query: &str) -> Result<Vec<ModpackSearchResult>> {
+    let response: SearchResponse = modrinth_client()?
+        .get(format!("{MODRINTH_API}/search"))
+        .query(&[
+            ("query", query),
+            ("facets", r#"[["project_type:modpack"]]"#),
+            ("limit", "20"),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(response.hits)
+}
+
+/// Download the newest installable `.mrpack` of a Modrinth project into `dest_dir`.
+pub async fn download_modpack(project_id: &str, dest_dir: &Path) -> Result<PathBuf> {
+    let client = modrinth_client()?;
+    let versions: Vec<ModrinthVersion> = client
+        .get(format!("{MODRINTH_API}/project/{project_id}/version"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    // Versions are listed newest first
+    let (version, file) = versions
+        .iter()
+        .filter(|version| {
+            version
+                .loaders
+                .iter()
+                .all(|loader| SUPPORTED_LOADERS.contains(&loader.as_str()))
+        })
+        .find_map(|version| {
+            let mut files = version
+                .files
+                .iter()
+                .filter(|f| f.filename.ends_with(".mrpack"));
+            let file = files.clone().find(|f| f.primary).or_else(|| files.next())?;
+            Some((version, file))
+        })
+        .ok_or_else(|| anyhow::anyhow!("No installable version of this modpack was found"))?;
+
+    let file_name = safe_relative_path(&file.filename)
+        .filter(|path| path.components().count() == 1)
+        .ok_or_else(|| anyhow::anyhow!("Invalid modpack file name: {}", file.filename))?;
+    let target = dest_dir.join(file_name);
+    let hashes = FileHashes {
+        sha1: file.hashes.get("sha1").cloned(),
+        sha512: file.hashes.get("sha512").cloned(),
+    };
+    download_file(&client, &file.url, &target, &hashes).await?;
+
+    info!("Downloaded modpack {project_id} version {}", version.name);
+    Ok(target)
+}
+
+fn modrinth_client() -> Result<reqwest::Client> {
+    Ok(http::client_builder()?
+        .user_agent(MODRINTH_USER_AGENT)
+        .build()?)
+}
+
+/// Download the files of a modpack and copy its overrides into an instance.
+///
+/// Returns the files that were skipped because they are server-only, point
+/// outside the instance or can only be downloaded from disallowed hosts.
+pub async fn install_mrpack(
+    index: &MrpackIndex,
+    archive_path: &Path,
+    instance_dir: &Path,
+) -> Result<Vec<SkippedFile>> {
+    let client = http::client()?;
+    let mut skipped = Vec::new();
+    let mut downloads = Vec::new();
+
+    for file in &index.files {
+        let mut skip = |reason: &str| {
+            warn!("Skipping modpack file {}: {reason}", file.path);
+            skipped.push(SkippedFile {
+                path: file.path.clone(),
+                reason: reason.to_string(),
+            });
+        };
+
+        if file.env.as_ref().is_some_and(|env| env.client == "unsupported") {
+            skip("not used on the client");
+            continue;
+        }
+        let Some(relative_path) = safe_relative_path(&file.path) else {
+            skip("path points outside the instance");
+            continue;
+        };
+        let Some(url) = file.downloads.iter().find(|url| is_allowed_url(url)) else {
+            skip("no download from an allowed host");
+            continue;
+        };
+
+        let hashes = FileHashes {
+            sha1: file.hashes.get("sha1").cloned(),
+            sha512: file.hashes.get("sha512").cloned(),
+        };
+        downloads.push((url, instance_dir.join(relative_path), hashes));
+    }
+
+    // Files left by an earlier, interrupted install don't need downloading again
+    let targets = downloads
+        .iter()
+        .map(|(_, target, hashes)| (target.clone(), hashes.clone()))
+        .collect();
+    let outdated = verify_files(targets).await?;
+    info!(
+        "{} of {} modpack files are already up to date",
+        downloads.len() - outdated.len(),
+        downloads.len()
+    );
+    for index in outdated {
+        let (url, target, hashes) = &downloads[index];
+        download_file(&client, url, target, hashes).await?;
+    }
+
+    let archive_path = archive_path.to_path_buf();
+    let instance_dir = instance_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || extract_overrides(&archive_path, &instance_dir))
+        .await??;
+
+    info!(
+        "Installed modpack {} ({} files, {} skipped)",
+        index.name,
+        index.files.len(),
+        skipped.len()
+    );
+    Ok(skipped)
+}
+
+/// Check that a download URL uses HTTPS and one of the allowed hosts.
+fn is_allowed_url(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url
+                .host_str()
+                .is_some_and(|host| ALLOWED_HOSTS.contains(&host))
+    })
+}
+
+/// Convert a pack path to a relative path that stays inside the instance.
+fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        .then(|| path.to_path_buf())
+}
+
+/// Copy `overrides/` and then `client-overrides/` into the instance.
+fn extract_overrides(archive_path: &Path, instance_dir: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(File::open(archive_path)?)?;
+
+    for prefix in OVERRIDE_DIRECTORIES {
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let Some(relative_path) = entry
+                .name()
+                .strip_prefix(prefix)
+                .and_then(safe_relative_path)
+            else {
+                continue;
+            };
+
+            let target = instance_dir.join(relative_path);
+            if entry.is_dir() {
+                std::fs::create_dir_all(&target)?;
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            io::copy(&mut entry, &mut File::create(&target)?)?;
+            apply_unix_mode(&target, entry.unix_mode())?;
+        }
+    }
+
+    Ok(())
+}
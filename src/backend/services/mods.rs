@@ -0,0 +1,174 @@
+//! Installed mod metadata.
+
+use crate::backend::utils::archive::read_zip_entry;
+use anyhow::Result;
+use log::warn;
+use serde_json::Value;
+use std::path::Path;
+
+/// Suffix added to a mod jar to keep the game from loading it.
+pub const DISABLED_SUFFIX: &str = ".disabled";
+
+/// A mod jar in the `mods` folder of an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModInfo {
+    pub file_name: String,
+    /// Mod id from the jar metadata, or `None` if the jar has none we can read.
+    pub id: Option<String>,
+    /// Display name, falling back to the file name.
+    pub name: String,
+    pub version: Option<String>,
+    pub enabled: bool,
+}
+
+/// Metadata read from a mod jar.
+#[derive(Debug, Default)]
+struct ModMetadata {
+    id: Option<String>,
+    name: Option<String>,
+    version: Option<String>,
+}
+
+/// Check whether a file in the `mods` folder is a mod jar, enabled or not.
+#[must_use]
+pub fn is_mod_file(file_name: &str) -> bool {
+    let name = file_name.strip_suffix(DISABLED_SUFFIX).unwrap_or(file_name);
+    name.to_lowercase().ends_with(".jar")
+}
+
+/// Read the metadata of a mod jar.
+///
+/// Fabric, Quilt, Forge and NeoForge metadata are supported. Jars without
+/// readable metadata, including broken archives, are named after the file.
+pub fn read_mod_info(path: &Path) -> Result<ModInfo> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid mod path: {path:?}"))?
+        .to_string_lossy()
+        .to_string();
+    let enabled = !file_name.ends_with(DISABLED_SUFFIX);
+
+    let metadata = read_metadata(path).unwrap_or_else(|e| {
+        warn!("Failed to read mod metadata from {file_name}: {e}");
+        ModMetadata::default()
+    });
+    let name = metadata
+        .name
+        .or_else(|| metadata.id.clone())
+        .unwrap_or_else(|| {
+            file_name
+                .trim_end_matches(DISABLED_SUFFIX)
+                .trim_end_matches(".jar")
+                .to_string()
+        });
+
+    Ok(ModInfo {
+        file_name,
+        id: metadata.id,
+        name,
+        version: metadata.version,
+        enabled,
+    })
+}
+
+fn read_metadata(path: &Path) -> Result<ModMetadata> {
+    if let Some(json) = read_zip_entry(path, "fabric.mod.json")? {
+        return Ok(parse_fabric(&json));
+    }
+    if let Some(json) = read_zip_entry(path, "quilt.mod.json")? {
+        return Ok(parse_quilt(&json));
+    }
+    for entry in ["META-INF/mods.toml", "META-INF/neoforge.mods.toml"] {
+        if let Some(toml) = read_zip_entry(path, entry)? {
+            let mut metadata = parse_mods_toml(&toml);
+            // Forge fills the version from the jar manifest at load time
+            if metadata.version.as_deref() == Some("${file.jarVersion}") {
+                metadata.version = read_zip_entry(path, "META-INF/MANIFEST.MF")?
+                    .as_deref()
+                    .and_then(manifest_version);
+            }
+            return Ok(metadata);
+        }
+    }
+    Ok(ModMetadata::default())
+}
+
+fn json_string(value: &Value, pointer: &str) -> Option<String> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn parse_fabric(json: &str) -> ModMetadata {
+    let Ok(value) = serde_json::from_str::<Value>(json) else {
+        return ModMetadata::default();
+    };
+    ModMetadata {
+        id: json_string(&value, "/id"),
+        name: json_string(&value, "/name"),
+        version: json_string(&value, "/version"),
+    }
+}
+
+fn parse_quilt(json: &str) -> ModMetadata {
+    let Ok(value) = serde_json::from_str::<Value>(json) else {
+        return ModMetadata::default();
+    };
+    ModMetadata {
+        id: json_string(&value, "/quilt_loader/id"),
+        name: json_string(&value, "/quilt_loader/metadata/name"),
+        version: json_string(&value, "/quilt_loader/version"),
+    }
+}
+
+/// Read the first `[[mods]]` table of a Forge `mods.toml`.
+///
+/// Only the plain `key = "value"` lines needed for the mod list are understood.
+fn parse_mods_toml(toml: &str) -> ModMetadata {
+    let mut metadata = ModMetadata::default();
+    let mut in_mods = false;
+
+    for line in toml.lines().map(str::trim) {
+        if line.starts_with('[') {
+            if in_mods {
+                break;
+            }
+            in_mods = line == "[[mods]]";
+            continue;
+        }
+        if !in_mods {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let Some(value) = toml_string(value) else {
+            continue;
+        };
+        match key.trim() {
+            "modId" => metadata.id = Some(value),
+            "displayName" => metadata.name = Some(value),
+            "version" => metadata.version = Some(value),
+            _ => {}
+        }
+    }
+
+    metadata
+}
+
+/// Value of a single-line TOML string, ignoring any trailing comment.
+fn toml_string(value: &str) -> Option<String> {
+    let value = value.trim();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &value[1..];
+    rest.find(quote).map(|end| rest[..end].to_string())
+}
+
+fn manifest_version(manifest: &str) -> Option<String> {
+    manifest.lines().find_map(|line| {
+        line.strip_prefix("Implementation-Version:")
+            .map(|version| version.trim().to_string())
+    })
+}
@@ -0,0 +1,181 @@
+//! Minecraft news feed.
+
+use crate::backend::services::settings::LauncherSettings;
+use crate::backend::services::tracker::VisitTracker;
+use crate::backend::utils::http;
+use crate::backend::utils::paths::get_cache_dir;
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// News shown by the official launcher.
+const NEWS_URL: &str = "https://launchercontent.mojang.com/news.json";
+
+/// Images in the feed are relative to this host.
+const NEWS_CONTENT_HOST: &str = "https://launchercontent.mojang.com";
+
+/// How long cached news is shown before the feed is fetched again.
+const NEWS_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// A news article.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewsItem {
+    pub title: String,
+    /// Short summary of the article.
+    pub text: String,
+    /// Publication date as `YYYY-MM-DD`.
+    pub date: String,
+    pub image_url: Option<String>,
+    /// Full article on minecraft.net.
+    pub link: Option<String>,
+}
+
+/// News stored on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedNews {
+    /// Seconds since the Unix epoch when the feed was fetched.
+    fetched_at: u64,
+    items: Vec<NewsItem>,
+}
+
+impl CachedNews {
+    fn is_fresh(&self) -> bool {
+        VisitTracker::current_timestamp().saturating_sub(self.fetched_at) < NEWS_TTL.as_secs()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NewsFeed {
+    entries: Vec<NewsEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NewsEntry {
+    title: String,
+    #[serde(default)]
+    text: String,
+    date: String,
+    news_page_image: Option<NewsImage>,
+    play_page_image: Option<NewsImage>,
+    read_more_link: Option<String>,
+    #[serde(default)]
+    news_type: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewsImage {
+    url: String,
+}
+
+impl From<NewsEntry> for NewsItem {
+    fn from(entry: NewsEntry) -> Self {
+        let image_url = entry
+            .news_page_image
+            .or(entry.play_page_image)
+            .map(|image| match image.url.strip_prefix('/') {
+                Some(path) => format!("{NEWS_CONTENT_HOST}/{path}"),
+                None => image.url,
+            });
+        Self {
+            title: entry.title,
+            text: entry.text,
+            date: entry.date,
+            image_url,
+            link: entry.read_more_link,
+        }
+    }
+}
+
+/// Fetches Java Edition news and keeps a copy on disk for offline use.
+pub struct NewsService;
+
+impl NewsService {
+    /// The newest `limit` articles, newest first.
+    ///
+    /// Cached news is used while it is fresh, in offline mode, and when the feed can't be reached.
+    pub async fn get_news(limit: usize) -> Result<Vec<NewsItem>> {
+        let mut items = Self::load_news().await?;
+        items.truncate(limit);
+        Ok(items)
+    }
+
+    async fn load_news() -> Result<Vec<NewsItem>> {
+        let cached = load_cached_news().await;
+        if LauncherSettings::load().await.offline_mode {
+            return cached.map(|cached| cached.items).ok_or_else(|| {
+                anyhow::anyhow!("News is not cached, turn off offline mode to download it")
+            });
+        }
+        if let Some(cached) = cached.as_ref().filter(|cached| cached.is_fresh()) {
+            return Ok(cached.items.clone());
+        }
+
+        match Self::fetch_news().await {
+            Ok(items) => {
+                let cached = CachedNews {
+                    fetched_at: VisitTracker::current_timestamp(),
+                    items,
+                };
+                if let Err(e) = save_cached_news(&cached).await {
+                    warn!("Failed to save news cache: {e}");
+                }
+                Ok(cached.items)
+            }
+            Err(e) => {
+                let Some(cached) = cached else {
+                    return Err(e);
+                };
+                warn!("Failed to refresh news, using cached copy: {e}");
+                Ok(cached.items)
+            }
+        }
+    }
+
+    async fn fetch_news() -> Result<Vec<NewsItem>> {
+        let feed: NewsFeed = http::client()?
+            .get(NEWS_URL)
+            .header("User-Agent", "DreamLauncher")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let mut items: Vec<NewsItem> = feed
+            .entries
+            .into_iter()
+            .filter(|entry| entry.news_type.iter().any(|t| t == "Java"))
+            .map(NewsItem::from)
+            .collect();
+        // ISO dates sort chronologically as strings
+        items.sort_by(|a, b| b.date.cmp(&a.date));
+
+        info!("Fetched {} news articles", items.len());
+        Ok(items)
+    }
+}
+
+fn news_cache_path() -> PathBuf {
+    get_cache_dir()
+        .unwrap_or_else(|_| PathBuf::from("Dream Launcher/cache"))
+        .join("news.json")
+}
+
+async fn load_cached_news() -> Option<CachedNews> {
+    let json = tokio::fs::read_to_string(news_cache_path()).await.ok()?;
+    serde_json::from_str(&json)
+        .map_err(|e| warn!("Ignoring invalid news cache: {e}"))
+        .ok()
+}
+
+async fn save_cached_news(cached: &CachedNews) -> Result<()> {
+    let path = news_cache_path();
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, serde_json::to_string(cached)?).await?;
+    Ok(())
+}
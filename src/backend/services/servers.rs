@@ -0,0 +1,50 @@
+//! Multiplayer servers saved in the game.
+
+use crate::backend::utils::nbt::{self, Tag};
+use anyhow::Result;
+use std::path::Path;
+
+/// A server from the multiplayer screen of the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedServer {
+    pub name: String,
+    pub address: String,
+}
+
+/// Read the servers saved in a `servers.dat` file.
+///
+/// A missing file means no servers were added yet. Entries without an address are skipped.
+pub async fn read_servers_dat(path: &Path) -> Result<Vec<SavedServer>> {
+    let bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let root = nbt::read(&bytes)?;
+    let servers = root
+        .get("servers")
+        .and_then(Tag::as_list)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|server| {
+            let address = server.get("ip")?.as_str()?.trim();
+            if address.is_empty() {
+                return None;
+            }
+            let name = server
+                .get("name")
+                .and_then(Tag::as_str)
+                .filter(|name| !name.is_empty())
+                .unwrap_or(address);
+            Some(SavedServer {
+                name: name.to_string(),
+                address: address.to_string(),
+            })
+        })
+        .collect();
+    Ok(servers)
+}
@@ -0,0 +1,451 @@
+//! Global launcher settings.
+
+use crate::backend::services::cloud::{CloudConfig, CloudPassword};
+use crate::backend::services::launcher::VersionInfo;
+use crate::backend::utils::http::{
+    parse_proxy, set_download_mirror, set_fallback_mirrors, set_offline, set_proxy,
+};
+use crate::backend::utils::paths::get_launcher_dir;
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs as async_fs;
+
+/// Name of the settings file in the launcher directory.
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Smallest Java heap the game can be started with, in megabytes.
+pub const MIN_MEMORY_MB: u32 = 512;
+
+/// Initial Java heap size in megabytes, capped at the maximum heap.
+const INITIAL_MEMORY_MB: u32 = 1024;
+
+/// Accent color used until the user picks one, as hex without `#`.
+pub const DEFAULT_ACCENT_COLOR: &str = "38ff10";
+
+/// Color scheme of the launcher window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    /// Themes in the order the settings page cycles through them.
+    pub const ALL: [Self; 2] = [Self::Dark, Self::Light];
+
+    /// Human-readable theme name.
+    #[must_use]
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            Self::Dark => "Dark",
+            Self::Light => "Light",
+        }
+    }
+
+    /// Theme shown after this one in the settings page.
+    #[must_use]
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&t| t == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// CSS custom properties of the theme, as `(name, value)` pairs.
+    ///
+    /// Besides `--bg` and `--fg` this overrides the shared variables the stylesheets already use.
+    #[must_use]
+    pub const fn variables(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Dark => &[
+                ("--bg", "#1b1b1b"),
+                ("--fg", "#fff"),
+                ("--block-bg", "#141414"),
+                ("--card-bg", "#1b1b1b"),
+                ("--icon-bg", "#252525"),
+                ("--divider-bg", "#1b1b1b"),
+                ("--font-color", "#fff"),
+                ("--font-secondary", "#707070"),
+            ],
+            Self::Light => &[
+                ("--bg", "#f2f2f2"),
+                ("--fg", "#1b1b1b"),
+                ("--block-bg", "#fff"),
+                ("--card-bg", "#e8e8e8"),
+                ("--icon-bg", "#d6d6d6"),
+                ("--divider-bg", "#e0e0e0"),
+                ("--font-color", "#1b1b1b"),
+                ("--font-secondary", "#6a6a6a"),
+            ],
+        }
+    }
+}
+
+/// Kinds of versions shown in the version selector, remembered between launches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersionFilter {
+    pub releases: bool,
+    pub snapshots: bool,
+    pub old_betas: bool,
+    pub old_alphas: bool,
+}
+
+impl Default for VersionFilter {
+    fn default() -> Self {
+        Self {
+            releases: true,
+            snapshots: false,
+            old_betas: false,
+            old_alphas: false,
+        }
+    }
+}
+
+impl VersionFilter {
+    /// Whether a version passes the filter.
+    #[must_use]
+    pub fn matches(&self, version: &VersionInfo) -> bool {
+        match version.version_type.as_str() {
+            "release" => self.releases,
+            "snapshot" => self.snapshots,
+            "old_beta" => self.old_betas,
+            "old_alpha" => self.old_alphas,
+            _ => false,
+        }
+    }
+}
+
+/// Host that game files are downloaded from first.
+///
+/// Files from a mirror are still checked against the hashes in the official version data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadMirror {
+    /// Mojang's own hosts, with mirrors only used when they fail and fallback mirrors are on.
+    #[default]
+    Official,
+    /// BMCLAPI, which is faster where the official hosts are slow.
+    Bmclapi,
+}
+
+impl DownloadMirror {
+    /// Mirrors in the order the settings page cycles through them.
+    pub const ALL: [Self; 2] = [Self::Official, Self::Bmclapi];
+
+    /// Name the launcher script knows the mirror by.
+    #[must_use]
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::Official => "official",
+            Self::Bmclapi => "bmclapi",
+        }
+    }
+
+    /// Human-readable mirror name.
+    #[must_use]
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            Self::Official => "Official",
+            Self::Bmclapi => "BMCLAPI",
+        }
+    }
+
+    /// Mirror shown after this one in the settings page.
+    #[must_use]
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Garbage collector flags added to the JVM arguments of an instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JvmPreset {
+    /// No extra flags, the JVM picks its own collector.
+    #[default]
+    Default,
+    /// Aikar's tuned G1 flags, usable on every Java version the game runs on.
+    Aikar,
+    /// The Z garbage collector, which needs Java 17 or newer.
+    Zgc,
+    /// User supplied arguments, split like a shell command line.
+    Custom(String),
+}
+
+/// Aikar's G1 flags, see <https://docs.papermc.io/paper/aikars-flags>.
+const AIKAR_FLAGS: [&str; 18] = [
+    "-XX:+UseG1GC",
+    "-XX:+ParallelRefProcEnabled",
+    "-XX:MaxGCPauseMillis=200",
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+DisableExplicitGC",
+    "-XX:+AlwaysPreTouch",
+    "-XX:G1NewSizePercent=30",
+    "-XX:G1MaxNewSizePercent=40",
+    "-XX:G1HeapRegionSize=8M",
+    "-XX:G1ReservePercent=20",
+    "-XX:G1HeapWastePercent=5",
+    "-XX:G1MixedGCCountTarget=4",
+    "-XX:InitiatingHeapOccupancyPercent=15",
+    "-XX:G1MixedGCLiveThresholdPercent=90",
+    "-XX:G1RSetUpdatingPauseTimePercent=5",
+    "-XX:SurvivorRatio=32",
+    "-XX:+PerfDisableSharedMem",
+    "-XX:MaxTenuringThreshold=1",
+];
+
+impl JvmPreset {
+    /// Built-in presets shown in the settings dialog.
+    pub const BUILT_IN: [Self; 3] = [Self::Default, Self::Aikar, Self::Zgc];
+
+    /// Human-readable preset name.
+    #[must_use]
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::Aikar => "Aikar",
+            Self::Zgc => "ZGC",
+            Self::Custom(_) => "Custom",
+        }
+    }
+
+    /// Oldest Java major version the preset works with, if it has a minimum.
+    #[must_use]
+    pub const fn min_java_version(&self) -> Option<u32> {
+        match self {
+            Self::Zgc => Some(17),
+            Self::Default | Self::Aikar | Self::Custom(_) => None,
+        }
+    }
+
+    /// JVM arguments of the preset.
+    pub fn arguments(&self) -> Result<Vec<String>> {
+        match self {
+            Self::Default => Ok(Vec::new()),
+            Self::Aikar => Ok(AIKAR_FLAGS.iter().map(ToString::to_string).collect()),
+            Self::Zgc => Ok(vec!["-XX:+UseZGC".to_string()]),
+            Self::Custom(args) => split_arguments(args),
+        }
+    }
+}
+
+/// Launcher-wide settings.
+///
+/// Every field has a default so settings files written by older versions still load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LauncherSettings {
+    /// Maximum Java heap size in megabytes.
+    pub max_memory_mb: u32,
+    /// Java executable used instead of the one picked automatically.
+    pub java_path: Option<PathBuf>,
+    /// Launch only from local files and skip every network request.
+    pub offline_mode: bool,
+    /// Server that instance saves are synced with.
+    pub cloud: CloudConfig,
+    /// Color scheme of the launcher window.
+    pub theme: Theme,
+    /// Accent color as hex without `#`, like instance colors.
+    pub accent_color: String,
+    /// Version types last shown in the version selector.
+    pub version_filter: VersionFilter,
+    /// Proxy URL for every download, overriding the proxy environment variables.
+    pub proxy: Option<String>,
+    /// Host that game files are downloaded from first.
+    pub download_mirror: DownloadMirror,
+    /// Retry downloads on third-party mirrors when the official hosts fail.
+    pub fallback_mirrors: bool,
+    /// Launcher release tag the user chose not to be offered again.
+    pub skipped_update: Option<String>,
+}
+
+impl Default for LauncherSettings {
+    fn default() -> Self {
+        Self {
+            max_memory_mb: 2048,
+            java_path: None,
+            offline_mode: false,
+            cloud: CloudConfig::default(),
+            theme: Theme::default(),
+            accent_color: DEFAULT_ACCENT_COLOR.to_string(),
+            version_filter: VersionFilter::default(),
+            proxy: None,
+            download_mirror: DownloadMirror::default(),
+            fallback_mirrors: false,
+            skipped_update: None,
+        }
+    }
+}
+
+impl LauncherSettings {
+    /// Path of the settings file.
+    pub fn path() -> Result<PathBuf> {
+        Ok(get_launcher_dir()?.join(SETTINGS_FILE))
+    }
+
+    /// Load settings from disk, falling back to defaults when missing or invalid.
+    pub async fn load() -> Self {
+        let path = match Self::path() {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("Failed to locate settings file: {e}");
+                return Self::default();
+            }
+        };
+        let Ok(json) = async_fs::read_to_string(&path).await else {
+            return Self::default();
+        };
+
+        let mut settings: Self = serde_json::from_str(&json).unwrap_or_else(|e| {
+            warn!("Ignoring invalid settings file {}: {e}", path.display());
+            Self::default()
+        });
+        if !settings.cloud.password.is_empty() {
+            settings.move_cloud_password(&path).await;
+        }
+        // Settings are loaded before going online, so this keeps every client up to date
+        set_proxy(settings.proxy.clone());
+        set_download_mirror(settings.download_mirror.id());
+        set_fallback_mirrors(settings.fallback_mirrors);
+        set_offline(settings.offline_mode);
+        settings
+    }
+
+    /// Move the cloud password saved by older versions into the user config and
+    /// rewrite the settings without it.
+    async fn move_cloud_password(&mut self, path: &Path) {
+        let moved = match CloudPassword::new() {
+            Ok(passwords) => passwords.take_from(&mut self.cloud).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = moved {
+            warn!("Keeping the cloud password in the settings for now: {e}");
+            return;
+        }
+
+        info!("Moved the cloud password to the user config");
+        let written = match serde_json::to_string_pretty(self) {
+            Ok(json) => async_fs::write(path, json)
+                .await
+                .map_err(anyhow::Error::from),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = written {
+            warn!("Failed to remove the cloud password from the settings: {e}");
+        }
+    }
+
+    /// Save settings to disk.
+    pub async fn save(&self) -> Result<()> {
+        self.validate()?;
+
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            async_fs::create_dir_all(parent).await?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        async_fs::write(&path, json).await?;
+        set_proxy(self.proxy.clone());
+        set_download_mirror(self.download_mirror.id());
+        set_fallback_mirrors(self.fallback_mirrors);
+        set_offline(self.offline_mode);
+
+        info!("Saved launcher settings to {}", path.display());
+        Ok(())
+    }
+
+    /// Check that the settings can be used to launch the game.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_memory_mb < MIN_MEMORY_MB {
+            return Err(anyhow::anyhow!(
+                "Maximum memory must be at least {MIN_MEMORY_MB} MB"
+            ));
+        }
+        if let Some(java_path) = &self.java_path
+            && !java_path.is_file()
+        {
+            return Err(anyhow::anyhow!(
+                "Java executable not found: {}",
+                java_path.display()
+            ));
+        }
+        if self.accent_color.len() != 6 || !self.accent_color.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(anyhow::anyhow!(
+                "Accent color must be six hex digits: {}",
+                self.accent_color
+            ));
+        }
+        if let Some(proxy) = &self.proxy {
+            parse_proxy(proxy)?;
+        }
+        self.cloud.validate()
+    }
+
+    /// CSS that sets the theme and accent color as custom properties on `:root`.
+    #[must_use]
+    pub fn theme_css(&self) -> String {
+        let mut css = String::from(":root {\n");
+        for (name, value) in self.theme.variables() {
+            css.push_str(&format!("    {name}: {value};\n"));
+        }
+        css.push_str(&format!("    --accent: #{};\n", self.accent_color));
+        css.push('}');
+        css
+    }
+
+    /// JVM arguments for the configured heap size.
+    #[must_use]
+    pub fn jvm_arguments(&self) -> Vec<String> {
+        let max_memory = self.max_memory_mb.max(MIN_MEMORY_MB);
+        vec![
+            format!("-Xmx{max_memory}M"),
+            format!("-Xms{}M", INITIAL_MEMORY_MB.min(max_memory)),
+        ]
+    }
+}
+
+/// Split a command line into arguments, keeping quoted text together.
+///
+/// Single and double quotes group whitespace and are removed; there are no escapes.
+fn split_arguments(input: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut quote = None;
+
+    for c in input.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_argument = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_argument {
+                    args.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_argument = true;
+            }
+        }
+    }
+
+    if let Some(open) = quote {
+        return Err(anyhow::anyhow!(
+            "Unterminated {open} quote in JVM arguments"
+        ));
+    }
+    if in_argument {
+        args.push(current);
+    }
+    Ok(args)
+}
@@ -0,0 +1,122 @@
+//! Availability of the Mojang and Microsoft services the launcher depends on.
+
+use crate::backend::services::settings::LauncherSettings;
+use crate::backend::utils::http;
+use anyhow::Result;
+use futures_util::future::join_all;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a check is reused before the services are probed again.
+const STATUS_TTL: Duration = Duration::from_secs(60);
+
+/// How long a service may take to answer before it counts as down.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Availability of each service, as reported by one check.
+type ServiceStatus = HashMap<MojangService, bool>;
+
+/// Last check and when it was made.
+static LAST_STATUS: LazyLock<Mutex<Option<(Instant, ServiceStatus)>>> =
+    LazyLock::new(Default::default);
+
+/// A service the launcher needs to log in or download the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MojangService {
+    /// Joining online-mode servers.
+    Session,
+    /// Microsoft account login and Minecraft profiles.
+    Auth,
+    /// Game assets.
+    Resources,
+}
+
+impl MojangService {
+    pub const ALL: [Self; 3] = [Self::Session, Self::Auth, Self::Resources];
+
+    /// Human-readable service name.
+    #[must_use]
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            Self::Session => "Session",
+            Self::Auth => "Authentication",
+            Self::Resources => "Downloads",
+        }
+    }
+
+    /// Endpoint probed to check the service.
+    const fn url(self) -> &'static str {
+        match self {
+            Self::Session => "https://sessionserver.mojang.com",
+            Self::Auth => "https://api.minecraftservices.com",
+            Self::Resources => "https://resources.download.minecraft.net",
+        }
+    }
+}
+
+pub struct StatusService;
+
+impl StatusService {
+    /// Whether each service is available, checked at most once a minute.
+    ///
+    /// Nothing is probed in offline mode, so the map is empty then.
+    pub async fn check() -> HashMap<MojangService, bool> {
+        if LauncherSettings::load().await.offline_mode {
+            return HashMap::new();
+        }
+        if let Some(status) = LAST_STATUS.lock().ok().and_then(|last| {
+            last.as_ref()
+                .filter(|(checked_at, _)| checked_at.elapsed() < STATUS_TTL)
+                .map(|(_, status)| status.clone())
+        }) {
+            return status;
+        }
+
+        let results = join_all(MojangService::ALL.map(|service| async move {
+            let available = match Self::probe(service).await {
+                Ok(available) => available,
+                Err(e) => {
+                    warn!("{} service is unreachable: {e}", service.display_name());
+                    false
+                }
+            };
+            (service, available)
+        }))
+        .await;
+        let status: HashMap<_, _> = results.into_iter().collect();
+
+        if status.values().any(|available| !available) {
+            info!("Mojang services degraded: {status:?}");
+        }
+        if let Ok(mut last) = LAST_STATUS.lock() {
+            *last = Some((Instant::now(), status.clone()));
+        }
+        status
+    }
+
+    /// Services that are currently down.
+    pub async fn unavailable() -> Vec<MojangService> {
+        let status = Self::check().await;
+        MojangService::ALL
+            .into_iter()
+            .filter(|service| status.get(service) == Some(&false))
+            .collect()
+    }
+
+    /// Send a `HEAD` request to a service.
+    ///
+    /// Any answer below 500 means the service is up, since the probed
+    /// endpoints don't serve anything at their root.
+    async fn probe(service: MojangService) -> Result<bool> {
+        let response = http::client_builder()?
+            .timeout(PROBE_TIMEOUT)
+            .build()?
+            .head(service.url())
+            .header("User-Agent", "DreamLauncher")
+            .send()
+            .await?;
+        Ok(!response.status().is_server_error())
+    }
+}
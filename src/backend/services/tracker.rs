@@ -151,23 +151,28 @@ impl VisitTracker {
     }
 
     pub fn format_time_ago(timestamp: u64) -> String {
+        format!("Visited {}", Self::format_elapsed(timestamp))
+    }
+
+    /// Format the time elapsed since a timestamp, e.g. "2 hours ago".
+    pub fn format_elapsed(timestamp: u64) -> String {
         let now = Self::current_timestamp();
         let diff = now.saturating_sub(timestamp);
 
         match diff {
-            0..=59 => "Visited just now".to_string(),
-            60..=119 => "Visited 1 minute ago".to_string(),
-            120..=3599 => format!("Visited {} minutes ago", diff / 60),
-            3600..=7199 => "Visited 1 hour ago".to_string(),
-            7200..=86399 => format!("Visited {} hours ago", diff / 3600),
-            86400..=172_799 => "Visited 1 day ago".to_string(),
-            172_800..=2_591_999 => format!("Visited {} days ago", diff / 86400),
-            2_592_000..=5_183_999 => "Visited 1 month ago".to_string(),
-            _ => format!("Visited {} months ago", diff / 2_592_000),
+            0..=59 => "just now".to_string(),
+            60..=119 => "1 minute ago".to_string(),
+            120..=3599 => format!("{} minutes ago", diff / 60),
+            3600..=7199 => "1 hour ago".to_string(),
+            7200..=86399 => format!("{} hours ago", diff / 3600),
+            86400..=172_799 => "1 day ago".to_string(),
+            172_800..=2_591_999 => format!("{} days ago", diff / 86400),
+            2_592_000..=5_183_999 => "1 month ago".to_string(),
+            _ => format!("{} months ago", diff / 2_592_000),
         }
     }
 
-    fn current_timestamp() -> u64 {
+    pub fn current_timestamp() -> u64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
@@ -1,9 +1,24 @@
 //! Service that checks for updates and downloads them automatically.
 
+use crate::backend::utils::http;
 use log::{error, info, warn};
 use self_update::cargo_crate_version;
 use serde::Deserialize;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Marker in the release notes for an update that can't be skipped or postponed.
+const CRITICAL_MARKER: &str = "[critical]";
+
+/// Set when the user postpones an update, so it isn't offered again this session.
+static UPDATE_POSTPONED: AtomicBool = AtomicBool::new(false);
+
+/// What the user chose when offered an update.
+enum UpdateChoice {
+    Install,
+    Skip,
+    Later,
+}
 
 #[derive(Deserialize, Debug)]
 struct ReleaseAsset {
@@ -92,12 +107,14 @@ async fn download_file_to_disk(
     target_path: &std::path::Path,
     expected_sha256: Option<&str>,
 ) -> Result<(), String> {
+    use crate::backend::utils::download::ProgressTracker;
+    use crate::backend::utils::hex::decode as hex_decode;
     use crate::frontend::services::states::set_update_state;
     use futures_util::StreamExt;
     use sha2::{Digest, Sha256};
     use tokio::io::AsyncWriteExt;
 
-    let client = reqwest::Client::new();
+    let client = http::client().map_err(|e| format!("Failed to create HTTP client: {e}"))?;
     let response = client
         .get(url)
         .header("User-Agent", "DreamLauncher-Updater")
@@ -112,8 +129,7 @@ async fn download_file_to_disk(
         ));
     }
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded = 0u64;
+    let mut tracker = ProgressTracker::new(response.content_length());
     let mut stream = response.bytes_stream();
 
     // Create parent directory if it doesn't exist
@@ -144,14 +160,20 @@ async fn download_file_to_disk(
             h.update(&chunk);
         }
 
-        downloaded += chunk.len() as u64;
+        tracker.advance(chunk.len() as u64);
 
-        if total_size > 0 {
-            let progress = (downloaded as f32 / total_size as f32) * 100.0;
-            let status = format!("Downloading update... {progress:.1}%");
+        if let Some(progress) = tracker.percent() {
+            let status = format!(
+                "Downloading update... {progress:.1}% · {}",
+                tracker.describe()
+            );
             set_update_state(true, progress, status);
         } else {
-            let status = format!("Downloading update... {downloaded} bytes");
+            let status = format!(
+                "Downloading update... {} bytes · {}",
+                tracker.downloaded(),
+                tracker.describe()
+            );
             set_update_state(true, 0.0, status);
         }
     }
@@ -163,8 +185,12 @@ async fn download_file_to_disk(
 
     // Verify SHA256 if expected hash is provided
     if let (Some(expected), Some(hasher)) = (expected_sha256, hasher) {
-        let computed_hash = hex::encode(hasher.finalize());
-        if computed_hash != expected {
+        let computed = hasher.finalize();
+        let computed_hash = hex::encode(computed);
+        // Compare the raw bytes, so the case and padding of the published hash don't matter
+        let matches =
+            hex_decode(expected.trim()).is_ok_and(|expected| expected == computed.as_slice());
+        if !matches {
             // Remove the corrupted file
             let _ = tokio::fs::remove_file(target_path).await;
             return Err(format!(
@@ -665,9 +691,63 @@ async fn install_dmg(dmg_content: &[u8], version: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Check whether the release notes mark an update as critical.
+fn is_critical_release(release: &Release) -> bool {
+    release
+        .body
+        .as_deref()
+        .is_some_and(|body| body.to_lowercase().contains(CRITICAL_MARKER))
+}
+
+/// Ask the user whether to install, skip or postpone a new version.
+async fn ask_update_choice(version: &str) -> UpdateChoice {
+    const UPDATE: &str = "Update";
+    const SKIP: &str = "Skip this version";
+    const LATER: &str = "Later";
+
+    let result = rfd::AsyncMessageDialog::new()
+        .set_level(rfd::MessageLevel::Info)
+        .set_title("Update available")
+        .set_description(format!(
+            "Dream Launcher {version} is available. Install it now?"
+        ))
+        .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+            UPDATE.to_string(),
+            SKIP.to_string(),
+            LATER.to_string(),
+        ))
+        .show()
+        .await;
+    match result {
+        rfd::MessageDialogResult::Yes => UpdateChoice::Install,
+        rfd::MessageDialogResult::No => UpdateChoice::Skip,
+        rfd::MessageDialogResult::Custom(label) if label == UPDATE => UpdateChoice::Install,
+        rfd::MessageDialogResult::Custom(label) if label == SKIP => UpdateChoice::Skip,
+        _ => UpdateChoice::Later,
+    }
+}
+
+/// Remember a release tag so it isn't offered again.
+async fn skip_update(tag_name: &str) {
+    use crate::backend::services::settings::LauncherSettings;
+
+    let mut settings = LauncherSettings::load().await;
+    settings.skipped_update = Some(tag_name.to_string());
+    if let Err(e) = settings.save().await {
+        error!("Failed to save skipped update: {e}");
+    }
+}
+
 pub async fn check_for_updates() {
+    use crate::backend::services::settings::LauncherSettings;
     use crate::frontend::services::states::set_update_state;
 
+    let settings = LauncherSettings::load().await;
+    if settings.offline_mode {
+        info!("Offline mode is on, skipping update check");
+        return;
+    }
+
     info!("Checking for updates...");
     set_update_state(true, 0.0, "Checking for updates...".to_string());
 
@@ -675,7 +755,14 @@ pub async fn check_for_updates() {
 
     // Fetch the latest release info from GitHub
     set_update_state(true, 10.0, "Fetching release information...".to_string());
-    let client = reqwest::Client::new();
+    let client = match http::client() {
+        Ok(client) => client,
+        Err(e) => {
+            error!("Failed to create HTTP client: {e}");
+            set_update_state(false, 0.0, "Failed to check for updates.".to_string());
+            return;
+        }
+    };
     let response = match client
         .get("https://api.github.com/repos/FrogdreamStudios/launcher/releases/latest")
         .header("User-Agent", "DreamLauncher-Updater")
@@ -750,6 +837,34 @@ pub async fn check_for_updates() {
     }
 
     info!("New version available: {latest_version} (current: {current_version})");
+
+    if is_critical_release(&release) {
+        info!("Update {latest_version} is critical, installing without asking");
+    } else if settings.skipped_update.as_deref() == Some(release.tag_name.as_str()) {
+        info!("Update {latest_version} was skipped by the user");
+        set_update_state(false, 0.0, String::new());
+        return;
+    } else if UPDATE_POSTPONED.load(Ordering::Relaxed) {
+        info!("Update {latest_version} was postponed for this session");
+        set_update_state(false, 0.0, String::new());
+        return;
+    } else {
+        set_update_state(false, 0.0, String::new());
+        match ask_update_choice(latest_version).await {
+            UpdateChoice::Install => {}
+            UpdateChoice::Skip => {
+                info!("Skipping update {latest_version}");
+                skip_update(&release.tag_name).await;
+                return;
+            }
+            UpdateChoice::Later => {
+                info!("Postponing update {latest_version}");
+                UPDATE_POSTPONED.store(true, Ordering::Relaxed);
+                return;
+            }
+        }
+    }
+
     set_update_state(
         true,
         25.0,
@@ -1,8 +1,12 @@
 //! Application routing and page components.
 
 use crate::frontend::pages::auth::Auth;
+use crate::frontend::pages::cloud::Cloud as CloudPage;
 use crate::frontend::pages::main::Main;
+use crate::frontend::pages::mods::Mods as ModsPage;
 use crate::frontend::pages::new::New as NewPage;
+use crate::frontend::pages::packs::Packs as PacksPage;
+use crate::frontend::pages::screenshots::Screenshots as ScreenshotsPage;
 use crate::frontend::pages::settings::Settings as SettingsPage;
 use crate::frontend::services::chats::Chat;
 
@@ -16,7 +20,7 @@ pub fn Home() -> Element {
 
 #[component]
 pub fn Packs() -> Element {
-    rsx! { div {} }
+    rsx! { PacksPage {} }
 }
 
 #[component]
@@ -24,9 +28,19 @@ pub fn Settings() -> Element {
     rsx! { SettingsPage {} }
 }
 
+#[component]
+pub fn Screenshots(instance_id: u32) -> Element {
+    rsx! { ScreenshotsPage { instance_id } }
+}
+
+#[component]
+pub fn Mods(instance_id: u32) -> Element {
+    rsx! { ModsPage { instance_id } }
+}
+
 #[component]
 pub fn Cloud() -> Element {
-    rsx! { div {} }
+    rsx! { CloudPage {} }
 }
 
 #[component]
@@ -51,6 +65,12 @@ pub enum Route {
     /// Application settings page.
     #[route("/settings")]
     Settings {},
+    /// Screenshot gallery of an instance.
+    #[route("/screenshots/:instance_id")]
+    Screenshots { instance_id: u32 },
+    /// Installed mods of an instance.
+    #[route("/mods/:instance_id")]
+    Mods { instance_id: u32 },
     /// Cloud storage management page.
     #[route("/cloud")]
     Cloud {},
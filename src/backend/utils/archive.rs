@@ -0,0 +1,219 @@
+//! Archive utilities.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use log::warn;
+
+/// Magic number at the start of every ZIP local file header.
+const ZIP_MAGIC: [u8; 4] = [0x50, 0x4B, 0x03, 0x04];
+
+/// Check whether a file starts with the ZIP magic number.
+pub fn is_zip_archive(path: &Path) -> Result<bool> {
+    let mut header = [0u8; 4];
+    let mut file = File::open(path)?;
+    if file.read(&mut header)? < header.len() {
+        return Ok(false);
+    }
+    Ok(header == ZIP_MAGIC)
+}
+
+/// Check whether a ZIP archive contains an entry with the given name.
+pub fn zip_contains(path: &Path, entry: &str) -> Result<bool> {
+    let archive = zip::ZipArchive::new(File::open(path)?)?;
+    Ok(archive.file_names().any(|name| name == entry))
+}
+
+/// Read a text entry of a ZIP archive, or `None` if the archive has no such entry.
+pub fn read_zip_entry(path: &Path, entry: &str) -> Result<Option<String>> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    let mut file = match archive.by_name(entry) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(Some(contents))
+}
+
+/// Apply the Unix permissions stored in a ZIP entry to an extracted file.
+///
+/// Only the permission bits are kept and the owner can always read and write,
+/// so archives cannot produce setuid files or files the launcher cannot replace.
+/// Entries without a Unix mode, such as those zipped on Windows, are left alone.
+pub fn apply_unix_mode(path: &Path, mode: Option<u32>) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::Permissions::from_mode((mode & 0o777) | 0o600);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
+
+/// Recursively collect all files under a directory.
+pub async fn collect_files(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        if !dir.exists() {
+            continue;
+        }
+
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                pending.push(entry.path());
+            } else {
+                files.push(entry.path());
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Name of a file inside a ZIP archive, relative to `root` with forward slashes.
+fn zip_entry_name(root: &Path, file: &Path) -> Result<String> {
+    Ok(file
+        .strip_prefix(root)?
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Write files into a ZIP archive, naming them relative to `root`.
+pub fn write_zip(archive_path: &Path, root: &Path, files: &[PathBuf]) -> Result<()> {
+    write_zip_with_entries(archive_path, root, files, &[])
+}
+
+/// Like [`write_zip`], with `entries` of generated contents, such as a manifest, written first.
+pub fn write_zip_with_entries(
+    archive_path: &Path,
+    root: &Path,
+    files: &[PathBuf],
+    entries: &[(&str, &[u8])],
+) -> Result<()> {
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut writer = zip::ZipWriter::new(File::create(archive_path)?);
+
+    for (name, contents) in entries {
+        writer.start_file(*name, options)?;
+        writer.write_all(contents)?;
+    }
+    for file in files {
+        // Keep permissions such as the executable bit of bundled helper scripts
+        #[cfg(unix)]
+        let options = {
+            use std::os::unix::fs::PermissionsExt;
+            options.unix_permissions(std::fs::metadata(file)?.permissions().mode())
+        };
+        writer.start_file(zip_entry_name(root, file)?, options)?;
+        std::io::copy(&mut File::open(file)?, &mut writer)?;
+    }
+
+    writer.finish()?;
+    Ok(())
+}
+
+/// Extract a ZIP archive into a directory, skipping entries that would escape it.
+pub fn extract_zip(archive_path: &Path, dest: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(File::open(archive_path)?)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative_path) = entry.enclosed_name() else {
+            warn!("Skipping unsafe archive entry: {}", entry.name());
+            continue;
+        };
+
+        let target = dest.join(relative_path);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut File::create(&target)?)?;
+        apply_unix_mode(&target, entry.unix_mode())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_entries_outside_the_target() {
+        let root =
+            std::env::temp_dir().join(format!("dream-launcher-unsafe-zip-{}", std::process::id()));
+        let dest = root.join("dest");
+        std::fs::create_dir_all(&dest).unwrap();
+
+        let archive = root.join("archive.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        for (name, contents) in [("../evil.txt", "evil"), ("mods/good.txt", "good")] {
+            writer
+                .start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        extract_zip(&archive, &dest).unwrap();
+
+        assert!(!root.join("evil.txt").exists());
+        assert!(!dest.join("evil.txt").exists());
+        assert_eq!(
+            std::fs::read_to_string(dest.join("mods/good.txt")).unwrap(),
+            "good"
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_executable_bit_through_zip_round_trip() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("dream-launcher-zip-{}", std::process::id()));
+        let source = root.join("source");
+        let dest = root.join("dest");
+        std::fs::create_dir_all(&source).unwrap();
+
+        let script = source.join("run.sh");
+        let text = source.join("notes.txt");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        std::fs::write(&text, "notes").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::set_permissions(&text, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let archive = root.join("archive.zip");
+        write_zip(&archive, &source, &[script, text]).unwrap();
+        extract_zip(&archive, &dest).unwrap();
+
+        let mode = |name: &str| {
+            std::fs::metadata(dest.join(name))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777
+        };
+        assert_eq!(mode("run.sh"), 0o755);
+        assert_eq!(mode("notes.txt"), 0o644);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+//! Recognition of common causes of game crashes.
+
+/// Log text of a known crash cause and the hint shown for it.
+const SIGNATURES: [(&str, &str); 7] = [
+    (
+        "java.lang.OutOfMemoryError",
+        "Out of memory, increase Memory in Settings",
+    ),
+    (
+        "no lwjgl in java.library.path",
+        "Native libraries are missing, repair the instance",
+    ),
+    (
+        "Failed to locate library: lwjgl",
+        "Native libraries are missing, repair the instance",
+    ),
+    (
+        "GLFW error 65542",
+        "OpenGL is not supported, update your graphics drivers",
+    ),
+    (
+        "GLFW error 65543",
+        "OpenGL is not supported, update your graphics drivers",
+    ),
+    (
+        "Pixel format not accelerated",
+        "OpenGL is not supported, update your graphics drivers",
+    ),
+    (
+        "Failed to create OpenGL context",
+        "OpenGL is not supported, update your graphics drivers",
+    ),
+];
+
+/// Class file version of Java 8; each later Java release adds one.
+const JAVA_8_CLASS_VERSION: u32 = 52;
+
+/// Hint for the first known crash cause found in the last lines a game logged.
+#[must_use]
+pub fn diagnose_crash<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    for line in lines {
+        if line.contains("UnsupportedClassVersionError") {
+            return Some(match required_java_version(line) {
+                Some(java) => format!("This version needs Java {java}+"),
+                None => "This version needs a newer Java".to_string(),
+            });
+        }
+        if let Some((_, hint)) = SIGNATURES
+            .iter()
+            .find(|(signature, _)| line.contains(signature))
+        {
+            return Some((*hint).to_string());
+        }
+    }
+    None
+}
+
+/// Java release of the class file version in an `UnsupportedClassVersionError`,
+/// e.g. "compiled by a more recent version of the Java Runtime (class file version 65.0)".
+fn required_java_version(line: &str) -> Option<u32> {
+    let (_, rest) = line.split_once("class file version ")?;
+    let version: u32 = rest
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()?;
+    version
+        .checked_sub(JAVA_8_CLASS_VERSION)
+        .map(|newer| 8 + newer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_every_signature() {
+        for (signature, hint) in SIGNATURES {
+            let line = format!("[12:34:56] [Render thread/ERROR]: {signature} (details)");
+            assert_eq!(diagnose_crash([line.as_str()]).as_deref(), Some(hint));
+        }
+    }
+
+    #[test]
+    fn maps_class_file_version_to_java_release() {
+        let line = |version: &str| {
+            format!(
+                "java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been \
+                 compiled by a more recent version of the Java Runtime (class file version {version}), \
+                 this version of the Java Runtime only recognizes class file versions up to 52.0"
+            )
+        };
+        for (version, java) in [("52.0", 8), ("60.0", 16), ("61.0", 17), ("65.0", 21)] {
+            assert_eq!(
+                diagnose_crash([line(version).as_str()]),
+                Some(format!("This version needs Java {java}+"))
+            );
+        }
+    }
+
+    #[test]
+    fn falls_back_without_class_file_version() {
+        let line = "java.lang.UnsupportedClassVersionError: Unsupported major.minor version 52.0";
+        assert_eq!(
+            diagnose_crash([line]).as_deref(),
+            Some("This version needs a newer Java")
+        );
+        assert_eq!(required_java_version("class file version 45.0"), None);
+    }
+
+    #[test]
+    fn returns_first_known_cause() {
+        let lines = [
+            "[12:34:56] [main/INFO]: Loading",
+            "GLFW error 65542: WGL: The driver does not appear to support OpenGL",
+            "java.lang.OutOfMemoryError: Java heap space",
+        ];
+        assert_eq!(
+            diagnose_crash(lines).as_deref(),
+            Some("OpenGL is not supported, update your graphics drivers")
+        );
+        assert_eq!(diagnose_crash(["[12:34:56] [main/INFO]: Stopping!"]), None);
+    }
+}
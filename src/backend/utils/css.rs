@@ -46,8 +46,40 @@ const ASSETS: &[(&str, &[u8])] = &[
     ),
     embed_asset!("curseforge_icon", "assets/images/other/curseforge.png"),
     embed_asset!("namemc_icon", "assets/images/other/namemc.png"),
+    embed_asset!("steve", "assets/images/other/steve.png"),
 ];
 
+/// Styles of the main window, in the order they are combined.
+const MAIN_STYLES: &[&str] = &[
+    "base",
+    "animations",
+    "logo",
+    "navigation",
+    "chat",
+    "home",
+    "news",
+    "context_menu",
+    "debug",
+    "crash_banner",
+    "settings",
+    "progress",
+    "new",
+    "packs",
+    "screenshots",
+    "mods",
+    "cloud",
+    "browser",
+    "error_message",
+    "version_selector",
+    "rename_dialog",
+    "instance_settings",
+    "titlebar",
+    "tailwind",
+];
+
+/// Styles of the authentication window.
+const AUTH_STYLES: &[&str] = &["auth", "titlebar"];
+
 pub struct ResourceLoader;
 
 // Include generated font constants
@@ -90,9 +122,14 @@ impl ResourceLoader {
         style!("news", "/assets/styles/components/news.css");
         style!("context_menu", "/assets/styles/components/context_menu.css");
         style!("debug", "/assets/styles/components/debug.css");
+        style!("crash_banner", "/assets/styles/components/crash_banner.css");
         style!("settings", "/assets/styles/components/settings.css");
         style!("progress", "/assets/styles/components/progress.css");
         style!("new", "/assets/styles/components/new.css");
+        style!("packs", "/assets/styles/components/packs.css");
+        style!("screenshots", "/assets/styles/components/screenshots.css");
+        style!("mods", "/assets/styles/components/mods.css");
+        style!("cloud", "/assets/styles/components/cloud.css");
         style!("browser", "/assets/styles/components/browser.css");
         style!(
             "error_message",
@@ -106,6 +143,10 @@ impl ResourceLoader {
             "rename_dialog",
             "/assets/styles/components/rename_dialog.css"
         );
+        style!(
+            "instance_settings",
+            "/assets/styles/components/instance_settings.css"
+        );
         style!("titlebar", "/assets/styles/components/titlebar.css");
         m
     }
@@ -130,26 +171,39 @@ impl ResourceLoader {
 
     #[must_use]
     pub fn get_combined_main_css() -> String {
-        Self::combine_css(&[
-            "base",
-            "animations",
-            "logo",
-            "navigation",
-            "chat",
-            "home",
-            "news",
-            "context_menu",
-            "debug",
-            "settings",
-            "progress",
-            "new",
-            "browser",
-            "error_message",
-            "version_selector",
-            "rename_dialog",
-            "titlebar",
-            "tailwind",
-        ])
+        Self::combine_css(MAIN_STYLES)
+    }
+
+    /// Check that every embedded image is a valid PNG and every style in use is embedded and not empty.
+    ///
+    /// Returns the names of the broken assets and styles.
+    pub fn validate() -> Result<(), Vec<&'static str>> {
+        let styles = CSS_CACHE.get_or_init(Self::get_all_styles);
+        let mut broken: Vec<&'static str> = ASSETS
+            .iter()
+            .filter(|(_, bytes)| !is_valid_png(bytes))
+            .map(|&(name, _)| name)
+            .collect();
+        broken.extend(
+            styles
+                .iter()
+                .filter(|(_, css)| css.trim().is_empty())
+                .map(|(&name, _)| name),
+        );
+        broken.extend(
+            MAIN_STYLES
+                .iter()
+                .chain(AUTH_STYLES)
+                .filter(|name| !styles.contains_key(*name)),
+        );
+
+        if broken.is_empty() {
+            Ok(())
+        } else {
+            broken.sort_unstable();
+            broken.dedup();
+            Err(broken)
+        }
     }
 
     fn get_all_fonts() -> HashMap<&'static str, String> {
@@ -212,14 +266,18 @@ impl ResourceLoader {
             Self::get_font("gilroy_bold")
         );
 
-        format!(
-            "{}\n{}",
-            fonts_css,
-            Self::combine_css(&["auth", "titlebar"])
-        )
+        format!("{}\n{}", fonts_css, Self::combine_css(AUTH_STYLES))
     }
 }
 
+fn is_valid_png(bytes: &[u8]) -> bool {
+    let Ok(mut reader) = png::Decoder::new(bytes).read_info() else {
+        return false;
+    };
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    reader.next_frame(&mut buffer).is_ok()
+}
+
 #[macro_export]
 macro_rules! include_styles {
     ($($style:expr),*) => {
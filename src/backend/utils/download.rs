@@ -0,0 +1,743 @@
+//! File download utilities.
+
+use std::collections::VecDeque;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use crate::backend::utils::error::{ErrorKind, TaggedError};
+use crate::backend::utils::hex::hashes_equal;
+use anyhow::Result;
+use futures_util::StreamExt;
+use log::warn;
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_RANGE, RANGE, RETRY_AFTER};
+use sha1::Digest as _;
+use sha2::Digest as _;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::broadcast;
+
+/// Times a failed download is retried by [`download_file`].
+pub const DOWNLOAD_RETRIES: u32 = 3;
+
+/// Wait before the first retry, doubled for every further one.
+#[cfg(not(test))]
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+#[cfg(test)]
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(10);
+
+/// Longest wait between retries, also applied to a server's `Retry-After`.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Events kept for subscribers that fall behind.
+const EVENT_CAPACITY: usize = 256;
+
+static EVENTS: LazyLock<broadcast::Sender<DownloadEvent>> =
+    LazyLock::new(|| broadcast::channel(EVENT_CAPACITY).0);
+
+/// Progress of a file download, sent to every [`subscribe`]r.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadEvent {
+    Started {
+        url: String,
+        total: Option<u64>,
+    },
+    /// Bytes of the file received so far, including any resumed part.
+    Progress {
+        url: String,
+        done: u64,
+        total: Option<u64>,
+    },
+    Completed {
+        url: String,
+    },
+    Failed {
+        url: String,
+        error: String,
+    },
+    /// Game files installed by the launcher script so far, counted in files.
+    InstallProgress {
+        /// What the script is doing, like "Download Assets".
+        status: Option<String>,
+        done: u64,
+        total: Option<u64>,
+    },
+}
+
+/// Receive the events of every download started from now on.
+///
+/// Subscribers that fall behind by more than `EVENT_CAPACITY` events miss the oldest ones.
+#[must_use]
+pub fn subscribe() -> broadcast::Receiver<DownloadEvent> {
+    EVENTS.subscribe()
+}
+
+/// Send an event to every subscriber.
+pub fn emit(event: DownloadEvent) {
+    // Nobody listening is fine
+    let _ = EVENTS.send(event);
+}
+
+/// Expected hashes of a downloaded file, as lowercase hex strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileHashes {
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
+}
+
+/// Download a file to disk, verifying it against the expected hashes.
+///
+/// The file is written next to the target and only moved into place once
+/// every provided hash matches, so a failed download never leaves a corrupt file.
+///
+/// An interrupted download leaves its `.part` file behind, and the next attempt
+/// resumes it with a range request. Servers that ignore the range send the whole
+/// file again, which then replaces the partial one. Downloads without an expected
+/// hash always start over, since nothing would catch a part of an older file.
+///
+/// Progress is reported to [`subscribe`]rs as [`DownloadEvent`]s.
+///
+/// Network errors and server errors are retried up to [`DOWNLOAD_RETRIES`] times,
+/// and a file that fails verification is downloaded once more.
+pub async fn download_file(
+    client: &reqwest::Client,
+    url: &str,
+    target_path: &Path,
+    hashes: &FileHashes,
+) -> Result<()> {
+    download_file_with_retries(client, url, target_path, hashes, DOWNLOAD_RETRIES).await
+}
+
+/// Like [`download_file`], retrying a failed download up to `retries` times.
+///
+/// Retries wait with exponential backoff, or as long as a `429 Too Many Requests`
+/// response asks, and continue from the partial file. A hash mismatch on the first
+/// attempt deletes the partial file and downloads it once more from the start, in
+/// case the file was truncated or resumed from an older part. Errors that would
+/// fail again, like `404 Not Found` or a second hash mismatch, are returned immediately.
+pub async fn download_file_with_retries(
+    client: &reqwest::Client,
+    url: &str,
+    target_path: &Path,
+    hashes: &FileHashes,
+    retries: u32,
+) -> Result<()> {
+    let mut attempt = 0;
+    let result = loop {
+        let result = fetch_file(client, url, target_path, hashes).await;
+        let Err(e) = &result else {
+            break result;
+        };
+        let Some(delay) = retry_delay(e, attempt).filter(|_| attempt < retries) else {
+            break result;
+        };
+        attempt += 1;
+        warn!("Download of {url} failed, retrying in {delay:?} ({attempt}/{retries}): {e}");
+        tokio::time::sleep(delay).await;
+    };
+    emit(match &result {
+        Ok(()) => DownloadEvent::Completed {
+            url: url.to_string(),
+        },
+        Err(e) => DownloadEvent::Failed {
+            url: url.to_string(),
+            error: e.to_string(),
+        },
+    });
+    result
+}
+
+async fn fetch_file(
+    client: &reqwest::Client,
+    url: &str,
+    target_path: &Path,
+    hashes: &FileHashes,
+) -> Result<()> {
+    if let Some(parent) = target_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let partial_path = partial_path(target_path);
+    let verified = hashes.sha1.is_some() || hashes.sha512.is_some();
+    let resume_from = if verified {
+        tokio::fs::metadata(&partial_path)
+            .await
+            .map_or(0, |metadata| metadata.len())
+    } else {
+        0
+    };
+
+    let mut request = client.get(url).header("User-Agent", "DreamLauncher");
+    if resume_from > 0 {
+        request = request.header(RANGE, format!("bytes={resume_from}-"));
+    }
+    let mut response = request.send().await?;
+
+    // Start over when the partial file is already as long as the server's file,
+    // or the server answers with some other range
+    if resume_from > 0
+        && (response.status() == StatusCode::RANGE_NOT_SATISFIABLE
+            || (response.status() == StatusCode::PARTIAL_CONTENT
+                && !resumes_at(&response, resume_from)))
+    {
+        response = client
+            .get(url)
+            .header("User-Agent", "DreamLauncher")
+            .send()
+            .await?;
+    }
+
+    if !response.status().is_success() {
+        return Err(StatusError {
+            url: url.to_string(),
+            status: response.status(),
+            retry_after: retry_after(&response),
+        }
+        .into());
+    }
+
+    let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+    let mut done = if resumed { resume_from } else { 0 };
+    let total = response.content_length().map(|length| done + length);
+    emit(DownloadEvent::Started {
+        url: url.to_string(),
+        total,
+    });
+
+    let mut sha1 = sha1::Sha1::new();
+    let mut sha512 = sha2::Sha512::new();
+    let mut file = if resumed {
+        // Hash what is already on disk so the final hashes cover the whole file
+        let mut existing = tokio::fs::File::open(&partial_path).await?;
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read = existing.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            sha1.update(&buffer[..read]);
+            sha512.update(&buffer[..read]);
+        }
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&partial_path)
+            .await?
+    } else {
+        tokio::fs::File::create(&partial_path).await?
+    };
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk).await?;
+        sha1.update(&chunk);
+        sha512.update(&chunk);
+        done += chunk.len() as u64;
+        emit(DownloadEvent::Progress {
+            url: url.to_string(),
+            done,
+            total,
+        });
+    }
+
+    file.flush().await?;
+    drop(file);
+
+    let checks = [
+        ("SHA1", hashes.sha1.as_deref(), hex::encode(sha1.finalize())),
+        ("SHA512", hashes.sha512.as_deref(), hex::encode(sha512.finalize())),
+    ];
+    for (algorithm, expected, computed) in checks {
+        if let Some(expected) = expected
+            && !hashes_equal(expected, &computed)
+        {
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            return Err(TaggedError::new(
+                ErrorKind::Verification,
+                format!(
+                    "{algorithm} verification failed for {url}. Expected: {expected}, Got: {computed}"
+                ),
+            )
+            .into());
+        }
+    }
+
+    tokio::fs::rename(&partial_path, target_path).await?;
+    Ok(())
+}
+
+/// Where a download is written until it is verified, like `client.jar.part`.
+fn partial_path(target_path: &Path) -> PathBuf {
+    let mut name = target_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    target_path.with_file_name(name)
+}
+
+/// A download answered with an unsuccessful HTTP status.
+#[derive(Debug)]
+pub struct StatusError {
+    pub url: String,
+    pub status: StatusCode,
+    retry_after: Option<Duration>,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Download of {} failed with status: {}",
+            self.url, self.status
+        )
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// How long to wait before retrying after `error`, or `None` if retrying can't help.
+fn retry_delay(error: &anyhow::Error, attempt: u32) -> Option<Duration> {
+    let backoff = RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_RETRY_DELAY);
+    if let Some(e) = error.downcast_ref::<StatusError>() {
+        let retryable = e.status == StatusCode::TOO_MANY_REQUESTS
+            || e.status == StatusCode::REQUEST_TIMEOUT
+            || e.status.is_server_error();
+        return retryable.then(|| e.retry_after.unwrap_or(backoff).min(MAX_RETRY_DELAY));
+    }
+    // The corrupt file was deleted, so a fresh download can still match
+    if let Some(e) = error.downcast_ref::<TaggedError>() {
+        return (e.kind == ErrorKind::Verification && attempt == 0).then_some(backoff);
+    }
+    // Connection problems and streams that broke off midway
+    error.downcast_ref::<reqwest::Error>().map(|_| backoff)
+}
+
+/// `Retry-After` of a response in seconds. The HTTP date form is rare for downloads and ignored.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Find which files are missing or don't match their expected hashes.
+///
+/// Files are hashed on the blocking thread pool, at most one per CPU core at a
+/// time so huge file sets don't run out of file descriptors. Returns the indices
+/// of the files that need to be downloaded again, in ascending order.
+pub async fn verify_files(files: Vec<(PathBuf, FileHashes)>) -> Result<Vec<usize>> {
+    let concurrency = std::thread::available_parallelism().map_or(4, usize::from);
+
+    let results: Vec<(usize, bool)> = futures_util::stream::iter(files.into_iter().enumerate())
+        .map(|(index, (path, hashes))| async move {
+            let valid = tokio::task::spawn_blocking(move || file_matches(&path, &hashes)).await?;
+            Ok::<_, anyhow::Error>((index, valid))
+        })
+        .buffer_unordered(concurrency)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_>>()?;
+
+    let mut invalid: Vec<usize> = results
+        .into_iter()
+        .filter_map(|(index, valid)| (!valid).then_some(index))
+        .collect();
+    invalid.sort_unstable();
+    Ok(invalid)
+}
+
+/// Check that a file exists and matches every expected hash.
+///
+/// A file without any expected hash can't be verified and never matches.
+fn file_matches(path: &Path, hashes: &FileHashes) -> bool {
+    if hashes.sha1.is_none() && hashes.sha512.is_none() {
+        return false;
+    }
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return false;
+    };
+
+    let mut sha1 = sha1::Sha1::new();
+    let mut sha512 = sha2::Sha512::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => {
+                if hashes.sha1.is_some() {
+                    sha1.update(&buffer[..read]);
+                }
+                if hashes.sha512.is_some() {
+                    sha512.update(&buffer[..read]);
+                }
+            }
+            Err(_) => return false,
+        }
+    }
+
+    let matches = |expected: Option<&str>, computed: String| {
+        expected.is_none_or(|expected| hashes_equal(expected, &computed))
+    };
+    matches(hashes.sha1.as_deref(), hex::encode(sha1.finalize()))
+        && matches(hashes.sha512.as_deref(), hex::encode(sha512.finalize()))
+}
+
+/// Whether a response continues a file at byte `offset`, rather than sending all of it.
+fn resumes_at(response: &reqwest::Response, offset: u64) -> bool {
+    response.status() == StatusCode::PARTIAL_CONTENT
+        && response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|range| range.to_str().ok())
+            .and_then(|range| range.strip_prefix("bytes "))
+            .and_then(|range| range.split_once('-'))
+            .and_then(|(start, _)| start.parse::<u64>().ok())
+            == Some(offset)
+}
+
+/// How far back download speed is measured.
+const SPEED_WINDOW: Duration = Duration::from_secs(5);
+
+/// Tracks the progress of a download and estimates its speed and remaining time.
+///
+/// Speed is averaged over the last few seconds so it does not jump between chunks.
+#[derive(Debug, Clone)]
+pub struct ProgressTracker {
+    total: Option<u64>,
+    downloaded: u64,
+    /// Recent `(time, downloaded)` samples, oldest first.
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl ProgressTracker {
+    /// Start tracking a download of `total` bytes, if the size is known.
+    ///
+    /// Each download phase gets its own tracker so speed never carries over between phases.
+    #[must_use]
+    pub fn new(total: Option<u64>) -> Self {
+        let mut samples = VecDeque::new();
+        samples.push_back((Instant::now(), 0));
+        Self {
+            total,
+            downloaded: 0,
+            samples,
+        }
+    }
+
+    /// Record newly downloaded bytes.
+    pub fn advance(&mut self, bytes: u64) {
+        self.downloaded += bytes;
+        let now = Instant::now();
+        self.samples.push_back((now, self.downloaded));
+        // Keep one sample older than the window so the whole window is covered
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|(time, _)| now.duration_since(*time) > SPEED_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Bytes downloaded so far.
+    #[must_use]
+    pub const fn downloaded(&self) -> u64 {
+        self.downloaded
+    }
+
+    /// Completed percentage, if the total size is known.
+    #[must_use]
+    pub fn percent(&self) -> Option<f32> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (self.downloaded as f32 / total as f32) * 100.0)
+    }
+
+    /// Download speed in bytes per second.
+    #[must_use]
+    pub fn speed(&self) -> f64 {
+        let (Some((start, start_bytes)), Some((end, end_bytes))) =
+            (self.samples.front(), self.samples.back())
+        else {
+            return 0.0;
+        };
+        let elapsed = end.duration_since(*start).as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        (end_bytes - start_bytes) as f64 / elapsed
+    }
+
+    /// Estimated time until the download finishes, if the total size is known.
+    #[must_use]
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total?.saturating_sub(self.downloaded);
+        let speed = self.speed();
+        (speed > 0.0).then(|| Duration::from_secs_f64(remaining as f64 / speed))
+    }
+
+    /// Speed and remaining time for display, like `12.3 MB/s, 45s remaining`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let speed = format!("{:.1} MB/s", self.speed() / 1_000_000.0);
+        match self.eta() {
+            Some(eta) => format!("{speed}, {} remaining", format_duration(eta)),
+            None => speed,
+        }
+    }
+}
+
+/// Format a duration as `45s` or `3m 05s`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{secs}s")
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A canned HTTP response of the test server.
+    struct Reply {
+        status: &'static str,
+        headers: Vec<(&'static str, String)>,
+        body: Vec<u8>,
+    }
+
+    impl Reply {
+        fn new(status: &'static str, body: &[u8]) -> Self {
+            Self {
+                status,
+                headers: Vec::new(),
+                body: body.to_vec(),
+            }
+        }
+    }
+
+    /// Answer one request per connection with `replies` in order, recording the request heads.
+    async fn serve(replies: Vec<Reply>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/file.jar", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            for reply in replies {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut head = Vec::new();
+                let mut byte = [0; 1];
+                while !head.ends_with(b"\r\n\r\n") && stream.read(&mut byte).await.unwrap() > 0 {
+                    head.push(byte[0]);
+                }
+                seen.lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&head).to_lowercase());
+
+                let mut response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    reply.status,
+                    reply.body.len()
+                );
+                for (name, value) in &reply.headers {
+                    response.push_str(&format!("{name}: {value}\r\n"));
+                }
+                response.push_str("\r\n");
+                stream.write_all(response.as_bytes()).await.unwrap();
+                stream.write_all(&reply.body).await.unwrap();
+                stream.shutdown().await.unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dream-launcher-{name}-{}", std::process::id()))
+    }
+
+    fn sha1_hashes(contents: &[u8]) -> FileHashes {
+        FileHashes {
+            sha1: Some(hex::encode(sha1::Sha1::digest(contents))),
+            sha512: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn resumes_a_partial_download() {
+        let mut resumed = Reply::new("206 Partial Content", b"jar");
+        resumed
+            .headers
+            .push(("Content-Range", "bytes 5-7/8".to_string()));
+        let (url, requests) = serve(vec![resumed]).await;
+        let dir = temp_dir("download-resume");
+        let target = dir.join("client.jar");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("client.jar.part"), b"game ").unwrap();
+
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &target,
+            &sha1_hashes(b"game jar"),
+        )
+        .await
+        .unwrap();
+
+        assert!(requests.lock().unwrap()[0].contains("range: bytes=5-"));
+        assert_eq!(std::fs::read(&target).unwrap(), b"game jar");
+        assert!(!dir.join("client.jar.part").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn does_not_resume_downloads_without_hashes() {
+        let (url, requests) = serve(vec![Reply::new("200 OK", b"new saves")]).await;
+        let dir = temp_dir("download-unverified");
+        let target = dir.join("saves.zip");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("saves.zip.part"), b"old").unwrap();
+
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &target,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap();
+
+        assert!(!requests.lock().unwrap()[0].contains("range:"));
+        assert_eq!(std::fs::read(&target).unwrap(), b"new saves");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn partial_files_keep_the_extension() {
+        let dir = Path::new("versions");
+        assert_ne!(
+            partial_path(&dir.join("1.20.1.jar")),
+            partial_path(&dir.join("1.20.1.json"))
+        );
+        assert_eq!(
+            partial_path(&dir.join("1.20.1.jar")),
+            dir.join("1.20.1.jar.part")
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_the_download_succeeds() {
+        let (url, requests) = serve(vec![
+            Reply::new("503 Service Unavailable", b""),
+            Reply::new("503 Service Unavailable", b""),
+            Reply::new("200 OK", b"game jar"),
+        ])
+        .await;
+        let dir = temp_dir("download-retry");
+        let target = dir.join("client.jar");
+
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &target,
+            &sha1_hashes(b"game jar"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(std::fs::read(&target).unwrap(), b"game jar");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn downloads_a_corrupt_file_again_once() {
+        let (url, requests) = serve(vec![
+            Reply::new("200 OK", b"game"),
+            Reply::new("200 OK", b"game jar"),
+        ])
+        .await;
+        let dir = temp_dir("download-corrupt");
+        let target = dir.join("client.jar");
+
+        download_file(
+            &reqwest::Client::new(),
+            &url,
+            &target,
+            &sha1_hashes(b"game jar"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert_eq!(std::fs::read(&target).unwrap(), b"game jar");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_files_that_stay_corrupt() {
+        let (url, requests) = serve(vec![
+            Reply::new("200 OK", b"game"),
+            Reply::new("200 OK", b"game"),
+            Reply::new("200 OK", b"game jar"),
+        ])
+        .await;
+        let dir = temp_dir("download-still-corrupt");
+        let target = dir.join("client.jar");
+
+        let error = download_file(
+            &reqwest::Client::new(),
+            &url,
+            &target,
+            &sha1_hashes(b"game jar"),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            crate::backend::utils::error::error_kind(&error),
+            Some(ErrorKind::Verification)
+        );
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert!(!target.exists());
+        assert!(!dir.join("client.jar.part").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_missing_files() {
+        let (url, requests) = serve(vec![
+            Reply::new("404 Not Found", b""),
+            Reply::new("200 OK", b"game jar"),
+        ])
+        .await;
+        let dir = temp_dir("download-missing");
+        let target = dir.join("client.jar");
+
+        let error = download_file(
+            &reqwest::Client::new(),
+            &url,
+            &target,
+            &FileHashes::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<StatusError>().unwrap().status,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert!(!target.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
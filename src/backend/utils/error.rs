@@ -0,0 +1,152 @@
+//! Kinds of launcher errors, so the UI can tell users what went wrong.
+
+use crate::backend::utils::download::StatusError;
+use reqwest::StatusCode;
+use std::fmt;
+
+/// What kind of failure an error is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// A server couldn't be reached or answered with an error.
+    Network,
+    /// Reading or writing local files failed.
+    Io,
+    /// A downloaded file didn't match its expected hash.
+    Verification,
+    /// Data from a server or file couldn't be read.
+    Parse,
+    /// A file or version doesn't exist.
+    NotFound,
+    /// A server took too long to answer.
+    Timeout,
+}
+
+impl ErrorKind {
+    /// Name the launcher script reports the kind by.
+    #[must_use]
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Io => "io",
+            Self::Verification => "verification",
+            Self::Parse => "parse",
+            Self::NotFound => "not_found",
+            Self::Timeout => "timeout",
+        }
+    }
+
+    /// Kind with the given [`id`](Self::id).
+    #[must_use]
+    pub fn from_id(id: &str) -> Option<Self> {
+        [
+            Self::Network,
+            Self::Io,
+            Self::Verification,
+            Self::Parse,
+            Self::NotFound,
+            Self::Timeout,
+        ]
+        .into_iter()
+        .find(|kind| kind.id() == id)
+    }
+
+    /// Short advice shown to the user after a failure of this kind.
+    #[must_use]
+    pub const fn advice(self) -> &'static str {
+        match self {
+            Self::Network => "check your internet connection",
+            Self::Io => "check disk space and permissions",
+            Self::Verification => "a download was corrupted, try again",
+            Self::Parse => "received invalid data, try again later",
+            Self::NotFound => "the version could not be found",
+            Self::Timeout => "the server took too long, try again",
+        }
+    }
+}
+
+/// An error message tagged with its [`ErrorKind`].
+///
+/// Displays exactly like the message, so tagging an error doesn't change what is logged.
+/// The error that caused it, if any, is kept as its [`source`](std::error::Error::source)
+/// and shows up when the error is logged with `{:#}`.
+#[derive(Debug)]
+pub struct TaggedError {
+    pub kind: ErrorKind,
+    pub message: String,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl TaggedError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Keep the error this one was caused by.
+    #[must_use]
+    pub fn with_source(
+        mut self,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+}
+
+impl fmt::Display for TaggedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for TaggedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Work out the kind of an error from the first cause in its chain that has one.
+///
+/// Besides [`TaggedError`] this understands the errors that `?` converts from,
+/// like I/O, HTTP and JSON errors.
+#[must_use]
+pub fn error_kind(error: &anyhow::Error) -> Option<ErrorKind> {
+    error.chain().find_map(|cause| {
+        if let Some(e) = cause.downcast_ref::<TaggedError>() {
+            Some(e.kind)
+        } else if let Some(e) = cause.downcast_ref::<StatusError>() {
+            Some(status_kind(e.status))
+        } else if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            Some(if e.is_timeout() {
+                ErrorKind::Timeout
+            } else if e.is_decode() {
+                ErrorKind::Parse
+            } else {
+                e.status().map_or(ErrorKind::Network, status_kind)
+            })
+        } else if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+            Some(match e.kind() {
+                std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+                std::io::ErrorKind::TimedOut => ErrorKind::Timeout,
+                _ => ErrorKind::Io,
+            })
+        } else if cause.is::<serde_json::Error>() {
+            Some(ErrorKind::Parse)
+        } else {
+            None
+        }
+    })
+}
+
+fn status_kind(status: StatusCode) -> ErrorKind {
+    match status {
+        StatusCode::NOT_FOUND | StatusCode::GONE => ErrorKind::NotFound,
+        StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => ErrorKind::Timeout,
+        _ => ErrorKind::Network,
+    }
+}
@@ -0,0 +1,84 @@
+//! Hex-encoded hash helpers.
+
+use anyhow::Result;
+
+/// Compare two hex-encoded hashes, ignoring case and surrounding whitespace.
+///
+/// Manifests don't agree on the case of their hashes, and some pad them with spaces.
+#[must_use]
+pub fn hashes_equal(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Decode a hex string into bytes, accepting both upper and lower case digits.
+pub fn decode(s: &str) -> Result<Vec<u8>> {
+    if let Some(invalid) = s.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(anyhow::anyhow!("Invalid hex digit {invalid:?} in {s:?}"));
+    }
+    if !s.len().is_multiple_of(2) {
+        return Err(anyhow::anyhow!(
+            "Hex string {s:?} has an odd number of digits"
+        ));
+    }
+
+    // Every character is an ASCII hex digit by now
+    let digit = |c: u8| (c as char).to_digit(16).unwrap_or_default() as u8;
+    Ok(s.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| (digit(pair[0]) << 4) | digit(pair[1]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_case() {
+        assert!(hashes_equal(
+            "2D8A3B1F0C9E4A7B6D5C3E2F1A0B9C8D7E6F5A4B",
+            "2d8a3b1f0c9e4a7b6d5c3e2f1a0b9c8d7e6f5a4b"
+        ));
+        assert!(hashes_equal("aBcDeF01", "AbCdEf01"));
+    }
+
+    #[test]
+    fn ignores_surrounding_whitespace() {
+        assert!(hashes_equal("  abcdef01\n", "abcdef01"));
+        assert!(hashes_equal("ABCDEF01", "\tabcdef01 "));
+    }
+
+    #[test]
+    fn rejects_different_hashes() {
+        assert!(!hashes_equal("abcdef01", "abcdef02"));
+        assert!(!hashes_equal("abcdef01", "abcdef0"));
+        assert!(!hashes_equal("abcd ef01", "abcdef01"));
+    }
+
+    #[test]
+    fn decodes_what_was_encoded() {
+        let bytes = [0x00, 0x7f, 0x80, 0xab, 0xff];
+        assert_eq!(decode(&::hex::encode(bytes)).unwrap(), bytes);
+        assert_eq!(decode(&::hex::encode_upper(bytes)).unwrap(), bytes);
+        assert_eq!(decode("").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn decodes_mixed_case() {
+        assert_eq!(decode("aBcDeF01").unwrap(), [0xab, 0xcd, 0xef, 0x01]);
+    }
+
+    #[test]
+    fn rejects_odd_length() {
+        let error = decode("abc").unwrap_err();
+        assert!(error.to_string().contains("odd number of digits"));
+    }
+
+    #[test]
+    fn rejects_bad_digits() {
+        let error = decode("abzz").unwrap_err();
+        assert!(error.to_string().contains("Invalid hex digit 'z'"));
+        assert!(decode("ab cd").is_err());
+        assert!(decode("é0").is_err());
+    }
+}
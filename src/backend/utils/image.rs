@@ -0,0 +1,81 @@
+//! Image utilities.
+
+use anyhow::Result;
+
+/// Decode a PNG and shrink it so neither side exceeds `max_size`.
+///
+/// Images that already fit are returned unchanged. Larger ones are
+/// box-filtered down and re-encoded as 8-bit RGBA.
+pub fn downscale_png(data: &[u8], max_size: u32) -> Result<Vec<u8>> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer)?;
+
+    if info.width <= max_size && info.height <= max_size {
+        return Ok(data.to_vec());
+    }
+
+    let rgba = to_rgba(&buffer[..info.buffer_size()], info.color_type)?;
+    let scale = f64::from(max_size) / f64::from(info.width.max(info.height));
+    let width = ((f64::from(info.width) * scale).round() as u32).max(1);
+    let height = ((f64::from(info.height) * scale).round() as u32).max(1);
+    let pixels = box_resize(&rgba, info.width, info.height, width, height);
+
+    let mut output = Vec::new();
+    let mut encoder = png::Encoder::new(&mut output, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+    Ok(output)
+}
+
+/// Expand 8-bit pixel data of any PNG color type to RGBA.
+fn to_rgba(data: &[u8], color_type: png::ColorType) -> Result<Vec<u8>> {
+    let rgba = match color_type {
+        png::ColorType::Rgba => data.to_vec(),
+        png::ColorType::Rgb => data
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => data
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => data.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => {
+            return Err(anyhow::anyhow!("Indexed PNG was not expanded"));
+        }
+    };
+    Ok(rgba)
+}
+
+/// Resize RGBA pixels by averaging every source pixel that maps to a target pixel.
+fn box_resize(src: &[u8], src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> Vec<u8> {
+    let mut dst = Vec::with_capacity((dst_w * dst_h * 4) as usize);
+
+    for y in 0..dst_h {
+        let y0 = y * src_h / dst_h;
+        let y1 = ((y + 1) * src_h / dst_h).max(y0 + 1);
+        for x in 0..dst_w {
+            let x0 = x * src_w / dst_w;
+            let x1 = ((x + 1) * src_w / dst_w).max(x0 + 1);
+
+            let mut sum = [0u64; 4];
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    let offset = ((sy * src_w + sx) * 4) as usize;
+                    for (channel, total) in sum.iter_mut().enumerate() {
+                        *total += u64::from(src[offset + channel]);
+                    }
+                }
+            }
+
+            let count = u64::from((y1 - y0) * (x1 - x0));
+            dst.extend(sum.iter().map(|total| (total / count) as u8));
+        }
+    }
+
+    dst
+}
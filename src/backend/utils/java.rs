@@ -0,0 +1,115 @@
+//! Java runtime utilities.
+
+use anyhow::Result;
+use std::fmt;
+use std::path::Path;
+
+/// The selected Java executable is older than the game version needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaVersionMismatch {
+    pub version: String,
+    /// Java major version the game version asks for.
+    pub required: u32,
+    /// Java major version of the selected executable.
+    pub found: u32,
+}
+
+impl fmt::Display for JavaVersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Minecraft {} needs Java {}+, but the selected Java is {}",
+            self.version, self.required, self.found
+        )
+    }
+}
+
+impl std::error::Error for JavaVersionMismatch {}
+
+/// Read the major version of a Java executable by running `java -version`.
+pub async fn java_major_version(java_path: &Path) -> Result<u32> {
+    let output = tokio::process::Command::new(java_path)
+        .arg("-version")
+        .output()
+        .await?;
+
+    // The version banner goes to stderr
+    parse_java_major_version(&String::from_utf8_lossy(&output.stderr))
+}
+
+/// Parse the feature version from `java -version` output.
+///
+/// Understands the legacy `1.8.0_392` scheme as well as modern ones like
+/// `17.0.9`, `21` and early-access `24-ea`.
+pub fn parse_java_major_version(output: &str) -> Result<u32> {
+    let version = output
+        .lines()
+        .find_map(|line| {
+            let start = line.find(" version \"")? + " version \"".len();
+            let end = start + line[start..].find('"')?;
+            Some(&line[start..end])
+        })
+        .ok_or_else(|| anyhow::anyhow!("No version found in Java output: {output}"))?;
+
+    let mut numbers = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(str::parse::<u32>);
+    let major = match numbers.next() {
+        Some(Ok(1)) => numbers.next(),
+        first => first,
+    };
+
+    match major {
+        Some(Ok(major)) if major > 0 => Ok(major),
+        _ => Err(anyhow::anyhow!("Unrecognized Java version: {version}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_temurin() {
+        let output = "openjdk version \"21.0.2\" 2024-01-16 LTS\n\
+            OpenJDK Runtime Environment Temurin-21.0.2+13 (build 21.0.2+13-LTS)\n\
+            OpenJDK 64-Bit Server VM Temurin-21.0.2+13 (build 21.0.2+13-LTS, mixed mode, sharing)\n";
+        assert_eq!(parse_java_major_version(output).unwrap(), 21);
+    }
+
+    #[test]
+    fn parses_legacy_zulu() {
+        let output = "openjdk version \"1.8.0_392\"\n\
+            OpenJDK Runtime Environment (Zulu 8.74.0.17-CA-macos-aarch64) (build 1.8.0_392-b08)\n\
+            OpenJDK 64-Bit Server VM (Zulu 8.74.0.17-CA-macos-aarch64) (build 25.392-b08, mixed mode)\n";
+        assert_eq!(parse_java_major_version(output).unwrap(), 8);
+    }
+
+    #[test]
+    fn parses_graalvm() {
+        let output = "java version \"17.0.9\" 2023-10-17 LTS\n\
+            Java(TM) SE Runtime Environment Oracle GraalVM 17.0.9+11.1 (build 17.0.9+11-LTS-jvmci-23.0-b21)\n\
+            Java HotSpot(TM) 64-Bit Server VM Oracle GraalVM 17.0.9+11.1 (build 17.0.9+11-LTS-jvmci-23.0-b21, mixed mode, sharing)\n";
+        assert_eq!(parse_java_major_version(output).unwrap(), 17);
+    }
+
+    #[test]
+    fn parses_early_access() {
+        let output = "openjdk version \"24-ea\" 2025-03-18\n\
+            OpenJDK Runtime Environment (build 24-ea+29-3578)\n\
+            OpenJDK 64-Bit Server VM (build 24-ea+29-3578, mixed mode, sharing)\n";
+        assert_eq!(parse_java_major_version(output).unwrap(), 24);
+    }
+
+    #[test]
+    fn skips_lines_before_the_version() {
+        let output = "Picked up _JAVA_OPTIONS: -Xmx1G\nopenjdk version \"11.0.21\" 2023-10-17\n";
+        assert_eq!(parse_java_major_version(output).unwrap(), 11);
+    }
+
+    #[test]
+    fn rejects_output_without_a_version() {
+        assert!(parse_java_major_version("Error: could not find libjava.so").is_err());
+        assert!(parse_java_major_version("openjdk version \"ea\"").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Following a log file as the game writes it.
+
+use anyhow::Result;
+use std::io::SeekFrom;
+use std::path::Path;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader};
+
+/// Most of an existing log read when following starts, so huge logs don't stall the UI.
+const MAX_INITIAL_BYTES: u64 = 256 * 1024;
+
+/// How far into a log file has been read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogCursor {
+    offset: u64,
+    /// First line of the file, which changes when the game starts a new log.
+    first_line: Option<String>,
+}
+
+/// Lines added to a log since the last read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogChunk {
+    pub lines: Vec<String>,
+    /// Where to continue reading from next time.
+    pub cursor: LogCursor,
+    /// The file was truncated or replaced, so earlier lines belong to an old log.
+    pub restarted: bool,
+}
+
+/// Read the complete lines written to a log since `cursor`.
+///
+/// A missing file has no lines. A file that got shorter or starts with a different
+/// line than before was rotated, and is read again from its start.
+pub async fn read_new_lines(path: &Path, cursor: &LogCursor) -> Result<LogChunk> {
+    let mut file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(LogChunk {
+                lines: Vec::new(),
+                cursor: LogCursor::default(),
+                restarted: cursor.offset > 0,
+            });
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let len = file.metadata().await?.len();
+
+    let first_line = {
+        let mut first_line = String::new();
+        BufReader::new(&mut file).read_line(&mut first_line).await?;
+        // A first line without its newline may still be growing
+        first_line
+            .ends_with('\n')
+            .then(|| first_line.trim_end().to_string())
+    };
+    let restarted = cursor.offset > 0
+        && (len < cursor.offset
+            || (cursor.first_line.is_some() && first_line != cursor.first_line));
+
+    let mut start = if restarted || cursor.offset == 0 {
+        len.saturating_sub(MAX_INITIAL_BYTES)
+    } else {
+        cursor.offset
+    };
+    file.seek(SeekFrom::Start(start)).await?;
+    let mut bytes = Vec::new();
+    file.take(len - start).read_to_end(&mut bytes).await?;
+
+    // Starting partway into an old log cuts its first line, which is dropped
+    if start > 0 && (restarted || cursor.offset == 0) {
+        let skipped = bytes
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(bytes.len(), |i| i + 1);
+        bytes.drain(..skipped);
+        start += skipped as u64;
+    }
+    // The last line is only read once the game has finished writing it
+    let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    bytes.truncate(complete);
+
+    let lines = String::from_utf8_lossy(&bytes)
+        .lines()
+        .map(ToString::to_string)
+        .collect();
+    Ok(LogChunk {
+        lines,
+        cursor: LogCursor {
+            offset: start + complete as u64,
+            first_line,
+        },
+        restarted,
+    })
+}
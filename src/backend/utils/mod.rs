@@ -1,5 +1,17 @@
 //! Utility modules.
 
 pub mod application;
+pub mod archive;
+pub mod crash;
 pub mod css;
+pub mod download;
+pub mod error;
+pub mod hex;
+pub mod http;
+pub mod image;
+pub mod java;
+pub mod log_tail;
+pub mod nbt;
 pub mod paths;
+pub mod process;
+pub mod window;
@@ -0,0 +1,173 @@
+//! Minimal reader for Minecraft's uncompressed NBT format.
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// A value read from an NBT file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(HashMap<String, Tag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    /// Child of a compound tag.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Self> {
+        match self {
+            Self::Compound(children) => children.get(name),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_list(&self) -> Option<&[Self]> {
+        match self {
+            Self::List(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Lists and compounds nested deeper than this are rejected instead of overflowing the stack.
+const MAX_DEPTH: usize = 512;
+
+/// Read the root compound of an uncompressed NBT file.
+///
+/// Gzip-compressed files such as `level.dat` have to be decompressed first.
+pub fn read(bytes: &[u8]) -> Result<Tag> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let tag_type = reader.u8()?;
+    if tag_type != 10 {
+        return Err(anyhow::anyhow!(
+            "NBT root must be a compound, found tag type {tag_type}"
+        ));
+    }
+    // The root name is unused
+    reader.string()?;
+    reader.payload(tag_type, 0)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let end = self.pos + N;
+        let bytes = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or_else(|| anyhow::anyhow!("Unexpected end of NBT data"))?;
+        self.pos = end;
+        Ok(bytes.try_into()?)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16> {
+        Ok(i16::from_be_bytes(self.take()?))
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.take()?))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(i64::from_be_bytes(self.take()?))
+    }
+
+    /// Length prefix of an array or list, which must not be negative.
+    fn len(&mut self) -> Result<usize> {
+        let len = self.i32()?;
+        usize::try_from(len).map_err(|_| anyhow::anyhow!("Negative NBT length: {len}"))
+    }
+
+    /// Strings are modified UTF-8, which only differs from UTF-8 for null and
+    /// supplementary characters, so those are read lossily.
+    fn string(&mut self) -> Result<String> {
+        let len = u16::from_be_bytes(self.take()?) as usize;
+        let end = self.pos + len;
+        let bytes = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or_else(|| anyhow::anyhow!("Unexpected end of NBT data"))?;
+        self.pos = end;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn payload(&mut self, tag_type: u8, depth: usize) -> Result<Tag> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow::anyhow!("NBT data is nested too deeply"));
+        }
+
+        Ok(match tag_type {
+            1 => Tag::Byte(self.u8()? as i8),
+            2 => Tag::Short(self.i16()?),
+            3 => Tag::Int(self.i32()?),
+            4 => Tag::Long(self.i64()?),
+            5 => Tag::Float(f32::from_be_bytes(self.take()?)),
+            6 => Tag::Double(f64::from_be_bytes(self.take()?)),
+            7 => {
+                let len = self.len()?;
+                Tag::ByteArray(
+                    (0..len)
+                        .map(|_| self.u8().map(|b| b as i8))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            8 => Tag::String(self.string()?),
+            9 => {
+                let item_type = self.u8()?;
+                let len = self.len()?;
+                Tag::List(
+                    (0..len)
+                        .map(|_| self.payload(item_type, depth + 1))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            10 => {
+                let mut children = HashMap::new();
+                loop {
+                    let child_type = self.u8()?;
+                    if child_type == 0 {
+                        break;
+                    }
+                    let name = self.string()?;
+                    children.insert(name, self.payload(child_type, depth + 1)?);
+                }
+                Tag::Compound(children)
+            }
+            11 => {
+                let len = self.len()?;
+                Tag::IntArray((0..len).map(|_| self.i32()).collect::<Result<_>>()?)
+            }
+            12 => {
+                let len = self.len()?;
+                Tag::LongArray((0..len).map(|_| self.i64()).collect::<Result<_>>()?)
+            }
+            _ => return Err(anyhow::anyhow!("Unknown NBT tag type {tag_type}")),
+        })
+    }
+}
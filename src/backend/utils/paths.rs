@@ -1,13 +1,18 @@
 //! Path utilities.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 
+use crate::backend::utils::error::{ErrorKind, TaggedError};
+
 /// Name of the main launcher directory.
 const LAUNCHER_DIR: &str = "Dream Launcher";
 
 /// Get the base launcher directory (`Dream Launcher`).
+///
+/// It lives in `%APPDATA%` on Windows, `~/Library/Application Support` on macOS
+/// and the XDG data directory elsewhere.
 #[inline]
 pub fn get_launcher_dir() -> Result<PathBuf> {
     let base_dir = match std::env::consts::OS {
@@ -19,14 +24,34 @@ pub fn get_launcher_dir() -> Result<PathBuf> {
             .ok()
             .map(|home| PathBuf::from(home).join("Library/Application Support"))
             .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?,
-        _ => std::env::var("HOME")
-            .ok()
-            .map(PathBuf::from)
-            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?,
+        _ => return get_xdg_launcher_dir(),
     };
     Ok(base_dir.join(LAUNCHER_DIR))
 }
 
+/// Get the launcher directory under `$XDG_DATA_HOME`, defaulting to `~/.local/share`.
+///
+/// Older versions kept everything in `~/Dream Launcher`, which is still used when it exists.
+fn get_xdg_launcher_dir() -> Result<PathBuf> {
+    let home = std::env::var("HOME")
+        .ok()
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+
+    let legacy_dir = home.join(LAUNCHER_DIR);
+    if legacy_dir.is_dir() {
+        return Ok(legacy_dir);
+    }
+
+    // Relative XDG paths are invalid per the spec and must be ignored
+    let data_dir = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| home.join(".local/share"));
+    Ok(data_dir.join(LAUNCHER_DIR))
+}
+
 /// Get the cache directory for launcher configuration files.
 #[inline]
 pub fn get_cache_dir() -> Result<PathBuf> {
@@ -38,3 +63,35 @@ pub fn get_cache_dir() -> Result<PathBuf> {
 pub fn get_shared_dir() -> Result<PathBuf> {
     Ok(get_launcher_dir()?.join("shared"))
 }
+
+/// Open a file or folder with the system's default application.
+///
+/// Uses `explorer` on Windows, `open` on macOS and `xdg-open` elsewhere.
+pub fn open_path(path: &Path) -> Result<()> {
+    let program = match std::env::consts::OS {
+        "windows" => "explorer",
+        "macos" => "open",
+        _ => "xdg-open",
+    };
+    let output = std::process::Command::new(program)
+        .arg(path)
+        .output()
+        .map_err(|e| {
+            TaggedError::new(
+                ErrorKind::Io,
+                format!("Failed to run {program} to open {}", path.display()),
+            )
+            .with_source(e)
+        })?;
+
+    // Explorer exits with 1 even when it opened the path
+    if !output.status.success() && program != "explorer" {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!(
+            "{program} failed to open {}: {}",
+            path.display(),
+            stderr.trim()
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Game process control.
+
+use anyhow::Result;
+use log::{info, warn};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// How often a stopping process is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Ask a process to exit and kill it if it is still running after `timeout`.
+///
+/// Uses `SIGTERM` and `SIGKILL` on Unix, and on Windows a close request
+/// followed by `TerminateProcess` through `taskkill`.
+/// Returns `false` if the process had already exited.
+pub async fn stop_process(pid: u32, timeout: Duration) -> Result<bool> {
+    if !is_process_running(pid).await {
+        return Ok(false);
+    }
+
+    info!("Stopping process {pid}");
+    if let Err(e) = signal_process(pid, false).await {
+        // The process may have exited in the meantime
+        if !is_process_running(pid).await {
+            return Ok(false);
+        }
+        warn!("Failed to ask process {pid} to exit: {e}");
+    }
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tokio::time::Instant::now() < deadline {
+        if !is_process_running(pid).await {
+            return Ok(true);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    warn!("Process {pid} did not exit within {timeout:?}, killing it");
+    if let Err(e) = signal_process(pid, true).await
+        && is_process_running(pid).await
+    {
+        return Err(e);
+    }
+    Ok(true)
+}
+
+/// Check whether a process with the given PID exists.
+pub async fn is_process_running(pid: u32) -> bool {
+    if cfg!(windows) {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+            .output()
+            .await
+            .is_ok_and(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .split_whitespace()
+                    .any(|field| field == pid.to_string())
+            })
+    } else {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .output()
+            .await
+            .is_ok_and(|output| output.status.success())
+    }
+}
+
+async fn signal_process(pid: u32, force: bool) -> Result<()> {
+    let pid = pid.to_string();
+    let output = if cfg!(windows) {
+        let mut command = Command::new("taskkill");
+        if force {
+            command.arg("/F");
+        }
+        command.args(["/PID", &pid]).output().await?
+    } else {
+        let signal = if force { "-KILL" } else { "-TERM" };
+        Command::new("kill").args([signal, &pid]).output().await?
+    };
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
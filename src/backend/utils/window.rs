@@ -0,0 +1,83 @@
+//! Saved size and position of the launcher window.
+
+use crate::backend::utils::paths::get_launcher_dir;
+use anyhow::Result;
+use dioxus_desktop::tao::window::Window;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+/// File in the launcher directory that stores the window geometry.
+const WINDOW_FILE: &str = "window.json";
+
+/// Size the layout is designed for, also used as the minimum size.
+pub const DEFAULT_WIDTH: f64 = 1056.0;
+pub const DEFAULT_HEIGHT: f64 = 709.0;
+
+/// Window size and position in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: f64,
+    pub height: f64,
+    /// Position of the top-left corner, left to the system on first run.
+    pub position: Option<(f64, f64)>,
+}
+
+impl Default for WindowGeometry {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            position: None,
+        }
+    }
+}
+
+impl WindowGeometry {
+    /// Load the geometry saved when the launcher was last closed, or the default on first run.
+    #[must_use]
+    pub fn load() -> Self {
+        let Ok(path) = get_launcher_dir().map(|dir| dir.join(WINDOW_FILE)) else {
+            return Self::default();
+        };
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<Self>(&json) {
+            // Never restore a size the layout does not fit in
+            Ok(geometry) => Self {
+                width: geometry.width.max(DEFAULT_WIDTH),
+                height: geometry.height.max(DEFAULT_HEIGHT),
+                position: geometry.position,
+            },
+            Err(e) => {
+                warn!("Ignoring invalid window geometry {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// Read the current geometry of a window.
+    #[must_use]
+    pub fn of(window: &Window) -> Self {
+        let scale_factor = window.scale_factor();
+        let size = window.inner_size().to_logical::<f64>(scale_factor);
+        let position = window
+            .outer_position()
+            .ok()
+            .map(|position| position.to_logical::<f64>(scale_factor))
+            .map(|position| (position.x, position.y));
+        Self {
+            width: size.width,
+            height: size.height,
+            position,
+        }
+    }
+
+    /// Save the geometry so the next start restores it.
+    pub fn save(&self) -> Result<()> {
+        let dir = get_launcher_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(WINDOW_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//! Banner offering the crash report of a crashed game.
+
+use crate::backend::utils::paths::open_path;
+use crate::frontend::services::instances::INSTANCES;
+use crate::frontend::services::states::CRASH_REPORT;
+use dioxus::prelude::*;
+use log::{error, info};
+
+#[component]
+pub fn CrashBanner() -> Element {
+    let mut copied = use_signal(|| false);
+
+    let Some(report) = CRASH_REPORT.read().clone() else {
+        return rsx! {};
+    };
+    let instance_name = INSTANCES
+        .read()
+        .get(&report.instance_id)
+        .map(|instance| instance.name.clone())
+        .unwrap_or_default();
+
+    let on_open = {
+        let path = report.path.clone();
+        move |_| {
+            if let Err(e) = open_path(&path) {
+                error!("Failed to open crash report {path:?}: {e:#}");
+            }
+        }
+    };
+
+    let on_copy = {
+        let path = report.path.clone();
+        move |_| {
+            let path = path.clone();
+            spawn(async move {
+                let contents = match tokio::fs::read_to_string(&path).await {
+                    Ok(contents) => contents,
+                    Err(e) => {
+                        error!("Failed to read crash report {path:?}: {e}");
+                        return;
+                    }
+                };
+                // The webview clipboard avoids a native clipboard dependency
+                let Ok(text) = serde_json::to_string(&contents) else {
+                    return;
+                };
+                match document::eval(&format!("navigator.clipboard.writeText({text})")).await {
+                    Ok(_) => {
+                        info!("Copied crash report {path:?} to the clipboard");
+                        copied.set(true);
+                    }
+                    Err(e) => error!("Failed to copy crash report: {e}"),
+                }
+            });
+        }
+    };
+
+    let on_dismiss = move |_| {
+        *CRASH_REPORT.write() = None;
+        copied.set(false);
+    };
+
+    rsx! {
+        div { class: "crash-banner",
+            div { class: "crash-banner-text", "{instance_name} crashed — view report" }
+            div { class: "crash-banner-button", onclick: on_open, "Open" }
+            div { class: "crash-banner-button", onclick: on_copy,
+                if copied() { "Copied" } else { "Copy" }
+            }
+            div { class: "crash-banner-close", onclick: on_dismiss, "×" }
+        }
+    }
+}
@@ -1,7 +1,13 @@
 //! Debug window component.
 
-use crate::frontend::services::states::{clear_debug_logs, get_debug_logs};
+use crate::backend::python::python::GameLogLine;
+use crate::backend::utils::log_tail::{LogCursor, read_new_lines};
+use crate::frontend::services::instances::{
+    export_debug_log, latest_log_path, launch_command, open_logs_folder,
+};
+use crate::frontend::services::states::{add_debug_log, clear_debug_logs, get_debug_logs};
 use dioxus::prelude::*;
+use log::error;
 use std::collections::VecDeque;
 
 #[derive(Props, Clone, PartialEq, Eq)]
@@ -14,9 +20,29 @@ pub struct DebugWindowProps {
 pub struct LogEntry {
     pub timestamp: String,
     pub level: String,
+    pub thread: Option<String>,
     pub message: String,
 }
 
+/// Lines of the game log kept in the window, oldest dropped first.
+const MAX_GAME_LOG_LINES: usize = 2000;
+
+/// Levels the console can be filtered by, `None` showing every line.
+const LEVEL_FILTERS: [Option<&str>; 4] = [None, Some("INFO"), Some("WARN"), Some("ERROR")];
+
+impl LogEntry {
+    /// Entry for a line of the game log.
+    fn from_game_log(line: &str) -> Self {
+        let line = GameLogLine::parse(line);
+        Self {
+            timestamp: line.time.unwrap_or_default(),
+            level: line.level.to_string(),
+            thread: line.thread,
+            message: line.message,
+        }
+    }
+}
+
 #[component]
 pub fn DebugWindow(props: DebugWindowProps) -> Element {
     let mut show = props.show;
@@ -24,6 +50,11 @@ pub fn DebugWindow(props: DebugWindowProps) -> Element {
     let mut is_hiding = use_signal(|| false);
     let mut should_render = use_signal(|| false);
     let mut console_logs = use_signal(VecDeque::<LogEntry>::new);
+    let mut action_status = use_signal(|| None::<String>);
+    let mut show_game_log = use_signal(|| false);
+    let mut game_log = use_signal(VecDeque::<LogEntry>::new);
+    let mut level_filter = use_signal(|| None::<&'static str>);
+    let mut game_log_task = use_signal(|| None::<Task>);
 
     // Handle show/hide animations
     use_effect(move || {
@@ -63,6 +94,7 @@ pub fn DebugWindow(props: DebugWindowProps) -> Element {
                                 logs.push_back(LogEntry {
                                     timestamp: debug_log.timestamp.clone(),
                                     level: debug_log.level.clone(),
+                                    thread: debug_log.thread.clone(),
                                     message: debug_log.message.clone(),
                                 });
                             }
@@ -71,6 +103,7 @@ pub fn DebugWindow(props: DebugWindowProps) -> Element {
                             logs.push_back(LogEntry {
                                 timestamp: debug_log.timestamp.clone(),
                                 level: debug_log.level.clone(),
+                                thread: debug_log.thread.clone(),
                                 message: debug_log.message.clone(),
                             });
                         }
@@ -80,6 +113,49 @@ pub fn DebugWindow(props: DebugWindowProps) -> Element {
         }
     });
 
+    // Follow logs/latest.log of the instance while the game log is shown
+    use_effect(move || {
+        // Only one follower may append to the log, even when toggled quickly
+        if let Some(task) = game_log_task.write().take() {
+            task.cancel();
+        }
+        if !show() || !show_game_log() {
+            return;
+        }
+        let Some(id) = instance_id() else {
+            return;
+        };
+        game_log.write().clear();
+        let task = spawn(async move {
+            let path = match latest_log_path(id).await {
+                Ok(path) => path,
+                Err(e) => {
+                    error!("Failed to find game log of instance {id}: {e}");
+                    return;
+                }
+            };
+            let mut cursor = LogCursor::default();
+            loop {
+                match read_new_lines(&path, &cursor).await {
+                    Ok(chunk) => {
+                        let mut log = game_log.write();
+                        if chunk.restarted {
+                            log.clear();
+                        }
+                        log.extend(chunk.lines.iter().map(|line| LogEntry::from_game_log(line)));
+                        while log.len() > MAX_GAME_LOG_LINES {
+                            log.pop_front();
+                        }
+                        cursor = chunk.cursor;
+                    }
+                    Err(e) => error!("Failed to read game log of instance {id}: {e}"),
+                }
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            }
+        });
+        game_log_task.set(Some(task));
+    });
+
     let handle_backdrop_click = move |_| {
         show.set(false);
     };
@@ -91,14 +167,103 @@ pub fn DebugWindow(props: DebugWindowProps) -> Element {
 
     let handle_clear_click = move |e: Event<MouseData>| {
         e.stop_propagation();
-        clear_debug_logs();
-        console_logs.write().clear();
+        if show_game_log() {
+            game_log.write().clear();
+        } else {
+            clear_debug_logs();
+            console_logs.write().clear();
+        }
+    };
+
+    let handle_game_log_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        show_game_log.set(!show_game_log());
+    };
+
+    let handle_logs_folder_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        let Some(id) = instance_id() else {
+            return;
+        };
+        spawn(async move {
+            if let Err(e) = open_logs_folder(id).await {
+                error!("Failed to open logs folder of instance {id}: {e:#}");
+                action_status.set(Some(e.to_string()));
+            }
+        });
+    };
+
+    let handle_level_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        let index = LEVEL_FILTERS
+            .iter()
+            .position(|&level| level == level_filter())
+            .unwrap_or(0);
+        level_filter.set(LEVEL_FILTERS[(index + 1) % LEVEL_FILTERS.len()]);
+    };
+
+    let handle_save_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        let Some(id) = instance_id() else {
+            return;
+        };
+        action_status.set(Some("Saving...".to_string()));
+        spawn(async move {
+            let status = match export_debug_log(id).await {
+                Ok(path) => format!(
+                    "Saved {}",
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                ),
+                Err(e) => {
+                    error!("Failed to save debug log of instance {id}: {e}");
+                    e.to_string()
+                }
+            };
+            action_status.set(Some(status));
+        });
+    };
+
+    let handle_command_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        let Some(id) = instance_id() else {
+            return;
+        };
+        action_status.set(Some("Building command...".to_string()));
+        spawn(async move {
+            let status = match launch_command(id).await {
+                Ok(command) => {
+                    add_debug_log(
+                        "INFO".to_string(),
+                        format!("Launch command: {command}"),
+                        Some(id),
+                    );
+                    "Command added to the console".to_string()
+                }
+                Err(e) => {
+                    error!("Failed to build launch command of instance {id}: {e}");
+                    e.to_string()
+                }
+            };
+            action_status.set(Some(status));
+        });
     };
 
     if !should_render() {
         return rsx! {};
     }
 
+    let logs = if show_game_log() {
+        game_log
+    } else {
+        console_logs
+    };
+    let visible_logs: Vec<LogEntry> = logs
+        .read()
+        .iter()
+        .filter(|log| level_filter().is_none_or(|level| log.level == level))
+        .cloned()
+        .collect();
+
     let animation_class = if is_hiding() {
         "version-selector-hide"
     } else {
@@ -155,6 +320,39 @@ pub fn DebugWindow(props: DebugWindowProps) -> Element {
                         onclick: handle_clear_click,
                         "Clear console"
                     }
+                    button {
+                        class: "debug-action-btn",
+                        onclick: handle_level_click,
+                        {format!("Level: {}", level_filter().unwrap_or("All"))}
+                    }
+                    if instance_id().is_some() {
+                        button {
+                            class: "debug-action-btn",
+                            onclick: handle_game_log_click,
+                            if show_game_log() { "Launcher console" } else { "Game log" }
+                        }
+                        button {
+                            class: "debug-action-btn",
+                            onclick: handle_logs_folder_click,
+                            "Logs folder"
+                        }
+                        button {
+                            class: "debug-action-btn",
+                            onclick: handle_save_click,
+                            "Save logs"
+                        }
+                        button {
+                            class: "debug-action-btn",
+                            onclick: handle_command_click,
+                            "Show command"
+                        }
+                    }
+                    if let Some(status) = action_status() {
+                        span {
+                            class: "debug-action-status",
+                            "{status}"
+                        }
+                    }
                 }
 
                 // Console content
@@ -162,13 +360,13 @@ pub fn DebugWindow(props: DebugWindowProps) -> Element {
                     class: "debug-content",
                     div {
                         class: "console-container",
-                        if console_logs.read().is_empty() {
+                        if visible_logs.is_empty() {
                             div {
                                 class: "console-empty",
                                 "No logs available"
                             }
                         } else {
-                            for log in console_logs.read().iter() {
+                            for log in visible_logs.iter() {
                                 div {
                                     class: "console-line console-{log.level}",
                                     span {
@@ -179,6 +377,12 @@ pub fn DebugWindow(props: DebugWindowProps) -> Element {
                                         class: "console-level",
                                         "[{log.level}]"
                                     }
+                                    if let Some(thread) = &log.thread {
+                                        span {
+                                            class: "console-thread",
+                                            "{thread}"
+                                        }
+                                    }
                                     span {
                                         class: "console-message",
                                         "{log.message}"
@@ -1,8 +1,10 @@
 //! Game progress component for installation and launch progress.
 
 use crate::backend::utils::css::ResourceLoader;
-use crate::frontend::services::states::ProgressStatus;
+use crate::frontend::services::instances::cancel_launch;
+use crate::frontend::services::states::{ProgressStatus, game_pid};
 use dioxus::prelude::*;
+use log::error;
 
 #[derive(Props, Clone, PartialEq)]
 pub struct GameProgressProps {
@@ -10,6 +12,8 @@ pub struct GameProgressProps {
     pub progress: f32,
     pub status: String,
     pub status_type: ProgressStatus,
+    /// Instance being installed or launched.
+    pub instance_id: Option<u32>,
 }
 
 #[component]
@@ -19,12 +23,17 @@ pub fn GameProgress(props: GameProgressProps) -> Element {
         progress,
         status,
         status_type,
+        instance_id,
     } = props;
 
     if !show {
         return rsx! { div {} };
     }
 
+    // Until the game runs there is no process to stop, only the install to cancel
+    let cancellable_id = instance_id
+        .filter(|&id| status_type == ProgressStatus::InProgress && game_pid(id).is_none());
+
     rsx! {
         style {
             dangerous_inner_html: ResourceLoader::get_css("progress")
@@ -35,8 +44,26 @@ pub fn GameProgress(props: GameProgressProps) -> Element {
             style: "--progress-width: {progress}%",
 
             div {
-                class: "launch-progress-text",
-                "{status}"
+                class: "launch-progress-header",
+
+                div {
+                    class: "launch-progress-text",
+                    "{status}"
+                }
+
+                if let Some(id) = cancellable_id {
+                    button {
+                        class: "launch-progress-cancel",
+                        onclick: move |_| {
+                            spawn(async move {
+                                if let Err(e) = cancel_launch(id).await {
+                                    error!("Failed to cancel launch: {e}");
+                                }
+                            });
+                        },
+                        "Cancel"
+                    }
+                }
             }
 
             div {
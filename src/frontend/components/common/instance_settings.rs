@@ -0,0 +1,269 @@
+//! Instance settings dialog component.
+
+use crate::{
+    backend::{
+        services::{JvmPreset, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH, validate_window_size},
+        utils::css::ResourceLoader,
+    },
+    frontend::services::instances::{INSTANCES, InstanceManager},
+};
+use dioxus::prelude::*;
+
+#[derive(Props, Clone, PartialEq, Eq)]
+pub struct InstanceSettingsDialogProps {
+    pub show: Signal<bool>,
+    pub instance_id: Signal<Option<u32>>,
+}
+
+#[component]
+pub fn InstanceSettingsDialog(props: InstanceSettingsDialogProps) -> Element {
+    let mut show = props.show;
+    let instance_id = props.instance_id;
+
+    let mut width = use_signal(String::new);
+    let mut height = use_signal(String::new);
+    let mut fullscreen = use_signal(|| false);
+    let mut preset = use_signal(JvmPreset::default);
+    let mut custom_args = use_signal(String::new);
+    let mut error_message = use_signal(|| None::<String>);
+    let mut is_hiding = use_signal(|| false);
+    let mut should_render = use_signal(|| false);
+
+    // Load the current settings when the dialog opens
+    use_effect(move || {
+        if show() {
+            if let Some(instance) = instance_id().and_then(|id| INSTANCES.peek().get(&id).cloned())
+            {
+                width.set(
+                    instance
+                        .window_width
+                        .map(|w| w.to_string())
+                        .unwrap_or_default(),
+                );
+                height.set(
+                    instance
+                        .window_height
+                        .map(|h| h.to_string())
+                        .unwrap_or_default(),
+                );
+                fullscreen.set(instance.fullscreen);
+                custom_args.set(match &instance.jvm_preset {
+                    JvmPreset::Custom(args) => args.clone(),
+                    _ => String::new(),
+                });
+                preset.set(instance.jvm_preset);
+            }
+            error_message.set(None);
+            should_render.set(true);
+            is_hiding.set(false);
+        } else if should_render() {
+            // Start hide animation
+            is_hiding.set(true);
+            spawn(async move {
+                // Wait for animation to complete
+                tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+                should_render.set(false);
+                is_hiding.set(false);
+            });
+        }
+    });
+
+    let mut save = move || {
+        let Some(id) = instance_id() else {
+            return;
+        };
+
+        // Both fields empty keeps the game's default window size
+        let size = match (width().trim(), height().trim()) {
+            ("", "") => None,
+            ("", _) | (_, "") => {
+                error_message.set(Some("Set both width and height".to_string()));
+                return;
+            }
+            (w, h) => {
+                let (Ok(w), Ok(h)) = (w.parse::<u32>(), h.parse::<u32>()) else {
+                    error_message.set(Some("Width and height must be numbers".to_string()));
+                    return;
+                };
+                if let Err(e) = validate_window_size(w, h) {
+                    error_message.set(Some(e.to_string()));
+                    return;
+                }
+                Some((w, h))
+            }
+        };
+
+        let jvm_preset = match preset() {
+            JvmPreset::Custom(_) => JvmPreset::Custom(custom_args().trim().to_string()),
+            built_in => built_in,
+        };
+        if let Err(e) = jvm_preset.arguments() {
+            error_message.set(Some(e.to_string()));
+            return;
+        }
+
+        InstanceManager::set_window_settings(id, size, fullscreen());
+        InstanceManager::set_jvm_preset(id, jvm_preset);
+        show.set(false);
+    };
+
+    let handle_save_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        save();
+    };
+
+    let handle_key_press = move |e: Event<KeyboardData>| match e.key() {
+        Key::Enter => save(),
+        Key::Escape => show.set(false),
+        _ => {}
+    };
+
+    if !should_render() {
+        return rsx! {};
+    }
+
+    let dialog_class = if is_hiding() {
+        "rename-dialog rename-dialog-hide"
+    } else {
+        "rename-dialog rename-dialog-show"
+    };
+
+    rsx! {
+        div {
+            class: "rename-dialog-backdrop",
+            onclick: move |_| show.set(false),
+
+            div {
+                class: "{dialog_class}",
+                onclick: |e| e.stop_propagation(),
+
+                // Header
+                div {
+                    class: "rename-dialog-header",
+                    h2 {
+                        class: "rename-dialog-title",
+                        "Instance settings"
+                    }
+                    button {
+                        class: "rename-dialog-close",
+                        onclick: move |e: Event<MouseData>| {
+                            e.stop_propagation();
+                            show.set(false);
+                        },
+                        img {
+                            src: "{ResourceLoader::get_asset(\"close\")}",
+                            alt: "Close"
+                        }
+                    }
+                }
+
+                // Content
+                div {
+                    class: "rename-dialog-content",
+
+                    div {
+                        class: "rename-input-section",
+                        div {
+                            class: "rename-input-label",
+                            "Window size:"
+                        }
+                        div {
+                            class: "instance-settings-row",
+                            input {
+                                r#type: "text",
+                                inputmode: "numeric",
+                                class: "rename-input instance-settings-input",
+                                value: "{width()}",
+                                placeholder: "{MIN_WINDOW_WIDTH}",
+                                disabled: fullscreen(),
+                                oninput: move |e| width.set(e.value()),
+                                onkeydown: handle_key_press,
+                            }
+                            div { class: "instance-settings-separator", "×" }
+                            input {
+                                r#type: "text",
+                                inputmode: "numeric",
+                                class: "rename-input instance-settings-input",
+                                value: "{height()}",
+                                placeholder: "{MIN_WINDOW_HEIGHT}",
+                                disabled: fullscreen(),
+                                oninput: move |e| height.set(e.value()),
+                                onkeydown: handle_key_press,
+                            }
+                        }
+                        label {
+                            class: "instance-settings-toggle",
+                            input {
+                                r#type: "checkbox",
+                                checked: fullscreen(),
+                                onchange: move |e| fullscreen.set(e.checked()),
+                            }
+                            "Fullscreen"
+                        }
+                    }
+
+                    div {
+                        class: "rename-input-section",
+                        div {
+                            class: "rename-input-label",
+                            "JVM flags:"
+                        }
+                        div {
+                            class: "instance-settings-row",
+                            for option in JvmPreset::BUILT_IN.into_iter().chain([JvmPreset::Custom(String::new())]) {
+                                button {
+                                    key: "{option.display_name()}",
+                                    class: if std::mem::discriminant(&preset()) == std::mem::discriminant(&option) {
+                                        "instance-settings-preset selected"
+                                    } else {
+                                        "instance-settings-preset"
+                                    },
+                                    title: match option.min_java_version() {
+                                        Some(java) => format!("Needs Java {java} or newer"),
+                                        None => String::new(),
+                                    },
+                                    onclick: move |e: Event<MouseData>| {
+                                        e.stop_propagation();
+                                        preset.set(option.clone());
+                                    },
+                                    "{option.display_name()}"
+                                }
+                            }
+                        }
+                        if matches!(preset(), JvmPreset::Custom(_)) {
+                            input {
+                                r#type: "text",
+                                class: "rename-input",
+                                value: "{custom_args()}",
+                                placeholder: "-XX:+UseG1GC -Dfile.encoding=UTF-8",
+                                oninput: move |e| custom_args.set(e.value()),
+                                onkeydown: handle_key_press,
+                            }
+                        }
+                        if let Some(message) = error_message() {
+                            div { class: "instance-settings-error", "{message}" }
+                        }
+                    }
+                }
+
+                // Actions
+                div {
+                    class: "rename-dialog-actions",
+                    button {
+                        class: "rename-action-btn cancel",
+                        onclick: move |e: Event<MouseData>| {
+                            e.stop_propagation();
+                            show.set(false);
+                        },
+                        "Cancel"
+                    }
+                    button {
+                        class: "rename-action-btn rename",
+                        onclick: handle_save_click,
+                        "Save"
+                    }
+                }
+            }
+        }
+    }
+}
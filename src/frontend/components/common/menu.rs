@@ -1,16 +1,21 @@
 //! Menu component.
 
+use crate::backend::services::SavedServer;
 use crate::frontend::services::context::AuthState;
 use crate::frontend::services::states::GameStatus;
 use crate::{
-    backend::utils::css::ResourceLoader,
+    backend::utils::{application::Route, css::ResourceLoader},
     frontend::{
         components::layout::main::install_and_launch_instance,
-        services::instances::{INSTANCES, InstanceManager, open_instance_folder},
-        services::states::set_instance_running,
+        services::instances::{
+            INSTANCES, InstanceManager, change_instance_icon, export_instance, list_servers,
+            list_worlds, open_instance_folder, repair_instance, stop_instance,
+        },
+        services::states::{game_pid, set_instance_running},
     },
 };
 use dioxus::prelude::*;
+use dioxus_router::navigator;
 use log::{error, info};
 
 #[derive(Props, Clone, PartialEq, Eq)]
@@ -24,6 +29,8 @@ pub struct ContextMenuProps {
     pub show_rename_dialog: Signal<bool>,
     pub rename_instance_id: Signal<Option<u32>>,
     pub rename_current_name: Signal<String>,
+    pub show_settings_dialog: Signal<bool>,
+    pub settings_instance_id: Signal<Option<u32>>,
     pub active_instance_id: Signal<Option<u32>>,
 }
 
@@ -38,12 +45,19 @@ pub fn ContextMenu(props: ContextMenuProps) -> Element {
     let mut rename_instance_id = props.rename_instance_id;
     let auth = use_context::<AuthState>();
     let mut rename_current_name = props.rename_current_name;
+    let mut show_settings_dialog = props.show_settings_dialog;
+    let mut settings_instance_id = props.settings_instance_id;
+    let mut worlds = use_signal(|| None::<Vec<String>>);
+    let mut servers = use_signal(|| None::<Vec<SavedServer>>);
     let mut is_hiding = use_signal(|| false);
     let mut should_render = use_signal(|| false);
 
     // Watch for show changes and handle animation
     use_effect(move || {
         if show() {
+            // The world and server lists are loaded again every time their submenu is opened
+            worlds.set(None);
+            servers.set(None);
             should_render.set(true);
             is_hiding.set(false);
         } else if should_render() {
@@ -108,10 +122,99 @@ pub fn ContextMenu(props: ContextMenuProps) -> Element {
                 username,
                 id,
                 props.active_instance_id,
+                None,
+                None,
             ));
         }
     };
 
+    let handle_stop_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if let Some(id) = instance_id() {
+            info!("Stop clicked for instance {id}");
+            stop_instance(id);
+        }
+        show.set(false);
+    };
+
+    let handle_play_world_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if worlds().is_some() {
+            worlds.set(None);
+            return;
+        }
+        if let Some(id) = instance_id() {
+            spawn(async move {
+                match list_worlds(id).await {
+                    Ok(list) => worlds.set(Some(list)),
+                    Err(e) => error!("Failed to list worlds of instance {id}: {e}"),
+                }
+            });
+        }
+    };
+
+    let handle_join_server_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if servers().is_some() {
+            servers.set(None);
+            return;
+        }
+        if let Some(id) = instance_id() {
+            spawn(async move {
+                match list_servers(id).await {
+                    Ok(list) => servers.set(Some(list)),
+                    Err(e) => error!("Failed to list servers of instance {id}: {e}"),
+                }
+            });
+        }
+    };
+
+    let mut join_server = move |address: String| {
+        let Some(id) = instance_id() else {
+            return;
+        };
+        let Some(version) = INSTANCES.read().get(&id).map(|i| i.version.clone()) else {
+            error!("Instance {id} not found in loaded instances");
+            return;
+        };
+        info!("Joining server {address} with instance {id}");
+
+        // Immediately mark as running to prevent race conditions
+        set_instance_running(id, true);
+        show.set(false);
+        spawn(install_and_launch_instance(
+            version,
+            auth.get_username(),
+            id,
+            props.active_instance_id,
+            Some(address),
+            None,
+        ));
+    };
+
+    let mut launch_world = move |world: String| {
+        let Some(id) = instance_id() else {
+            return;
+        };
+        let Some(version) = INSTANCES.read().get(&id).map(|i| i.version.clone()) else {
+            error!("Instance {id} not found in loaded instances");
+            return;
+        };
+        info!("Opening world {world} of instance {id}");
+
+        // Immediately mark as running to prevent race conditions
+        set_instance_running(id, true);
+        show.set(false);
+        spawn(install_and_launch_instance(
+            version,
+            auth.get_username(),
+            id,
+            props.active_instance_id,
+            None,
+            Some(world),
+        ));
+    };
+
     let handle_folder_click = move |e: Event<MouseData>| {
         e.stop_propagation();
         if let Some(id) = instance_id() {
@@ -125,6 +228,61 @@ pub fn ContextMenu(props: ContextMenuProps) -> Element {
         }
     };
 
+    let handle_screenshots_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if let Some(id) = instance_id() {
+            info!("Screenshots clicked for instance {id}");
+            navigator().push(Route::Screenshots { instance_id: id });
+        }
+        show.set(false);
+    };
+
+    let handle_mods_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if let Some(id) = instance_id() {
+            info!("Mods clicked for instance {id}");
+            navigator().push(Route::Mods { instance_id: id });
+        }
+        show.set(false);
+    };
+
+    let handle_icon_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if let Some(id) = instance_id() {
+            info!("Change icon clicked for instance {id}");
+            change_instance_icon(id);
+        }
+        show.set(false);
+    };
+
+    let handle_export_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if let Some(id) = instance_id() {
+            info!("Export clicked for instance {id}");
+            export_instance(id);
+        }
+        show.set(false);
+    };
+
+    let handle_settings_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if let Some(id) = instance_id() {
+            info!("Settings clicked for instance {id}");
+            settings_instance_id.set(Some(id));
+            show_settings_dialog.set(true);
+        }
+        show.set(false);
+    };
+
+    let handle_repair_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if let Some(id) = instance_id() {
+            info!("Repair clicked for instance {id}");
+            repair_instance(id);
+        }
+        show.set(false);
+    };
+
     let handle_change_click = move |e: Event<MouseData>| {
         e.stop_propagation();
         if let Some(id) = instance_id() {
@@ -141,6 +299,15 @@ pub fn ContextMenu(props: ContextMenuProps) -> Element {
         show.set(false);
     };
 
+    let handle_duplicate_click = move |e: Event<MouseData>| {
+        e.stop_propagation();
+        if let Some(id) = instance_id() {
+            info!("Duplicate clicked for instance {id}");
+            InstanceManager::clone_instance(id);
+        }
+        show.set(false);
+    };
+
     let handle_delete_click = move |e: Event<MouseData>| {
         e.stop_propagation();
         if let Some(id) = instance_id() {
@@ -165,6 +332,12 @@ pub fn ContextMenu(props: ContextMenuProps) -> Element {
 
     // Check if we have an instance selected and if debug mode is enabled
     let has_instance = instance_id().is_some();
+    // The game can be stopped once the launcher script has reported its PID
+    let is_game_running = instance_id().and_then(game_pid).is_some();
+    // Older versions cannot open a world on startup, so the submenu is hidden for them
+    let supports_quick_play = instance_id()
+        .and_then(|id| INSTANCES.read().get(&id).map(|i| i.supports_quick_play()))
+        .unwrap_or(false);
 
     rsx! {
         div {
@@ -185,6 +358,84 @@ pub fn ContextMenu(props: ContextMenuProps) -> Element {
                     div { class: "context-menu-text", "Run" }
                 }
 
+                if is_game_running {
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_stop_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("close") }
+                        }
+                        div { class: "context-menu-text", "Stop" }
+                    }
+                }
+
+                if supports_quick_play {
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_play_world_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("play") }
+                        }
+                        div { class: "context-menu-text", "Play world…" }
+                    }
+
+                    if let Some(list) = worlds() {
+                        div { class: "context-menu-submenu",
+                            if list.is_empty() {
+                                div { class: "context-menu-submenu-empty", "No worlds yet" }
+                            }
+                            for world in list {
+                                button {
+                                    key: "{world}",
+                                    class: "context-menu-button context-menu-submenu-button",
+                                    onclick: {
+                                        let world = world.clone();
+                                        move |e: Event<MouseData>| {
+                                            e.stop_propagation();
+                                            launch_world(world.clone());
+                                        }
+                                    },
+                                    div { class: "context-menu-text", "{world}" }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if has_instance {
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_join_server_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("play") }
+                        }
+                        div { class: "context-menu-text", "Join server…" }
+                    }
+
+                    if let Some(list) = servers() {
+                        div { class: "context-menu-submenu",
+                            if list.is_empty() {
+                                div { class: "context-menu-submenu-empty", "No servers yet" }
+                            }
+                            for server in list {
+                                button {
+                                    key: "{server.address}",
+                                    class: "context-menu-button context-menu-submenu-button",
+                                    title: "{server.address}",
+                                    onclick: {
+                                        let address = server.address.clone();
+                                        move |e: Event<MouseData>| {
+                                            e.stop_propagation();
+                                            join_server(address.clone());
+                                        }
+                                    },
+                                    div { class: "context-menu-text", "{server.name}" }
+                                }
+                            }
+                        }
+                    }
+                }
+
                 button {
                     class: "context-menu-button",
                     onclick: handle_folder_click,
@@ -194,6 +445,53 @@ pub fn ContextMenu(props: ContextMenuProps) -> Element {
                     div { class: "context-menu-text", "Folder" }
                 }
 
+                if has_instance {
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_screenshots_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("open") }
+                        }
+                        div { class: "context-menu-text", "Screenshots" }
+                    }
+
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_mods_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("open") }
+                        }
+                        div { class: "context-menu-text", "Mods" }
+                    }
+
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_export_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("folder") }
+                        }
+                        div { class: "context-menu-text", "Export" }
+                    }
+
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_settings_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("settings") }
+                        }
+                        div { class: "context-menu-text", "Settings" }
+                    }
+
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_repair_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("debug") }
+                        }
+                        div { class: "context-menu-text", "Repair" }
+                    }
+                }
+
                 if has_instance {
                     button {
                         class: "context-menu-button",
@@ -204,6 +502,26 @@ pub fn ContextMenu(props: ContextMenuProps) -> Element {
                         div { class: "context-menu-text", "Change" }
                     }
 
+                    button {
+                        class: "context-menu-button",
+                        onclick: handle_icon_click,
+                        div { class: "context-menu-icon",
+                            img { src: ResourceLoader::get_asset("change") }
+                        }
+                        div { class: "context-menu-text", "Change icon" }
+                    }
+
+                    if InstanceManager::can_create_instance() {
+                        button {
+                            class: "context-menu-button",
+                            onclick: handle_duplicate_click,
+                            div { class: "context-menu-icon",
+                                img { src: ResourceLoader::get_asset("plus") }
+                            }
+                            div { class: "context-menu-text", "Duplicate" }
+                        }
+                    }
+
                     button {
                         class: "context-menu-button",
                         onclick: handle_delete_click,
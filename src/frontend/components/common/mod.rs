@@ -1,7 +1,9 @@
 //! Common reusable components.
 
+pub mod crash_banner;
 pub mod debug;
 pub mod game_progress;
+pub mod instance_settings;
 pub mod logo;
 pub mod menu;
 pub mod news;
@@ -10,8 +12,10 @@ pub mod renamer;
 pub mod selector;
 pub mod titlebar;
 
+pub use crash_banner::CrashBanner;
 pub use debug::DebugWindow;
 pub use game_progress::GameProgress;
+pub use instance_settings::InstanceSettingsDialog;
 pub use logo::Logo;
 pub use menu::ContextMenu;
 pub use news::News;
@@ -1,9 +1,14 @@
 //! News component.
 
+use crate::backend::services::NewsItem;
+use crate::frontend::services::news::get_news;
+use chrono::NaiveDate;
 use dioxus::prelude::*;
+use log::{error, warn};
 
+/// Launcher announcement bundled with the app, shown when the news feed is unavailable.
 #[derive(Debug, Clone)]
-struct NewsItem {
+struct BundledNews {
     date: String,
     content: String,
 }
@@ -62,7 +67,7 @@ fn markdown_to_html(markdown: &str) -> String {
     html
 }
 
-fn parse_markdown() -> Vec<NewsItem> {
+fn parse_markdown() -> Vec<BundledNews> {
     let markdown_content =
         include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/news/news.md"));
 
@@ -95,7 +100,7 @@ fn parse_markdown() -> Vec<NewsItem> {
         let html_content = markdown_to_html(content_md);
 
         if !html_content.is_empty() {
-            news_items.push(NewsItem {
+            news_items.push(BundledNews {
                 date,
                 content: html_content,
             });
@@ -107,20 +112,66 @@ fn parse_markdown() -> Vec<NewsItem> {
     news_items
 }
 
+/// Format a `YYYY-MM-DD` feed date like `7 June 2025`.
+fn format_date(date: &str) -> String {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_or_else(|_| date.to_string(), |d| d.format("%-d %B %Y").to_string())
+}
+
 #[component]
 pub fn News(animations_played: bool) -> Element {
-    let news_items = use_signal(parse_markdown);
+    let bundled_news = use_signal(parse_markdown);
+    let mut feed = use_signal(Vec::<NewsItem>::new);
+
+    use_effect(move || {
+        spawn(async move {
+            match get_news().await {
+                Ok(items) => feed.set(items),
+                Err(e) => warn!("Failed to load news, showing launcher news: {e}"),
+            }
+        });
+    });
 
     rsx! {
         div {
             class: if !animations_played { "news-block news-animate" } else { "news-block" },
 
-            for (index, item) in news_items().iter().enumerate() {
-                div {
-                    key: "{index}",
-                    class: "news-item",
-                    div { class: "news-date", "{item.date}" }
-                    div { dangerous_inner_html: "{item.content}" }
+            if feed.read().is_empty() {
+                for (index, item) in bundled_news().iter().enumerate() {
+                    div {
+                        key: "{index}",
+                        class: "news-item",
+                        div { class: "news-date", "{item.date}" }
+                        div { dangerous_inner_html: "{item.content}" }
+                    }
+                }
+            } else {
+                for item in feed() {
+                    div {
+                        key: "{item.title}",
+                        class: "news-item news-link",
+                        onclick: {
+                            let link = item.link.clone();
+                            move |_| {
+                                let Some(link) = link.clone() else {
+                                    return;
+                                };
+                                spawn(async move {
+                                    if let Err(e) = webbrowser::open(&link) {
+                                        error!("Failed to open browser: {e}");
+                                    }
+                                });
+                            }
+                        },
+                        if let Some(image_url) = &item.image_url {
+                            img { class: "news-image", src: "{image_url}" }
+                        }
+                        div { class: "news-date", "{format_date(&item.date)}" }
+                        h1 { "{item.title}" }
+                        if !item.text.is_empty() {
+                            p { "{item.text}" }
+                        }
+                    }
                 }
             }
         }
@@ -1,6 +1,9 @@
 //! Instance rename dialog component.
 
-use crate::{backend::utils::css::ResourceLoader, frontend::services::instances::InstanceManager};
+use crate::{
+    backend::{services::MAX_NAME_LENGTH, utils::css::ResourceLoader},
+    frontend::services::instances::InstanceManager,
+};
 use dioxus::prelude::*;
 
 #[derive(Props, Clone, PartialEq, Eq)]
@@ -63,7 +66,7 @@ pub fn RenameDialog(props: RenameDialogProps) -> Element {
     };
 
     let handle_input_change = move |e: Event<FormData>| {
-        let value: String = e.value().chars().take(8).collect();
+        let value: String = e.value().chars().take(MAX_NAME_LENGTH).collect();
         new_name.set(value);
     };
 
@@ -148,14 +151,14 @@ pub fn RenameDialog(props: RenameDialogProps) -> Element {
                             class: "rename-input",
                             value: "{new_name()}",
                             placeholder: "Enter new name...",
-                            maxlength: "8",
+                            maxlength: "{MAX_NAME_LENGTH}",
                             autofocus: true,
                             oninput: handle_input_change,
                             onkeydown: handle_key_press,
                         }
                         div {
                             class: "rename-char-count",
-                            "{new_name().len()}/8"
+                            "{new_name().len()}/{MAX_NAME_LENGTH}"
                         }
                     }
                 }
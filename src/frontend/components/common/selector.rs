@@ -1,9 +1,13 @@
 //! Minecraft version selector component.
 
 use crate::{
-    backend::services::VersionInfo,
+    backend::services::{LauncherSettings, ModLoader, VersionFilter, VersionInfo, VisitTracker},
     backend::utils::css::ResourceLoader,
-    frontend::services::{instances::InstanceManager, launcher},
+    frontend::services::{
+        instances::InstanceManager,
+        launcher::{self, filter_versions},
+        settings::{SETTINGS, save_settings},
+    },
 };
 
 use dioxus::prelude::*;
@@ -21,7 +25,10 @@ pub fn Selector(props: SelectorProps) -> Element {
     let available_versions = use_signal(Vec::<VersionInfo>::new);
     let mut filtered_versions = use_signal(Vec::<VersionInfo>::new);
     let mut is_loading = use_signal(|| false);
-    let mut version_filter = use_signal(|| "all".to_string()); // all, release, snapshot, beta, alpha
+    let mut refreshed_at = use_signal(|| None::<u64>);
+    let mut version_filter = use_signal(|| SETTINGS.read().version_filter);
+    let mut search = use_signal(String::new);
+    let mut selected_loader = use_signal(ModLoader::default);
     let mut showing = use_signal(|| false);
     let mut hiding = use_signal(|| false);
 
@@ -39,6 +46,10 @@ pub fn Selector(props: SelectorProps) -> Element {
                     }
                     Err(e) => error!("Failed to get version manifest: {e}"),
                 }
+                match launcher::manifest_refreshed_at().await {
+                    Ok(timestamp) => refreshed_at.set(timestamp),
+                    Err(e) => error!("Failed to read version manifest age: {e}"),
+                }
                 is_loading.set(false);
             });
         }
@@ -46,32 +57,32 @@ pub fn Selector(props: SelectorProps) -> Element {
 
     // Filter versions based on selected filter
     use_effect(move || {
-        let filter = version_filter.read().clone();
-        let all_versions = available_versions.read().clone();
-
-        let filtered: Vec<VersionInfo> = match filter.as_str() {
-            "release" => all_versions
-                .into_iter()
-                .filter(|v| v.version_type == "release")
-                .collect(),
-            "snapshot" => all_versions
-                .into_iter()
-                .filter(|v| v.version_type == "snapshot")
-                .collect(),
-            "beta" => all_versions
-                .into_iter()
-                .filter(|v| v.version_type == "old_beta")
-                .collect(),
-            "alpha" => all_versions
-                .into_iter()
-                .filter(|v| v.version_type == "old_alpha")
-                .collect(),
-            _ => all_versions, // "all" or any other value
-        };
-
+        let filtered =
+            filter_versions(&available_versions.read(), version_filter(), &search.read());
         filtered_versions.set(filtered);
     });
 
+    // Keep the filter in sync once settings finish loading
+    use_effect(move || {
+        version_filter.set(SETTINGS.read().version_filter);
+    });
+
+    // Remember the filter for the next time the selector opens
+    let mut toggle_filter = move |toggle: fn(&mut VersionFilter)| {
+        let mut filter = version_filter();
+        toggle(&mut filter);
+        version_filter.set(filter);
+        let settings = LauncherSettings {
+            version_filter: filter,
+            ..SETTINGS.read().clone()
+        };
+        spawn(async move {
+            if let Err(e) = save_settings(settings).await {
+                error!("Failed to save version filter: {e}");
+            }
+        });
+    };
+
     // Handle show/hide animations
     use_effect(move || {
         if show() {
@@ -89,7 +100,7 @@ pub fn Selector(props: SelectorProps) -> Element {
 
     let handle_select_click = move |_| {
         let version = selected_version.read().clone();
-        match InstanceManager::create_instance_with_version(&version) {
+        match InstanceManager::create_instance_with_version(&version, selected_loader()) {
             Some(_) => {}
             None => error!("Failed to create instance"),
         }
@@ -118,7 +129,14 @@ pub fn Selector(props: SelectorProps) -> Element {
                 // Header
                 div {
                     class: "version-selector-header",
-                    h3 { class: "version-selector-title", "Select Minecraft version" }
+                    div {
+                        h3 { class: "version-selector-title", "Select Minecraft version" }
+                        if let Some(timestamp) = refreshed_at() {
+                            div { class: "version-selector-updated",
+                                "Version list updated {VisitTracker::format_elapsed(timestamp)}"
+                            }
+                        }
+                    }
                     button {
                         class: "version-selector-close",
                         onclick: move |_| show.set(false),
@@ -130,29 +148,44 @@ pub fn Selector(props: SelectorProps) -> Element {
                 div {
                     class: "version-selector-filters",
                     button {
-                        class: format!("version-filter-btn{}", if version_filter() == "all" { " active" } else { "" }),
-                        onclick: move |_| version_filter.set("all".to_string()),
-                        "All"
-                    }
-                    button {
-                        class: format!("version-filter-btn{}", if version_filter() == "release" { " active" } else { "" }),
-                        onclick: move |_| version_filter.set("release".to_string()),
+                        class: format!("version-filter-btn{}", if version_filter().releases { " active" } else { "" }),
+                        onclick: move |_| toggle_filter(|f| f.releases = !f.releases),
                         "Releases"
                     }
                     button {
-                        class: format!("version-filter-btn{}", if version_filter() == "snapshot" { " active" } else { "" }),
-                        onclick: move |_| version_filter.set("snapshot".to_string()),
+                        class: format!("version-filter-btn{}", if version_filter().snapshots { " active" } else { "" }),
+                        onclick: move |_| toggle_filter(|f| f.snapshots = !f.snapshots),
                         "Snapshots"
                     }
                     button {
-                        class: format!("version-filter-btn{}", if version_filter() == "beta" { " active" } else { "" }),
-                        onclick: move |_| version_filter.set("beta".to_string()),
-                        "Betas"
+                        class: format!("version-filter-btn{}", if version_filter().old_betas { " active" } else { "" }),
+                        onclick: move |_| toggle_filter(|f| f.old_betas = !f.old_betas),
+                        "Beta"
                     }
                     button {
-                        class: format!("version-filter-btn{}", if version_filter() == "alpha" { " active" } else { "" }),
-                        onclick: move |_| version_filter.set("alpha".to_string()),
-                        "Alphas"
+                        class: format!("version-filter-btn{}", if version_filter().old_alphas { " active" } else { "" }),
+                        onclick: move |_| toggle_filter(|f| f.old_alphas = !f.old_alphas),
+                        "Alpha"
+                    }
+                    input {
+                        class: "version-search",
+                        r#type: "text",
+                        placeholder: "Search",
+                        value: "{search}",
+                        oninput: move |e| search.set(e.value()),
+                    }
+                }
+
+                // Mod loader buttons
+                div {
+                    class: "version-selector-filters",
+                    for loader in ModLoader::ALL {
+                        button {
+                            key: "{loader.id()}",
+                            class: format!("version-filter-btn{}", if selected_loader() == loader { " active" } else { "" }),
+                            onclick: move |_| selected_loader.set(loader),
+                            "{loader.display_name()}"
+                        }
                     }
                 }
 
@@ -191,7 +224,7 @@ pub fn Selector(props: SelectorProps) -> Element {
                                 }
                             }
                             if filtered_versions.read().is_empty() && !*is_loading.read() {
-                                div { class: "version-list-empty", "No versions available for selected filter" }
+                                div { class: "version-list-empty", "No versions match the filter" }
                             }
                         }
                     }
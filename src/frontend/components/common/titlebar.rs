@@ -1,7 +1,17 @@
 //! Custom titlebar with window controls.
 
 use crate::backend::utils::css::ResourceLoader;
+use crate::backend::utils::window::WindowGeometry;
 use dioxus::prelude::*;
+use log::warn;
+
+/// Save the size and position of the launcher window for the next start.
+pub fn save_window_geometry() {
+    let window = dioxus_desktop::window();
+    if let Err(e) = WindowGeometry::of(&window.window).save() {
+        warn!("Failed to save window geometry: {e}");
+    }
+}
 
 #[component]
 pub fn TitleBar() -> Element {
@@ -22,6 +32,21 @@ pub fn TitleBar() -> Element {
             }
         }
 
+        // The window has no system frame, so it is resized from this corner
+        div {
+            class: "window-resize-grip",
+            onmousedown: move |_event| {
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    use dioxus_desktop::tao::window::ResizeDirection;
+                    let window = dioxus_desktop::window();
+                    if let Err(e) = window.drag_resize_window(ResizeDirection::SouthEast) {
+                        warn!("Failed to resize window: {e}");
+                    }
+                }
+            }
+        }
+
         div {
             class: "window-controls window-controls-windows",
             button {
@@ -51,6 +76,7 @@ pub fn TitleBar() -> Element {
                     spawn(async move {
                         #[cfg(not(target_arch = "wasm32"))]
                         {
+                            save_window_geometry();
                             let window = dioxus_desktop::window();
                             window.close();
                         }
@@ -1,28 +1,42 @@
 //! Main layout component.
 
 use crate::backend::communicator::communicator::Communicator;
-use crate::backend::services::VisitTracker;
+use crate::backend::services::launcher::InstallCancelled;
+use crate::backend::services::{LauncherSettings, VisitTracker};
 use crate::backend::utils::application::Route;
+use crate::backend::utils::crash::diagnose_crash;
 use crate::backend::utils::css::ResourceLoader;
+use crate::backend::utils::error::error_kind;
+use crate::backend::utils::java::JavaVersionMismatch;
 use crate::frontend::components::common::titlebar::TitleBar;
-use crate::frontend::components::common::{ContextMenu, DebugWindow, RenameDialog};
+use crate::frontend::components::common::{
+    ContextMenu, DebugWindow, InstanceSettingsDialog, RenameDialog,
+};
+use crate::frontend::services::avatars::AvatarManager;
 use crate::frontend::services::context::AuthState;
+use crate::frontend::services::settings::{SETTINGS, save_settings};
 use crate::frontend::{
     components::{
-        common::{GameProgress, Logo, News, Selector, UpdateProgress},
+        common::{CrashBanner, GameProgress, Logo, News, Selector, UpdateProgress},
         layout::Navigation,
     },
-    services::instances::InstanceManager,
+    services::instances::{
+        InstanceManager, icon_data_url, latest_crash_report, parse_server_connection,
+    },
     services::states::{
-        GameStatus, ProgressStatus, set_game_progress_state, set_game_progress_state_simple,
-        set_instance_running, use_game_progress_state, use_game_state, use_update_state,
+        CRASH_REPORT, CrashReport, GameStatus, ProgressStatus, set_game_pid,
+        set_game_progress_state, set_game_progress_state_simple, set_instance_running,
+        take_game_pid, use_game_progress_state, use_game_state, use_update_state,
     },
 };
 use dioxus::prelude::{Key, *};
 use dioxus_router::{components::Outlet, navigator, use_route};
-use log::error;
+use log::{error, info};
 use webbrowser;
 
+/// Number of game log lines kept to find the cause of a crash.
+const CRASH_LOG_TAIL: usize = 200;
+
 #[component]
 pub fn Layout() -> Element {
     let mut show_ui = use_signal(|| false);
@@ -37,7 +51,7 @@ pub fn Layout() -> Element {
     let (show_update, progress, status) = use_update_state();
 
     // Game progress state
-    let (show_game_progress, game_progress, game_status_text, game_status_type, _game_instance_id) =
+    let (show_game_progress, game_progress, game_status_text, game_status_type, game_instance_id) =
         use_game_progress_state();
 
     // Visit the tracker with reactive signals
@@ -62,6 +76,24 @@ pub fn Layout() -> Element {
         InstanceManager::initialize();
     });
 
+    // Load launcher settings
+    use_effect(move || {
+        spawn(async {
+            if let Err(e) = crate::frontend::services::settings::load_settings().await {
+                error!("Failed to load launcher settings: {e}");
+            }
+        });
+    });
+
+    // Head of the signed-in player, shown on the settings page
+    let mut avatar = use_signal(AvatarManager::default_avatar);
+    use_effect(move || {
+        let username = auth.get_username();
+        spawn(async move {
+            avatar.set(AvatarManager::get(&username).await);
+        });
+    });
+
     // Watch for instance deletions and clear context menu if needed
     use_effect(move || {
         use crate::frontend::services::instances::INSTANCES;
@@ -89,10 +121,16 @@ pub fn Layout() -> Element {
     let rename_instance_id = use_signal(|| None::<u32>);
     let rename_current_name = use_signal(String::new);
 
+    // Instance settings dialog state
+    let show_settings_dialog = use_signal(|| false);
+    let settings_instance_id = use_signal(|| None::<u32>);
+
     // Determine current page and update last active if not in chat
     let current_page = match route {
         Route::Home { .. } | Route::Auth { .. } => "Home",
         Route::Packs { .. } => "Packs",
+        Route::Screenshots { .. } => "Screenshots",
+        Route::Mods { .. } => "Mods",
         Route::Settings { .. } => "Settings",
         Route::Cloud { .. } => "Cloud",
         Route::New { .. } => "New",
@@ -105,6 +143,18 @@ pub fn Layout() -> Element {
     }
 
     let is_home = current_page == "Home";
+
+    // Most recently played instances
+    let recent_instances: Vec<_> = InstanceManager::get_instances_by_recency()
+        .into_iter()
+        .filter_map(|instance| Some((instance.last_played?, instance)))
+        .take(3)
+        .collect();
+    let shift_class = if recent_instances.is_empty() {
+        ""
+    } else {
+        "with-last-connections"
+    };
     let is_settings = current_page == "Settings";
     let is_new = current_page == "New";
 
@@ -131,6 +181,10 @@ pub fn Layout() -> Element {
         style {
             dangerous_inner_html: ResourceLoader::get_embedded_css_with_fonts()
         }
+        // Comes after the embedded styles so the theme overrides their defaults
+        style {
+            dangerous_inner_html: SETTINGS.read().theme_css()
+        }
 
         TitleBar {}
 
@@ -144,9 +198,12 @@ pub fn Layout() -> Element {
             show: show_game_progress(),
             progress: game_progress(),
             status: game_status_text(),
-            status_type: game_status_type()
+            status_type: game_status_type(),
+            instance_id: game_instance_id()
         }
 
+        CrashBanner {}
+
         div {
             class: if show_ui() { "desktop fade-in" } else { "desktop fade-out" },
             tabindex: "0",
@@ -172,60 +229,70 @@ pub fn Layout() -> Element {
 
                 div { class: if !animations_played() { "center-block center-animate" } else { "center-block" },
                     if is_home {
-                        // Temporarily hidden last connections section
-                        /*
-                        div { class: "last-connections-title", "Last connections" }
-                        div { class: "last-connections-divider" }
-
-                        // Connection cards
-                        div { class: "connection-card connection-card-1" }
-                        div { class: "connection-card connection-card-2" }
-                        div { class: "connection-card connection-card-3" }
-
-                        // Server icons
-                        div { class: "server-icon server-icon-1" }
-                        div { class: "server-icon server-icon-2" }
-                        div { class: "server-icon server-icon-3" }
-
-                        // Server names
-                        div { class: "server-name server-name-1", "Server 1" }
-                        div { class: "server-name server-name-2", "Server 2" }
-                        div { class: "server-name server-name-3", "Server 3" }
-
-                        // Server last played
-                        div { class: "server-last-played server-last-played-1", "Last played: 15m ago" }
-                        div { class: "server-last-played server-last-played-2", "Last played: 15m ago" }
-                        div { class: "server-last-played server-last-played-3", "Last played: 15m ago" }
-
-                        // Last connection play buttons
-                        div { class: "last-connection-play last-connection-play-1",
-                            img { src: ResourceLoader::get_asset("play"), class: "play-icon" }
-                            div { class: "play-text", "Play" }
-                        }
-                        div { class: "last-connection-play last-connection-play-2",
-                            img { src: ResourceLoader::get_asset("play"), class: "play-icon" }
-                            div { class: "play-text", "Play" }
-                        }
-                        div { class: "last-connection-play last-connection-play-3",
-                            img { src: ResourceLoader::get_asset("play"), class: "play-icon" }
-                            div { class: "play-text", "Play" }
+                        if !recent_instances.is_empty() {
+                            div { class: "last-connections-title", "Last connections" }
+                            div { class: "last-connections-divider" }
                         }
 
-                        // Additional buttons
-                        img { src: ResourceLoader::get_asset("additional"), class: "additional-button additional-button-1" }
-                        img { src: ResourceLoader::get_asset("additional"), class: "additional-button additional-button-2" }
-                        img { src: ResourceLoader::get_asset("additional"), class: "additional-button additional-button-3" }
-                        */
+                        for (index, (last_played, instance)) in recent_instances.iter().enumerate() {
+                            div {
+                                key: "recent-{instance.id}",
+                                class: "connection-card connection-card-{index + 1}"
+                            }
+                            div {
+                                class: "server-icon server-icon-{index + 1}",
+                                style: match icon_data_url(instance) {
+                                    Some(icon) => format!("background: url('{icon}') center / cover"),
+                                    None => format!("background: #{}", instance.color),
+                                }
+                            }
+                            // Cards join the last server played on the instance, if any
+                            if let Some(server) = instance.recent_servers.first() {
+                                div { class: "server-name server-name-{index + 1}", "{server}" }
+                                div {
+                                    class: "server-last-played server-last-played-{index + 1}",
+                                    "{instance.name} · {VisitTracker::format_elapsed(*last_played)}"
+                                }
+                            } else {
+                                div { class: "server-name server-name-{index + 1}", "{instance.name}" }
+                                div {
+                                    class: "server-last-played server-last-played-{index + 1}",
+                                    "Last played: {VisitTracker::format_elapsed(*last_played)}"
+                                }
+                            }
+                            div {
+                                class: "last-connection-play last-connection-play-{index + 1}",
+                                onclick: {
+                                    let instance_version = instance.version.clone();
+                                    let instance_id = instance.id;
+                                    let server_address = instance.recent_servers.first().cloned();
+                                    move |_| {
+                                        set_instance_running(instance_id, true);
+                                        active_instance_id.set(Some(instance_id));
+                                        spawn(install_and_launch_instance(
+                                            instance_version.clone(),
+                                            auth.get_username(),
+                                            instance_id,
+                                            active_instance_id,
+                                            server_address.clone(),
+                                            None
+                                        ));
+                                    }
+                                },
+                                img { src: ResourceLoader::get_asset("play"), class: "play-icon" }
+                                div { class: "play-text", "Play" }
+                            }
+                        }
 
-                        div { class: "instances-title", "Instances" }
-                        div { class: "instances-divider" }
+                        div { class: "instances-title {shift_class}", "Instances" }
+                        div { class: "instances-divider {shift_class}" }
 
                         // Instance cards container
                         div {
-                            class: "instances-container",
+                            class: "instances-container {shift_class}",
 
                             // Render existing instances
-                            for (_index, instance) in InstanceManager::get_instances_sorted().iter().enumerate() {
+                            for (_index, instance) in InstanceManager::get_instances_by_recency().iter().enumerate() {
                                 div {
                                     key: "{instance.id}",
                                     class: {
@@ -235,7 +302,13 @@ pub fn Layout() -> Element {
                                         }
                                         classes.join(" ")
                                     },
-                                    style: format!("--instance-color: #{}", instance.color),
+                                    style: match icon_data_url(instance) {
+                                        Some(icon) => format!(
+                                            "--instance-color: #{}; background-image: url('{icon}'); background-size: cover; background-position: center;",
+                                            instance.color
+                                        ),
+                                        None => format!("--instance-color: #{}", instance.color),
+                                    },
                                     onclick: {
                                         let instance_version = instance.version.clone();
                                         let instance_id = instance.id;
@@ -251,7 +324,9 @@ pub fn Layout() -> Element {
                                                 instance_version.clone(),
                                                 username,
                                                 instance_id,
-                                                active_instance_id
+                                                active_instance_id,
+                                                None,
+                                                None
                                             ));
                                         }
                                     },
@@ -283,6 +358,13 @@ pub fn Layout() -> Element {
                                         },
                                         "{instance.name}"
                                     }
+
+                                    div { class: "instance-card-last-played",
+                                        match instance.last_played {
+                                            Some(timestamp) => format!("Last played: {}", VisitTracker::format_elapsed(timestamp)),
+                                            None => "Never played".to_string(),
+                                        }
+                                    }
                                 }
                             }
 
@@ -351,15 +433,9 @@ pub fn Layout() -> Element {
 
                 div {
                     class: "settings-server-icon",
-                    {
-                        let username = auth.get_username();
-                        let display_name = if username.is_empty() { "cubelius" } else { &username };
-                        rsx! {
-                            img {
-                                src: format!("https://minotar.net/helm/{display_name}/49.png"),
-                                style: "width: 49px; height: 49px; border-radius: 8px; object-fit: cover;"
-                            }
-                        }
+                    img {
+                        src: avatar(),
+                        style: "width: 49px; height: 49px; border-radius: 8px; object-fit: cover; image-rendering: pixelated;"
                     }
                 }
 
@@ -500,6 +576,8 @@ pub fn Layout() -> Element {
                 show_rename_dialog: show_rename_dialog,
                 rename_instance_id: rename_instance_id,
                 rename_current_name: rename_current_name,
+                show_settings_dialog: show_settings_dialog,
+                settings_instance_id: settings_instance_id,
                 active_instance_id: active_instance_id
             }
 
@@ -509,6 +587,11 @@ pub fn Layout() -> Element {
                 current_name: rename_current_name
             }
 
+            InstanceSettingsDialog {
+                show: show_settings_dialog,
+                instance_id: settings_instance_id
+            }
+
             // Version selector
             Selector {
                 show: show_version_selector
@@ -528,6 +611,8 @@ pub async fn install_and_launch_instance(
     _username: String,
     instance_id: u32,
     mut active_instance_id: Signal<Option<u32>>,
+    server_address: Option<String>,
+    world: Option<String>,
 ) {
     set_game_progress_state_simple(
         true,
@@ -594,6 +679,16 @@ pub async fn install_and_launch_instance(
         return;
     }
 
+    // Crash reports older than this launch belong to earlier runs
+    let launched_at = VisitTracker::current_timestamp();
+    if CRASH_REPORT
+        .read()
+        .as_ref()
+        .is_some_and(|report| report.instance_id == instance_id)
+    {
+        *CRASH_REPORT.write() = None;
+    }
+
     // Create a channel for log messages
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
     let version_clone = version.clone();
@@ -601,9 +696,12 @@ pub async fn install_and_launch_instance(
     // Handle log messages in a separate task
     spawn(async move {
         let mut game_started = false;
+        let mut game_launched = false;
+        // Last lines of game output, scanned for the cause of a crash
+        let mut log_tail = std::collections::VecDeque::with_capacity(CRASH_LOG_TAIL);
 
         while let Some(log_message) = rx.recv().await {
-            use crate::backend::python::python::MinecraftLogMessage;
+            use crate::backend::python::python::{GameEvent, MinecraftLogMessage};
 
             match log_message {
                 MinecraftLogMessage::LaunchResult {
@@ -612,6 +710,12 @@ pub async fn install_and_launch_instance(
                     message,
                 } => {
                     if success {
+                        InstanceManager::mark_played(instance_id);
+                        // Only the launcher script reports the game PID, which the Stop action needs
+                        if let Some(pid) = pid {
+                            set_game_pid(instance_id, pid);
+                            game_launched = true;
+                        }
                         crate::frontend::services::states::add_debug_log(
                             "INFO".to_string(),
                             if let Some(pid_val) = pid {
@@ -645,37 +749,53 @@ pub async fn install_and_launch_instance(
                 }
                 MinecraftLogMessage::Log { line, pid: _ } => {
                     // Add to debug console
-                    crate::frontend::services::states::add_debug_log(
-                        "INFO".to_string(),
-                        line.clone(),
+                    crate::frontend::services::states::add_game_log(&line, Some(instance_id));
+                    if log_tail.len() == CRASH_LOG_TAIL {
+                        log_tail.pop_front();
+                    }
+                    log_tail.push_back(line.clone());
+
+                    // Remember servers the player joins
+                    if let Some(address) = parse_server_connection(&line) {
+                        InstanceManager::record_server(instance_id, &address);
+                    }
+                }
+                MinecraftLogMessage::Event(GameEvent::Downloading) => {
+                    set_game_progress_state(
+                        true,
+                        40.0,
+                        "Downloading game files...".to_string(),
+                        ProgressStatus::InProgress,
+                        Some(instance_id),
+                    );
+                }
+                MinecraftLogMessage::Event(GameEvent::Launching) => {
+                    set_game_progress_state(
+                        true,
+                        80.0,
+                        "Launching Minecraft...".to_string(),
+                        ProgressStatus::InProgress,
+                        Some(instance_id),
+                    );
+                }
+                MinecraftLogMessage::Event(GameEvent::Started) => {
+                    game_started = true;
+                    set_game_progress_state(
+                        true,
+                        100.0,
+                        "Minecraft has started successfully".to_string(),
+                        ProgressStatus::Success,
                         Some(instance_id),
                     );
 
-                    // Check for successful game start indicators
-                    if !game_started
-                        && (line.contains("[main/INFO]: Setting user:")
-                            || line.contains("[main/INFO]: Environment:")
-                            || line.contains("[Render thread/INFO]: OpenGL")
-                            || line.contains("[Render thread/INFO]: Created:")
-                            || (line.contains("[main/INFO]: Loaded") && line.contains("recipes"))
-                            || line.contains("[Render thread/INFO]: Stopping worker threads"))
-                    {
-                        game_started = true;
-                        set_game_progress_state(
-                            true,
-                            100.0,
-                            "Minecraft has started successfully".to_string(),
-                            ProgressStatus::Success,
-                            Some(instance_id),
-                        );
-
-                        // Hide success status after 3 seconds
-                        spawn(async move {
-                            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
-                            set_game_progress_state_simple(false, 0.0, String::new(), None);
-                        });
-                    }
+                    // Hide success status after 3 seconds
+                    spawn(async move {
+                        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+                        set_game_progress_state_simple(false, 0.0, String::new(), None);
+                    });
                 }
+                // The exit message that follows carries the exit code and message
+                MinecraftLogMessage::Event(GameEvent::Stopped | GameEvent::Crashed { .. }) => {}
                 MinecraftLogMessage::Exit {
                     pid,
                     exit_code,
@@ -687,12 +807,49 @@ pub async fn install_and_launch_instance(
                         Some(instance_id),
                     );
 
-                    if !game_started {
-                        // Game failed to start properly
+                    // The Stop action forgets the PID before ending the game
+                    if game_launched && take_game_pid(instance_id).is_none() {
+                        set_instance_running(instance_id, false);
+                        active_instance_id.set(None);
+                        break;
+                    }
+
+                    if exit_code != 0 {
+                        spawn(async move {
+                            match latest_crash_report(instance_id, launched_at).await {
+                                Ok(Some(path)) => {
+                                    crate::frontend::services::states::add_debug_log(
+                                        "ERROR".to_string(),
+                                        format!("Crash report: {}", path.display()),
+                                        Some(instance_id),
+                                    );
+                                    *CRASH_REPORT.write() = Some(CrashReport { instance_id, path });
+                                }
+                                Ok(None) => {}
+                                Err(e) => error!("Failed to look for a crash report: {e}"),
+                            }
+                        });
+                    }
+
+                    let hint = if exit_code == 0 {
+                        None
+                    } else {
+                        diagnose_crash(log_tail.iter().map(String::as_str))
+                    };
+                    if let Some(hint) = &hint {
+                        crate::frontend::services::states::add_debug_log(
+                            "ERROR".to_string(),
+                            format!("Likely crash cause: {hint}"),
+                            Some(instance_id),
+                        );
+                    }
+
+                    if !game_started || hint.is_some() {
+                        // Game failed to start properly or crashed for a known reason
                         set_game_progress_state(
                             true,
                             100.0,
-                            "Minecraft failed to start".to_string(),
+                            hint.unwrap_or_else(|| "Minecraft failed to start".to_string()),
                             ProgressStatus::Failed,
                             Some(instance_id),
                         );
@@ -718,6 +875,7 @@ pub async fn install_and_launch_instance(
                         format!("Minecraft error: {message}"),
                         Some(instance_id),
                     );
+                    take_game_pid(instance_id);
                     set_game_progress_state(
                         true,
                         100.0,
@@ -742,7 +900,7 @@ pub async fn install_and_launch_instance(
 
     // Launch the instance through the communicator
     match communicator
-        .install_and_launch_instance(instance_id, &version, tx)
+        .install_and_launch_instance(instance_id, &version, server_address, world, tx)
         .await
     {
         Ok(launch_result) => {
@@ -768,12 +926,27 @@ pub async fn install_and_launch_instance(
                 set_game_progress_state_simple(false, 0.0, String::new(), None);
             }
         }
+        Err(e) if e.downcast_ref::<InstallCancelled>().is_some() => {
+            info!("{e}");
+            set_instance_running(instance_id, false);
+            set_game_progress_state_simple(false, 0.0, String::new(), None);
+        }
         Err(e) => {
-            error!("Failed to launch instance: {e}");
+            error!("Failed to launch instance: {e:#}");
+            let status = match e.downcast_ref::<JavaVersionMismatch>() {
+                Some(mismatch) => {
+                    spawn(offer_required_java(mismatch.clone()));
+                    mismatch.to_string()
+                }
+                None => match error_kind(&e) {
+                    Some(kind) => format!("Failed to start {version}: {}", kind.advice()),
+                    None => format!("Failed to start {version}"),
+                },
+            };
             set_game_progress_state(
                 true,
                 100.0,
-                format!("Failed to start {version}"),
+                status,
                 ProgressStatus::Failed,
                 Some(instance_id),
             );
@@ -783,3 +956,40 @@ pub async fn install_and_launch_instance(
         }
     }
 }
+
+/// Offer to switch from a custom Java that is too old to the Java the game version asks for.
+///
+/// Without a custom executable the launcher downloads the matching runtime on the next launch.
+async fn offer_required_java(mismatch: JavaVersionMismatch) {
+    const DOWNLOAD: &str = "Download Java";
+
+    let result = rfd::AsyncMessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title("Java version")
+        .set_description(format!(
+            "{mismatch}. Download Java {} and use it instead of the custom Java?",
+            mismatch.required
+        ))
+        .set_buttons(rfd::MessageButtons::OkCancelCustom(
+            DOWNLOAD.to_string(),
+            "Cancel".to_string(),
+        ))
+        .show()
+        .await;
+    let accepted = match result {
+        rfd::MessageDialogResult::Ok => true,
+        rfd::MessageDialogResult::Custom(label) => label == DOWNLOAD,
+        _ => false,
+    };
+    if !accepted {
+        return;
+    }
+
+    let settings = LauncherSettings {
+        java_path: None,
+        ..SETTINGS.read().clone()
+    };
+    if let Err(e) = save_settings(settings).await {
+        error!("Failed to reset custom Java: {e}");
+    }
+}
@@ -14,7 +14,7 @@ pub fn Navigation(animations_played: bool) -> Element {
     // Only update the active tab for non-chat routes
     let current_tab = match route {
         Route::Auth {} => "Auth",
-        Route::Home { .. } => "Main",
+        Route::Home { .. } | Route::Screenshots { .. } | Route::Mods { .. } => "Main",
         Route::Packs { .. } => "Packs",
         Route::Settings { .. } => "Settings",
         Route::Cloud { .. } => "Cloud",
@@ -32,7 +32,7 @@ pub fn Navigation(animations_played: bool) -> Element {
     let home = ResourceLoader::get_asset("home");
     // let packs = ResourceLoader::get_asset("packs");
     let settings = ResourceLoader::get_asset("settings");
-    // let cloud = ResourceLoader::get_asset("cloud");
+    let cloud = ResourceLoader::get_asset("cloud");
     let add = ResourceLoader::get_asset("add");
 
     rsx! {
@@ -59,17 +59,14 @@ pub fn Navigation(animations_played: bool) -> Element {
                     img { class: "nav-icon", src: "{settings}", alt: "Settings" }
                     span { class: "nav-text", "Settings" }
                 }
-                // Temporarily hidden
-                /*
                 li {
-                    class: if active_tab == "Cloud" { "nav-item active nav-item-4" } else { "nav-item nav-item-4" },
+                    class: if active_tab == "Cloud" { "nav-item active nav-item-3" } else { "nav-item nav-item-3" },
                     onclick: move |_| { nav.push("/cloud"); },
                     img { class: "nav-icon", src: "{cloud}", alt: "Cloud" }
                     span { class: "nav-text", "Cloud" }
                 }
-                */
                 li {
-                    class: if active_tab == "New" { "nav-item active nav-item-3" } else { "nav-item nav-item-3" },
+                    class: if active_tab == "New" { "nav-item active nav-item-4" } else { "nav-item nav-item-4" },
                     onclick: move |_| { nav.push("/new"); },
                     img { class: "nav-icon", src: "{add}", alt: "New tab" }
                 }
@@ -1,8 +1,10 @@
 //! Authentication page component.
 
+use crate::backend::services::{MojangService, StatusService};
 use crate::backend::utils::css::ResourceLoader;
 use crate::frontend::components::layout::AuthLayout;
 use crate::frontend::services::context::AuthState;
+use crate::frontend::services::user::UserConfig;
 use dioxus::{events::KeyboardEvent, prelude::*};
 use dioxus_router::use_navigator;
 use std::time::Duration;
@@ -17,6 +19,8 @@ pub fn Auth() -> Element {
     let hide_ui = use_signal(|| false);
     let mut input_ref = use_signal(|| None as Option<std::rc::Rc<MountedData>>);
     let mut show_error = use_signal(|| false);
+    let error_message = use_signal(String::new);
+    let mut unavailable_services = use_signal(Vec::<MojangService>::new);
 
     let logo = ResourceLoader::get_asset("logo");
     let microsoft = ResourceLoader::get_asset("microsoft");
@@ -28,21 +32,33 @@ pub fn Auth() -> Element {
 
         move |e: KeyboardEvent| {
             if e.key() == Key::Enter {
-                let username_value = username.read().trim().to_string();
+                let username_value = username.read().clone();
                 let mut auth = auth;
                 let nav = nav;
                 let mut show_error = show_error;
+                let mut error_message = error_message;
                 let mut hide_ui = hide_ui;
 
+                // Reject invalid names right away instead of after the fade-out
+                if let Err(e) = UserConfig::validate_username(&username_value) {
+                    error_message.set(e.to_string());
+                    show_error.set(true);
+                    return;
+                }
+
                 show_error.set(false);
                 hide_ui.set(true);
                 spawn(async move {
                     sleep(Duration::from_millis(700)).await;
-                    if let Ok(()) = auth.login(username_value).await {
-                        nav.push("/home");
-                    } else {
-                        show_error.set(true);
-                        hide_ui.set(false);
+                    match auth.login(username_value).await {
+                        Ok(()) => {
+                            nav.push("/home");
+                        }
+                        Err(e) => {
+                            error_message.set(e.to_string());
+                            show_error.set(true);
+                            hide_ui.set(false);
+                        }
                     }
                 });
             }
@@ -61,6 +77,13 @@ pub fn Auth() -> Element {
         }
     });
 
+    // Warn about Mojang outages so login and download failures aren't blamed on the launcher
+    use_effect(move || {
+        spawn(async move {
+            unavailable_services.set(StatusService::unavailable().await);
+        });
+    });
+
     // Check if a user is already authenticated and redirect
     use_effect(move || {
         if *auth.is_authenticated.read() {
@@ -148,7 +171,19 @@ pub fn Auth() -> Element {
                         }
                         div {
                             class: if show_error() { "error-message error-visible" } else { "error-message error-hidden" },
-                            "Username must be 3-16 characters long and can only contain letters, numbers, and underscores"
+                            "{error_message}"
+                        }
+                        if !unavailable_services().is_empty() {
+                            div { class: "service-status",
+                                span { class: "service-status-dot" }
+                                {
+                                    let names: Vec<&str> = unavailable_services()
+                                        .iter()
+                                        .map(|service| service.display_name())
+                                        .collect();
+                                    format!("Mojang services degraded: {}", names.join(", "))
+                                }
+                            }
                         }
                     }
                 }
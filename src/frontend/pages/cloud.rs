@@ -0,0 +1,329 @@
+//! Cloud sync page.
+
+use crate::backend::services::{
+    CloudConfig, CloudSyncInfo, LauncherSettings, SyncStatus, VisitTracker,
+};
+use crate::backend::utils::css::ResourceLoader;
+use crate::frontend::services::cloud;
+use crate::frontend::services::instances::InstanceManager;
+use crate::frontend::services::settings::{SETTINGS, save_settings};
+use crate::frontend::services::states::is_instance_running;
+use dioxus::prelude::*;
+use log::error;
+use std::collections::HashMap;
+
+/// What a sync action applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SyncTarget {
+    /// The instance list and the game settings of every instance.
+    Configs,
+    Saves(u32),
+}
+
+/// Direction of a sync action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncAction {
+    Upload,
+    Download,
+}
+
+impl SyncAction {
+    /// Direction that syncs both sides without losing changes, if one is needed.
+    const fn for_status(status: SyncStatus) -> Option<Self> {
+        match status {
+            SyncStatus::NotSynced | SyncStatus::LocalChanges => Some(Self::Upload),
+            SyncStatus::RemoteChanges => Some(Self::Download),
+            SyncStatus::UpToDate | SyncStatus::Conflict => None,
+        }
+    }
+
+    /// Whether the action overwrites changes the other side does not have.
+    const fn loses_changes(self, status: SyncStatus) -> bool {
+        matches!(
+            (self, status),
+            (_, SyncStatus::Conflict)
+                | (Self::Upload, SyncStatus::RemoteChanges)
+                | (Self::Download, SyncStatus::LocalChanges)
+        )
+    }
+}
+
+#[component]
+pub fn Cloud() -> Element {
+    let mut url = use_signal(|| SETTINGS.read().cloud.url.clone());
+    let mut username = use_signal(|| SETTINGS.read().cloud.username.clone());
+    let mut password = use_signal(String::new);
+    let mut statuses = use_signal(HashMap::<SyncTarget, Result<CloudSyncInfo, String>>::new);
+    let mut busy = use_signal(|| None::<SyncTarget>);
+    let mut pending_confirm = use_signal(|| None::<(SyncTarget, SyncAction)>);
+    let mut error_message = use_signal(|| None::<String>);
+
+    // Keep the fields in sync once settings finish loading
+    use_effect(move || {
+        let config = SETTINGS.read().cloud.clone();
+        url.set(config.url);
+        username.set(config.username);
+    });
+
+    // The password is stored with the user, not in the settings
+    use_effect(move || {
+        spawn(async move {
+            password.set(cloud::load_password().await);
+        });
+    });
+
+    // Check everything again whenever the cloud settings change
+    use_effect(move || {
+        statuses.write().clear();
+        if !SETTINGS.read().cloud.is_configured() {
+            return;
+        }
+        let targets = InstanceManager::get_instances_by_recency()
+            .into_iter()
+            .map(|instance| SyncTarget::Saves(instance.id));
+        for target in std::iter::once(SyncTarget::Configs).chain(targets) {
+            spawn(async move {
+                let status = target_status(target).await;
+                statuses.write().insert(target, status);
+            });
+        }
+    });
+
+    let save_config = move |_| {
+        let settings = LauncherSettings {
+            cloud: CloudConfig {
+                url: url().trim().to_string(),
+                username: username().trim().to_string(),
+                password: String::new(),
+            },
+            ..SETTINGS.read().clone()
+        };
+        spawn(async move {
+            // Saved first, as saving the settings checks the sync status again
+            let result = match cloud::save_password(password()).await {
+                Ok(()) => save_settings(settings).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => error_message.set(None),
+                Err(e) => {
+                    error!("Failed to save cloud settings: {e}");
+                    error_message.set(Some(e.to_string()));
+                }
+            }
+        });
+    };
+
+    let mut run_action = move |target: SyncTarget, action: SyncAction| {
+        if busy().is_some() {
+            return;
+        }
+        let running = match target {
+            SyncTarget::Configs => InstanceManager::get_instances_sorted()
+                .iter()
+                .any(|instance| is_instance_running(instance.id)),
+            SyncTarget::Saves(instance_id) => is_instance_running(instance_id),
+        };
+        if running {
+            error_message.set(Some("Close the game before syncing".to_string()));
+            return;
+        }
+
+        // Actions that would overwrite newer changes need a second click
+        let status = statuses
+            .read()
+            .get(&target)
+            .and_then(|status| status.as_ref().ok())
+            .map(|info| info.status);
+        if status.is_some_and(|status| action.loses_changes(status))
+            && pending_confirm() != Some((target, action))
+        {
+            pending_confirm.set(Some((target, action)));
+            return;
+        }
+        pending_confirm.set(None);
+
+        busy.set(Some(target));
+        spawn(async move {
+            let result = match (target, action) {
+                (SyncTarget::Configs, SyncAction::Upload) => cloud::push_configs().await,
+                (SyncTarget::Configs, SyncAction::Download) => cloud::pull_configs().await,
+                (SyncTarget::Saves(id), SyncAction::Upload) => cloud::upload_saves(id).await,
+                (SyncTarget::Saves(id), SyncAction::Download) => cloud::download_saves(id).await,
+            };
+            match result {
+                Ok(()) => error_message.set(None),
+                Err(e) => {
+                    error!("Cloud sync of {target:?} failed: {e}");
+                    error_message.set(Some(e.to_string()));
+                }
+            }
+            let status = target_status(target).await;
+            statuses.write().insert(target, status);
+            busy.set(None);
+        });
+    };
+
+    // Syncs in whichever direction is safe, or only checks again when nothing changed
+    let sync_configs = move |_| {
+        let status = statuses
+            .read()
+            .get(&SyncTarget::Configs)
+            .and_then(|status| status.as_ref().ok())
+            .map(|info| info.status);
+        match status.and_then(SyncAction::for_status) {
+            Some(action) => run_action(SyncTarget::Configs, action),
+            None if busy().is_none() => {
+                statuses.write().remove(&SyncTarget::Configs);
+                spawn(async move {
+                    let status = target_status(SyncTarget::Configs).await;
+                    statuses.write().insert(SyncTarget::Configs, status);
+                });
+            }
+            None => {}
+        }
+    };
+
+    let configs_status = statuses.read().get(&SyncTarget::Configs).cloned();
+    let configs_conflict =
+        matches!(&configs_status, Some(Ok(info)) if info.status == SyncStatus::Conflict);
+
+    let is_configured = SETTINGS.read().cloud.is_configured();
+
+    rsx! {
+        div { class: "cloud-page",
+            div { class: "cloud-title", "Cloud" }
+            div { class: "cloud-divider" }
+
+            div { class: "cloud-config",
+                input {
+                    class: "cloud-input cloud-input-url",
+                    r#type: "text",
+                    placeholder: "WebDAV folder URL",
+                    value: "{url}",
+                    oninput: move |e| url.set(e.value()),
+                }
+                input {
+                    class: "cloud-input",
+                    r#type: "text",
+                    placeholder: "Username",
+                    value: "{username}",
+                    oninput: move |e| username.set(e.value()),
+                }
+                input {
+                    class: "cloud-input",
+                    r#type: "password",
+                    placeholder: "Password",
+                    value: "{password}",
+                    oninput: move |e| password.set(e.value()),
+                }
+                div { class: "cloud-button", onclick: save_config, "Save" }
+            }
+
+            if let Some(message) = error_message() {
+                div { class: "cloud-error", "{message}" }
+            }
+
+            div { class: "cloud-list",
+                if !is_configured {
+                    div { class: "cloud-empty", "Set up a WebDAV server to sync instances and saves." }
+                }
+
+                if is_configured {
+                    div { class: "cloud-entry",
+                        div { class: "cloud-entry-icon cloud-entry-configs",
+                            img { src: ResourceLoader::get_asset("settings") }
+                        }
+                        div { class: "cloud-entry-text",
+                            div { class: "cloud-entry-name", "Instances and game settings" }
+                            div {
+                                class: status_class(configs_status.as_ref()),
+                                "{status_text(configs_status.as_ref())}"
+                            }
+                        }
+                        if busy() == Some(SyncTarget::Configs) {
+                            div { class: "cloud-entry-status", "Syncing..." }
+                        } else if configs_conflict {
+                            for action in [SyncAction::Upload, SyncAction::Download] {
+                                div {
+                                    class: "cloud-button",
+                                    onclick: move |_| run_action(SyncTarget::Configs, action),
+                                    if pending_confirm() == Some((SyncTarget::Configs, action)) {
+                                        "Confirm"
+                                    } else if action == SyncAction::Upload {
+                                        "Upload"
+                                    } else {
+                                        "Download"
+                                    }
+                                }
+                            }
+                        } else {
+                            div { class: "cloud-button", onclick: sync_configs, "Sync" }
+                        }
+                    }
+
+                    for instance in InstanceManager::get_instances_by_recency() {
+                        div { key: "{instance.id}", class: "cloud-entry",
+                            div { class: "cloud-entry-icon", style: "background: #{instance.color}" }
+                            div { class: "cloud-entry-text",
+                                div { class: "cloud-entry-name", "{instance.name}" }
+                                div {
+                                    class: status_class(statuses.read().get(&SyncTarget::Saves(instance.id))),
+                                    "{status_text(statuses.read().get(&SyncTarget::Saves(instance.id)))}"
+                                }
+                            }
+                            if busy() == Some(SyncTarget::Saves(instance.id)) {
+                                div { class: "cloud-entry-status", "Syncing..." }
+                            } else {
+                                for action in [SyncAction::Upload, SyncAction::Download] {
+                                    div {
+                                        class: "cloud-button",
+                                        onclick: move |_| run_action(SyncTarget::Saves(instance.id), action),
+                                        if pending_confirm() == Some((SyncTarget::Saves(instance.id), action)) {
+                                            "Confirm"
+                                        } else if action == SyncAction::Upload {
+                                            "Upload"
+                                        } else {
+                                            "Download"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn target_status(target: SyncTarget) -> Result<CloudSyncInfo, String> {
+    let status = match target {
+        SyncTarget::Configs => cloud::configs_status().await,
+        SyncTarget::Saves(instance_id) => cloud::sync_status(instance_id).await,
+    };
+    status.map_err(|e| e.to_string())
+}
+
+fn status_class(status: Option<&Result<CloudSyncInfo, String>>) -> &'static str {
+    match status {
+        Some(Ok(info)) if info.status != SyncStatus::Conflict => "cloud-entry-status",
+        Some(_) => "cloud-entry-status cloud-entry-warning",
+        None => "cloud-entry-status",
+    }
+}
+
+fn status_text(status: Option<&Result<CloudSyncInfo, String>>) -> String {
+    match status {
+        None => "Checking...".to_string(),
+        Some(Err(e)) => e.clone(),
+        Some(Ok(info)) => match info.last_synced {
+            Some(time) => format!(
+                "{} · Synced {}",
+                info.status.display_name(),
+                VisitTracker::format_elapsed(time)
+            ),
+            None => info.status.display_name().to_string(),
+        },
+    }
+}
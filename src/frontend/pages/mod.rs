@@ -1,6 +1,10 @@
 //! Application pages.
 
 pub mod auth;
+pub mod cloud;
 pub mod main;
+pub mod mods;
 pub mod new;
+pub mod packs;
+pub mod screenshots;
 pub mod settings;
@@ -0,0 +1,83 @@
+//! Installed mods page.
+
+use crate::backend::services::ModInfo;
+use crate::frontend::services::instances::INSTANCES;
+use crate::frontend::services::mods::ModManager;
+use dioxus::prelude::*;
+use log::error;
+
+#[component]
+pub fn Mods(instance_id: u32) -> Element {
+    let mut mods = use_signal(Vec::<ModInfo>::new);
+    let mut loaded = use_signal(|| false);
+
+    let instance_name = INSTANCES
+        .read()
+        .get(&instance_id)
+        .map(|instance| instance.name.clone())
+        .unwrap_or_default();
+
+    use_effect(move || {
+        spawn(async move {
+            match ModManager::list(instance_id).await {
+                Ok(list) => mods.set(list),
+                Err(e) => error!("Failed to list mods: {e}"),
+            }
+            loaded.set(true);
+        });
+    });
+
+    let toggle_mod = move |index: usize| {
+        let Some(info) = mods.read().get(index).cloned() else {
+            return;
+        };
+        spawn(async move {
+            let enabled = !info.enabled;
+            match ModManager::set_enabled(instance_id, &info, enabled).await {
+                Ok(file_name) => {
+                    if let Some(entry) = mods.write().iter_mut().find(|m| **m == info) {
+                        entry.file_name = file_name;
+                        entry.enabled = enabled;
+                    }
+                }
+                Err(e) => error!("Failed to toggle mod {}: {e}", info.file_name),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "mods-page",
+            div { class: "mods-title", "Mods" }
+            div { class: "mods-divider" }
+            div { class: "mods-instance", "{instance_name}" }
+
+            div { class: "mods-list",
+                if loaded() && mods.read().is_empty() {
+                    div { class: "mods-empty", "No mods installed. Put mod jars into the mods folder of this instance." }
+                }
+
+                for (index, info) in mods.read().iter().enumerate() {
+                    div {
+                        key: "{info.file_name}",
+                        class: if info.enabled { "mod-entry" } else { "mod-entry mod-disabled" },
+                        div { class: "mod-info",
+                            div { class: "mod-name", "{info.name}" }
+                            div { class: "mod-details",
+                                if let Some(version) = &info.version {
+                                    "{version} · {info.file_name}"
+                                } else {
+                                    "{info.file_name}"
+                                }
+                            }
+                        }
+                        div {
+                            class: "mod-toggle",
+                            onclick: move |_| toggle_mod(index),
+                            if info.enabled { "Disable" } else { "Enable" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
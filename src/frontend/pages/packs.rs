@@ -0,0 +1,272 @@
+//! Packs page.
+
+use crate::backend::services::{ModpackImport, ModpackSearchResult};
+use crate::backend::utils::css::ResourceLoader;
+use crate::backend::utils::download::{self, DownloadEvent};
+use crate::frontend::services::instances::{
+    InstanceManager, import_existing_installation, import_instance,
+};
+use crate::frontend::services::modpacks::{import_modpack, install_modpack, search_modpacks};
+use dioxus::prelude::*;
+use log::error;
+
+#[component]
+pub fn Packs() -> Element {
+    let mut importing = use_signal(|| false);
+    let mut last_import = use_signal(|| None::<ModpackImport>);
+    let mut import_error = use_signal(|| None::<String>);
+    let mut query = use_signal(String::new);
+    let mut results = use_signal(Vec::<ModpackSearchResult>::new);
+    let mut searching = use_signal(|| false);
+    let mut installing = use_signal(|| None::<String>);
+    // Files downloaded by the running install or import
+    let downloaded = use_signal(|| 0_usize);
+
+    let mut search = move || {
+        searching.set(true);
+        spawn(async move {
+            match search_modpacks(query().trim()).await {
+                Ok(hits) => results.set(hits),
+                Err(e) => {
+                    error!("Failed to search modpacks: {e}");
+                    import_error.set(Some(format!("Failed to search modpacks: {e}")));
+                }
+            }
+            searching.set(false);
+        });
+    };
+
+    // Show popular modpacks before anything is searched
+    use_effect(move || search());
+
+    let mut on_install = move |project_id: String| {
+        if installing().is_some() || importing() || !InstanceManager::can_create_instance() {
+            return;
+        }
+
+        installing.set(Some(project_id.clone()));
+        import_error.set(None);
+        spawn(async move {
+            let counter = count_downloads(downloaded);
+            let result = install_modpack(&project_id).await;
+            counter.cancel();
+            match result {
+                Ok(import) => last_import.set(Some(import)),
+                Err(e) => {
+                    error!("Failed to install modpack {project_id}: {e}");
+                    last_import.set(None);
+                    import_error.set(Some(e.to_string()));
+                }
+            }
+            installing.set(None);
+        });
+    };
+
+    let on_import = move |_| {
+        if importing() || installing().is_some() || !InstanceManager::can_create_instance() {
+            return;
+        }
+
+        spawn(async move {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("Modpack or instance archive", &["mrpack", "zip"])
+                .pick_file()
+                .await
+            else {
+                return;
+            };
+
+            importing.set(true);
+            import_error.set(None);
+            let counter = count_downloads(downloaded);
+            // Zip archives are instances exported by the launcher
+            let is_instance_archive = file
+                .path()
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+            let result = if is_instance_archive {
+                import_instance(file.path())
+                    .await
+                    .map(|instance| ModpackImport {
+                        instance_id: instance.id,
+                        name: instance.name,
+                        skipped: Vec::new(),
+                    })
+            } else {
+                import_modpack(file.path()).await
+            };
+            counter.cancel();
+
+            match result {
+                Ok(import) => last_import.set(Some(import)),
+                Err(e) => {
+                    error!("Failed to import modpack: {e}");
+                    last_import.set(None);
+                    import_error.set(Some(e.to_string()));
+                }
+            }
+            importing.set(false);
+        });
+    };
+
+    let on_import_folder = move |_| {
+        if importing() || installing().is_some() || !InstanceManager::can_create_instance() {
+            return;
+        }
+
+        spawn(async move {
+            let Some(folder) = rfd::AsyncFileDialog::new()
+                .set_title("Select a .minecraft folder")
+                .pick_folder()
+                .await
+            else {
+                return;
+            };
+
+            importing.set(true);
+            import_error.set(None);
+            match import_existing_installation(folder.path()).await {
+                Ok(instances) if instances.is_empty() => {
+                    last_import.set(None);
+                    import_error.set(Some("No supported versions found".to_string()));
+                }
+                Ok(instances) => last_import.set(Some(ModpackImport {
+                    instance_id: instances[0].id,
+                    name: format!("{} versions", instances.len()),
+                    skipped: Vec::new(),
+                })),
+                Err(e) => {
+                    error!("Failed to import Minecraft installation: {e}");
+                    last_import.set(None);
+                    import_error.set(Some(e.to_string()));
+                }
+            }
+            importing.set(false);
+        });
+    };
+
+    rsx! {
+        div { class: "packs-page",
+            div { class: "packs-title", "Packs" }
+            div { class: "packs-divider" }
+
+            div { class: "packs-panel" }
+            div { class: "packs-server-icon" }
+            div { class: "packs-server-name", "Import instance" }
+            div { class: "packs-server-last-played",
+                if importing() {
+                    {progress_text("Importing", downloaded())}
+                } else if InstanceManager::can_create_instance() {
+                    "Add a modpack, archive or .minecraft folder"
+                } else {
+                    "Instance limit reached"
+                }
+            }
+            div {
+                class: "packs-import-button packs-import-folder",
+                onclick: on_import_folder,
+                img { src: ResourceLoader::get_asset("open"), class: "open-icon" }
+                div { class: "open-text", "Folder" }
+            }
+            div {
+                class: "packs-import-button",
+                onclick: on_import,
+                img { src: ResourceLoader::get_asset("open"), class: "open-icon" }
+                div { class: "open-text", "Import" }
+            }
+
+            div { class: "packs-result",
+                if let Some(message) = import_error() {
+                    div { class: "packs-error", "{message}" }
+                }
+                if let Some(import) = last_import() {
+                    div { "Imported {import.name}" }
+                    for file in import.skipped.iter() {
+                        div { class: "packs-skipped", "Skipped {file.path}: {file.reason}" }
+                    }
+                }
+            }
+
+            input {
+                class: "packs-search",
+                r#type: "text",
+                placeholder: "Search Modrinth modpacks",
+                value: "{query}",
+                oninput: move |e| query.set(e.value()),
+                onkeydown: move |e: KeyboardEvent| {
+                    if e.key() == Key::Enter && !searching() {
+                        search();
+                    }
+                },
+            }
+
+            div { class: "packs-list",
+                if searching() {
+                    div { class: "packs-list-status", "Searching..." }
+                } else if results().is_empty() {
+                    div { class: "packs-list-status", "No modpacks found" }
+                }
+                for pack in results() {
+                    div { key: "{pack.project_id}", class: "packs-entry",
+                        if let Some(icon_url) = pack.icon_url.clone() {
+                            img { class: "packs-entry-icon", src: "{icon_url}" }
+                        } else {
+                            div { class: "packs-entry-icon" }
+                        }
+                        div { class: "packs-entry-info",
+                            div { class: "packs-entry-name", "{pack.title}" }
+                            div { class: "packs-entry-description", "{pack.description}" }
+                        }
+                        div { class: "packs-entry-downloads", "{format_downloads(pack.downloads)}" }
+                        div {
+                            class: "packs-entry-install",
+                            onclick: {
+                                let project_id = pack.project_id.clone();
+                                move |_| on_install(project_id.clone())
+                            },
+                            if installing().as_deref() == Some(pack.project_id.as_str()) {
+                                {progress_text("Installing", downloaded())}
+                            } else {
+                                "Install"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Format a download count as e.g. "1.2M downloads".
+fn format_downloads(downloads: u64) -> String {
+    let count = match downloads {
+        0..1_000 => downloads.to_string(),
+        1_000..1_000_000 => format!("{:.1}K", downloads as f64 / 1_000.0),
+        _ => format!("{:.1}M", downloads as f64 / 1_000_000.0),
+    };
+    format!("{count} downloads")
+}
+
+/// Status of a running install or import, with the number of files downloaded so far.
+fn progress_text(action: &str, downloaded: usize) -> String {
+    match downloaded {
+        0 => format!("{action}..."),
+        1 => format!("{action}... (1 file)"),
+        _ => format!("{action}... ({downloaded} files)"),
+    }
+}
+
+/// Count finished downloads into `downloaded` until the returned task is cancelled.
+fn count_downloads(mut downloaded: Signal<usize>) -> Task {
+    downloaded.set(0);
+    let mut events = download::subscribe();
+    spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(DownloadEvent::Completed { .. }) => downloaded += 1,
+                Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
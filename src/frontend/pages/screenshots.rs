@@ -0,0 +1,102 @@
+//! Screenshot gallery page.
+
+use crate::backend::services::{Screenshot, VisitTracker};
+use crate::frontend::services::instances::INSTANCES;
+use crate::frontend::services::screenshots::ScreenshotManager;
+use dioxus::prelude::*;
+use log::error;
+
+#[component]
+pub fn Screenshots(instance_id: u32) -> Element {
+    let mut screenshots = use_signal(Vec::<(Screenshot, String)>::new);
+    let mut loaded = use_signal(|| false);
+    let mut pending_delete = use_signal(|| None::<usize>);
+
+    let instance_name = INSTANCES
+        .read()
+        .get(&instance_id)
+        .map(|instance| instance.name.clone())
+        .unwrap_or_default();
+
+    use_effect(move || {
+        spawn(async move {
+            let list = match ScreenshotManager::list(instance_id).await {
+                Ok(list) => list,
+                Err(e) => {
+                    error!("Failed to list screenshots: {e}");
+                    Vec::new()
+                }
+            };
+
+            let mut thumbnails = Vec::with_capacity(list.len());
+            for screenshot in list {
+                match ScreenshotManager::load_data_url(&screenshot.path).await {
+                    Ok(data_url) => thumbnails.push((screenshot, data_url)),
+                    Err(e) => error!("Failed to load screenshot {:?}: {e}", screenshot.path),
+                }
+            }
+            screenshots.set(thumbnails);
+            loaded.set(true);
+        });
+    });
+
+    let mut delete_screenshot = move |index: usize| {
+        // The first click asks for confirmation, the second deletes
+        if pending_delete() != Some(index) {
+            pending_delete.set(Some(index));
+            return;
+        }
+        pending_delete.set(None);
+
+        let Some((screenshot, _)) = screenshots.read().get(index).cloned() else {
+            return;
+        };
+        spawn(async move {
+            match ScreenshotManager::delete(instance_id, &screenshot).await {
+                Ok(()) => screenshots.write().retain(|(s, _)| *s != screenshot),
+                Err(e) => error!("Failed to delete screenshot: {e}"),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "screenshots-page",
+            div { class: "screenshots-title", "Screenshots" }
+            div { class: "screenshots-divider" }
+            div { class: "screenshots-instance", "{instance_name}" }
+
+            div { class: "screenshots-grid",
+                if loaded() && screenshots.read().is_empty() {
+                    div { class: "screenshots-empty", "No screenshots yet. Press F2 in game to take one." }
+                }
+
+                for (index, (screenshot, data_url)) in screenshots.read().iter().enumerate() {
+                    div {
+                        key: "{screenshot.path.display()}",
+                        class: "screenshot-card",
+                        img {
+                            class: "screenshot-thumbnail",
+                            src: "{data_url}",
+                            onclick: {
+                                let path = screenshot.path.clone();
+                                move |_| {
+                                    if let Err(e) = ScreenshotManager::open(&path) {
+                                        error!("Failed to open screenshot: {e:#}");
+                                    }
+                                }
+                            }
+                        }
+                        div { class: "screenshot-time",
+                            "{VisitTracker::format_elapsed(screenshot.modified)}"
+                        }
+                        div {
+                            class: "screenshot-delete",
+                            onclick: move |_| delete_screenshot(index),
+                            if pending_delete() == Some(index) { "Confirm" } else { "Delete" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Player head avatars.
+
+use crate::backend::utils::css::ResourceLoader;
+use crate::backend::utils::http;
+use crate::backend::utils::paths::get_cache_dir;
+use anyhow::Result;
+use base64::{Engine as _, engine::general_purpose};
+use log::warn;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// How long a downloaded head is used before it is fetched again.
+const AVATAR_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Size of the downloaded heads in pixels.
+const AVATAR_SIZE: u32 = 49;
+
+/// Heads already loaded this session, as data URLs keyed by username.
+static AVATARS: LazyLock<Mutex<HashMap<String, String>>> = LazyLock::new(Default::default);
+
+#[derive(Clone, Copy)]
+pub struct AvatarManager;
+
+impl AvatarManager {
+    /// Get the head of a player as a data URL.
+    ///
+    /// Heads are cached on disk for a day. When a head can't be downloaded, or
+    /// offline mode is on, the cached one is used even if it is older, and
+    /// Steve's head if there is none.
+    pub async fn get(username: &str) -> String {
+        if !is_valid_username(username) {
+            return Self::default_avatar();
+        }
+        if let Some(avatar) = AVATARS
+            .lock()
+            .ok()
+            .and_then(|avatars| avatars.get(username).cloned())
+        {
+            return avatar;
+        }
+
+        let bytes = match Self::load(username).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("Failed to load avatar of {username}: {e}");
+                None
+            }
+        };
+        let Some(bytes) = bytes else {
+            return Self::default_avatar();
+        };
+
+        let avatar = format!(
+            "data:image/png;base64,{}",
+            general_purpose::STANDARD.encode(bytes)
+        );
+        if let Ok(mut avatars) = AVATARS.lock() {
+            avatars.insert(username.to_string(), avatar.clone());
+        }
+        avatar
+    }
+
+    /// Steve's head, shown for players without an avatar.
+    #[must_use]
+    pub fn default_avatar() -> String {
+        ResourceLoader::get_asset("steve")
+    }
+
+    /// Read a head from the cache, downloading it when missing or stale.
+    async fn load(username: &str) -> Result<Option<Vec<u8>>> {
+        let path = Self::cache_path(username)?;
+        let cached = tokio::fs::read(&path).await.ok();
+        let fresh = tokio::fs::metadata(&path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age < AVATAR_TTL);
+        if (fresh && cached.is_some()) || http::is_offline() {
+            return Ok(cached);
+        }
+
+        match Self::download(username).await {
+            Ok(bytes) => {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&path, &bytes).await?;
+                Ok(Some(bytes))
+            }
+            Err(e) if cached.is_some() => {
+                warn!("Failed to refresh avatar of {username}, using the cached one: {e}");
+                Ok(cached)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn download(username: &str) -> Result<Vec<u8>> {
+        let bytes = http::client()?
+            .get(format!(
+                "https://minotar.net/helm/{username}/{AVATAR_SIZE}.png"
+            ))
+            .header("User-Agent", "DreamLauncher")
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        Ok(bytes.to_vec())
+    }
+
+    fn cache_path(username: &str) -> Result<PathBuf> {
+        Ok(get_cache_dir()?
+            .join("avatars")
+            .join(format!("{}.png", username.to_lowercase())))
+    }
+}
+
+/// Check that a name can be a Minecraft username, which also keeps it safe to use in a path.
+fn is_valid_username(username: &str) -> bool {
+    !username.is_empty()
+        && username.len() <= 16
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,69 @@
+//! Cloud sync service.
+
+use crate::backend::communicator::communicator::Communicator;
+use crate::backend::services::CloudSyncInfo;
+use crate::frontend::services::instances::{INSTANCES, NEXT_ID};
+use anyhow::Result;
+use dioxus::prelude::*;
+use log::info;
+
+/// Compare the saves of an instance with the cloud copy.
+pub async fn sync_status(instance_id: u32) -> Result<CloudSyncInfo> {
+    communicator().await?.cloud_sync_status(instance_id).await
+}
+
+/// Upload the saves of an instance to the cloud.
+pub async fn upload_saves(instance_id: u32) -> Result<()> {
+    communicator().await?.upload_saves(instance_id).await?;
+    info!("Uploaded saves of instance {instance_id}");
+    Ok(())
+}
+
+/// Replace the saves of an instance with the cloud copy.
+pub async fn download_saves(instance_id: u32) -> Result<()> {
+    communicator().await?.download_saves(instance_id).await?;
+    info!("Downloaded saves of instance {instance_id}");
+    Ok(())
+}
+
+/// Compare the instance list and game settings with the cloud copy.
+pub async fn configs_status() -> Result<CloudSyncInfo> {
+    communicator().await?.cloud_configs_status().await
+}
+
+/// Upload the instance list and game settings to the cloud.
+pub async fn push_configs() -> Result<()> {
+    communicator().await?.push_configs().await?;
+    info!("Uploaded launcher configs");
+    Ok(())
+}
+
+/// Replace the instance list and game settings with the cloud copy and show the new instances.
+pub async fn pull_configs() -> Result<()> {
+    let communicator = communicator().await?;
+    communicator.pull_configs().await?;
+
+    let instances = communicator.get_instances().await?;
+    *NEXT_ID.write() = instances.iter().map(|i| i.id).max().unwrap_or(0) + 1;
+    *INSTANCES.write() = instances.into_iter().map(|i| (i.id, i)).collect();
+    info!("Downloaded launcher configs");
+    Ok(())
+}
+
+/// Password of the cloud server, stored with the user rather than in the launcher settings.
+pub async fn load_password() -> String {
+    match communicator().await {
+        Ok(communicator) => communicator.load_cloud_password().await,
+        Err(_) => String::new(),
+    }
+}
+
+/// Save the password of the cloud server with the logged in user.
+pub async fn save_password(password: String) -> Result<()> {
+    communicator().await?.save_cloud_password(&password).await
+}
+
+async fn communicator() -> Result<Communicator> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    Communicator::new(archon).await
+}
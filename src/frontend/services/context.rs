@@ -1,8 +1,35 @@
 //! Authentication context and state management.
 
-use crate::frontend::services::user::UserConfig;
+use crate::frontend::services::user::{UserConfig, UsernameError};
 use dioxus::prelude::*;
 use log::{error, info, warn};
+use std::fmt;
+
+/// Reason a login attempt failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    /// The username was rejected before anything was saved.
+    InvalidUsername(UsernameError),
+    /// The user config could not be saved.
+    Storage(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUsername(e) => write!(f, "{e}"),
+            Self::Storage(e) => write!(f, "Failed to save user config: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl From<UsernameError> for AuthError {
+    fn from(e: UsernameError) -> Self {
+        Self::InvalidUsername(e)
+    }
+}
 
 #[derive(Clone, Copy)]
 pub struct AuthState {
@@ -20,23 +47,21 @@ impl AuthState {
     }
 
     /// Logs in with a username and saves to config.
-    pub async fn login(&mut self, username: String) -> Result<(), String> {
+    pub async fn login(&mut self, username: String) -> Result<(), AuthError> {
         info!("Starting login process for username: '{username}'");
-        let trimmed_username = username.trim().to_string();
-        info!("Trimmed username: '{trimmed_username}'");
 
-        if !UserConfig::is_valid_username(&trimmed_username) {
-            warn!("Invalid username: '{trimmed_username}'");
-            return Err("Username must be 3-16 characters long and can only contain letters, numbers, and underscores".to_string());
+        if let Err(e) = UserConfig::validate_username(&username) {
+            warn!("Invalid username '{username}': {e}");
+            return Err(e.into());
         }
 
         info!("Username validation passed, creating user config");
-        let user_config = UserConfig::new(trimmed_username);
+        let user_config = UserConfig::new(username);
 
         info!("Attempting to save user config");
         if let Err(e) = user_config.save().await {
             error!("Failed to save user config: {e}");
-            return Err(format!("Failed to save user config: {e}"));
+            return Err(AuthError::Storage(e.to_string()));
         }
         info!("User config saved successfully");
 
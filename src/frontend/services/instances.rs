@@ -1,11 +1,21 @@
 //! Instance management service.
 
-use crate::backend::services::Instance;
+use crate::backend::services::{
+    Instance, JvmPreset, MAX_INSTANCES, ModLoader, SavedServer, VisitTracker,
+};
 
 use crate::backend::communicator::communicator::Communicator;
+use crate::backend::utils::process::stop_process;
+use crate::frontend::services::states::{
+    ProgressStatus, format_debug_logs, is_instance_running, set_game_pid, set_game_progress_state,
+    set_game_progress_state_simple, set_instance_running, take_game_pid,
+};
 use dioxus::prelude::*;
+use base64::{Engine as _, engine::general_purpose};
 use log::{error, info, warn};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
 
 pub static INSTANCES: GlobalSignal<HashMap<u32, Instance>> = Signal::global(HashMap::new);
 pub static NEXT_ID: GlobalSignal<u32> = Signal::global(|| 1);
@@ -43,7 +53,7 @@ impl InstanceManager {
                                 error!("Failed to load instances: {e}, creating default instance");
                                 // Create default instance if loading fails
                                 if let Ok(instance_id_opt) =
-                                    communicator.create_instance("1.21.8").await
+                                    communicator.create_instance("1.21.8", ModLoader::Vanilla).await
                                     && let Some(instance_id) = instance_id_opt
                                 {
                                     let mut instances = INSTANCES.write();
@@ -67,10 +77,13 @@ impl InstanceManager {
     }
 
     #[must_use]
-    pub fn create_instance_with_version(version: &str) -> Option<u32> {
-        info!("create_instance_with_version called with version: {version}");
+    pub fn create_instance_with_version(version: &str, loader: ModLoader) -> Option<u32> {
+        info!(
+            "create_instance_with_version called with version: {version}, loader: {}",
+            loader.id()
+        );
 
-        // Check if we can create more instances (max 14)
+        // Check if we can create more instances
         if !Self::can_create_instance() {
             warn!("Cannot create more instances, limit reached");
             return None;
@@ -84,11 +97,11 @@ impl InstanceManager {
             };
             match Communicator::new(archon).await {
                 Ok(communicator) => {
-                    match communicator.create_instance(&version).await {
+                    match communicator.create_instance(&version, loader).await {
                         Ok(instance_id_opt) => {
                             if let Some(instance_id) = instance_id_opt {
                                 let new_instance =
-                                    Instance::new_with_version(instance_id, version.clone());
+                                    Instance::new_with_loader(instance_id, version.clone(), loader);
                                 info!("Created instance {instance_id} with version: {version}");
 
                                 // Update local state
@@ -147,6 +160,110 @@ impl InstanceManager {
         removed
     }
 
+    /// Clone an instance in the background, adding the copy once the backend saved it.
+    pub fn clone_instance(id: u32) {
+        info!("clone_instance called for instance {id}");
+
+        // Check if we can create more instances
+        if !Self::can_create_instance() {
+            warn!("Cannot duplicate instance {id}, limit reached");
+            return;
+        }
+
+        let Some(source) = INSTANCES.read().get(&id).cloned() else {
+            warn!("Cannot duplicate instance {id}: not found");
+            return;
+        };
+        spawn(async move {
+            let Some(archon) = crate::get_archon() else {
+                error!("Archon not available");
+                return;
+            };
+            let communicator = match Communicator::new(archon).await {
+                Ok(communicator) => communicator,
+                Err(e) => {
+                    error!("Failed to initialize communicator: {e}");
+                    return;
+                }
+            };
+
+            set_game_progress_state_simple(
+                true,
+                0.0,
+                format!("Copying {}", source.name),
+                Some(id),
+            );
+
+            let result = communicator
+                .clone_instance(id, move |copied, total| {
+                    let progress = copied as f32 / total.max(1) as f32 * 100.0;
+                    set_game_progress_state_simple(
+                        true,
+                        progress,
+                        format!("Copying files {copied}/{total}"),
+                        Some(id),
+                    );
+                })
+                .await;
+
+            match result {
+                Ok(Some(new_id)) => {
+                    info!("Duplicated instance {id} into {new_id}");
+                    // The backend gives the copy its own icon path
+                    let cloned = match communicator.get_instances().await {
+                        Ok(instances) => instances.into_iter().find(|i| i.id == new_id),
+                        Err(e) => {
+                            warn!("Failed to reload duplicated instance {new_id}: {e}");
+                            None
+                        }
+                    };
+                    INSTANCES.write().insert(
+                        new_id,
+                        cloned.unwrap_or_else(|| Instance {
+                            id: new_id,
+                            name: source.copy_name(),
+                            last_played: None,
+                            icon_path: None,
+                            ..source
+                        }),
+                    );
+                    *NEXT_ID.write() = new_id + 1;
+                    set_game_progress_state(
+                        true,
+                        100.0,
+                        "Instance duplicated".to_string(),
+                        ProgressStatus::Success,
+                        Some(new_id),
+                    );
+                }
+                Ok(None) => {
+                    error!("Failed to clone instance {id}: no ID returned");
+                    set_game_progress_state(
+                        true,
+                        100.0,
+                        "Failed to duplicate instance".to_string(),
+                        ProgressStatus::Failed,
+                        Some(id),
+                    );
+                }
+                Err(e) => {
+                    error!("Failed to clone instance {id}: {e}");
+                    set_game_progress_state(
+                        true,
+                        100.0,
+                        "Failed to duplicate instance".to_string(),
+                        ProgressStatus::Failed,
+                        Some(id),
+                    );
+                }
+            }
+
+            // Hide the status after 3 seconds
+            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+            set_game_progress_state_simple(false, 0.0, String::new(), None);
+        });
+    }
+
     #[must_use]
     pub fn rename_instance(id: u32, new_name: &str) -> bool {
         let mut instances = INSTANCES.write();
@@ -183,6 +300,92 @@ impl InstanceManager {
         renamed
     }
 
+    /// Remember a server joined from an instance.
+    pub fn record_server(id: u32, address: &str) {
+        if let Some(instance) = INSTANCES.write().get_mut(&id) {
+            instance.recent_servers.retain(|server| server != address);
+            instance.recent_servers.insert(0, address.to_string());
+            instance.recent_servers.truncate(3);
+        }
+
+        let address = address.to_string();
+        spawn(async move {
+            let Some(archon) = crate::get_archon() else {
+                error!("Archon not available");
+                return;
+            };
+            match Communicator::new(archon).await {
+                Ok(communicator) => {
+                    if let Err(e) = communicator.record_server(id, &address).await {
+                        error!("Failed to record server for instance {id}: {e}");
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to initialize communicator: {e}");
+                }
+            }
+        });
+    }
+
+    /// Set the game window size and fullscreen mode of an instance.
+    pub fn set_window_settings(id: u32, size: Option<(u32, u32)>, fullscreen: bool) {
+        if let Some(instance) = INSTANCES.write().get_mut(&id) {
+            instance.window_width = size.map(|(width, _)| width);
+            instance.window_height = size.map(|(_, height)| height);
+            instance.fullscreen = fullscreen;
+        }
+
+        spawn(async move {
+            let Some(archon) = crate::get_archon() else {
+                error!("Archon not available");
+                return;
+            };
+            match Communicator::new(archon).await {
+                Ok(communicator) => {
+                    if let Err(e) = communicator.set_window_settings(id, size, fullscreen).await {
+                        error!("Failed to save window settings for instance {id}: {e}");
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to initialize communicator: {e}");
+                }
+            }
+        });
+    }
+
+    /// Set the JVM garbage collector preset of an instance.
+    pub fn set_jvm_preset(id: u32, preset: JvmPreset) {
+        if let Some(instance) = INSTANCES.write().get_mut(&id) {
+            instance.jvm_preset = preset.clone();
+        }
+
+        spawn(async move {
+            let Some(archon) = crate::get_archon() else {
+                error!("Archon not available");
+                return;
+            };
+            match Communicator::new(archon).await {
+                Ok(communicator) => {
+                    if let Err(e) = communicator.set_jvm_preset(id, preset).await {
+                        error!("Failed to save JVM preset for instance {id}: {e}");
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to initialize communicator: {e}");
+                }
+            }
+        });
+    }
+
+    /// Mark an instance as launched just now.
+    ///
+    /// The backend persists the timestamp itself once the launch succeeds.
+    pub fn mark_played(id: u32) {
+        if let Some(instance) = INSTANCES.write().get_mut(&id) {
+            instance.last_played = Some(VisitTracker::current_timestamp());
+        }
+    }
+
     pub fn toggle_debug_mode() {
         let current = *DEBUG_MODE.read();
         *DEBUG_MODE.write() = !current;
@@ -195,7 +398,7 @@ impl InstanceManager {
 
     #[must_use]
     pub fn can_create_instance() -> bool {
-        INSTANCES.read().len() < 14
+        INSTANCES.read().len() < MAX_INSTANCES
     }
 
     #[must_use]
@@ -205,6 +408,14 @@ impl InstanceManager {
         sorted.sort_by_key(|i| i.id);
         sorted
     }
+
+    /// Get instances with the most recently played first; never played ones follow by id.
+    #[must_use]
+    pub fn get_instances_by_recency() -> Vec<Instance> {
+        let mut sorted = Self::get_instances_sorted();
+        sorted.sort_by_key(|i| std::cmp::Reverse(i.last_played));
+        sorted
+    }
 }
 
 /// Open instance folder in system file explorer.
@@ -217,7 +428,7 @@ pub fn open_instance_folder(instance_id: u32) {
         match Communicator::new(archon).await {
             Ok(communicator) => {
                 if let Err(e) = communicator.open_instance_folder(instance_id).await {
-                    error!("Failed to open instance {instance_id} folder: {e}");
+                    error!("Failed to open instance {instance_id} folder: {e:#}");
                 } else {
                     info!("Opened instance {instance_id} folder");
                 }
@@ -228,3 +439,400 @@ pub fn open_instance_folder(instance_id: u32) {
         }
     });
 }
+
+/// Extract the server address from a "Connecting to host, port" game log line.
+///
+/// The default port is left out so addresses match what players type.
+#[must_use]
+pub fn parse_server_connection(line: &str) -> Option<String> {
+    let (_, target) = line.split_once("Connecting to ")?;
+    let (host, port) = target.trim().rsplit_once(", ")?;
+    let port: u16 = port.trim().parse().ok()?;
+    if host.is_empty() {
+        return None;
+    }
+
+    // IPv6 literals need brackets before a port can be appended
+    let host = if host.contains(':') && !host.starts_with('[') {
+        format!("[{host}]")
+    } else {
+        host.to_string()
+    };
+
+    Some(if port == 25565 {
+        host
+    } else {
+        format!("{host}:{port}")
+    })
+}
+
+/// Ask whether to include worlds and for a destination, then export an instance as a zip archive.
+pub fn export_instance(instance_id: u32) {
+    let file_name = INSTANCES
+        .read()
+        .get(&instance_id)
+        .map_or_else(|| format!("instance_{instance_id}"), |i| i.name.clone());
+
+    spawn(async move {
+        let include_worlds = matches!(
+            rfd::AsyncMessageDialog::new()
+                .set_title("Export instance")
+                .set_description("Include worlds in the archive? They can make it much larger.")
+                .set_buttons(rfd::MessageButtons::YesNo)
+                .show()
+                .await,
+            rfd::MessageDialogResult::Yes
+        );
+
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("Instance archive", &["zip"])
+            .set_file_name(format!("{file_name}.zip"))
+            .save_file()
+            .await
+        else {
+            return;
+        };
+
+        let Some(archon) = crate::get_archon() else {
+            error!("Archon not available");
+            return;
+        };
+        set_game_progress_state_simple(
+            true,
+            50.0,
+            "Exporting instance...".to_string(),
+            Some(instance_id),
+        );
+        let result = match Communicator::new(archon).await {
+            Ok(communicator) => {
+                communicator
+                    .export_instance(instance_id, file.path().to_path_buf(), include_worlds)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(()) => {
+                info!("Exported instance {instance_id} to {:?}", file.path());
+                set_game_progress_state(
+                    true,
+                    100.0,
+                    "Instance exported".to_string(),
+                    ProgressStatus::Success,
+                    Some(instance_id),
+                );
+            }
+            Err(e) => {
+                error!("Failed to export instance {instance_id}: {e}");
+                set_game_progress_state(
+                    true,
+                    100.0,
+                    "Failed to export instance".to_string(),
+                    ProgressStatus::Failed,
+                    Some(instance_id),
+                );
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        set_game_progress_state_simple(false, 0.0, String::new(), None);
+    });
+}
+
+/// Re-download missing or corrupted game files of an instance.
+pub fn repair_instance(instance_id: u32) {
+    if is_instance_running(instance_id) {
+        warn!("Cannot repair instance {instance_id} while it is running");
+        return;
+    }
+
+    spawn(async move {
+        let Some(archon) = crate::get_archon() else {
+            error!("Archon not available");
+            return;
+        };
+        // Mark the instance busy so a second repair is not started meanwhile
+        set_instance_running(instance_id, true);
+        set_game_progress_state_simple(
+            true,
+            50.0,
+            "Verifying game files...".to_string(),
+            Some(instance_id),
+        );
+        let result = match Communicator::new(archon).await {
+            Ok(communicator) => communicator.repair_instance(instance_id).await,
+            Err(e) => Err(e),
+        };
+        set_instance_running(instance_id, false);
+
+        match result {
+            Ok(repaired) => {
+                for file in &repaired {
+                    info!("Repaired {file} for instance {instance_id}");
+                }
+                let status = match repaired.len() {
+                    0 => "No corrupted files found".to_string(),
+                    1 => "Repaired 1 file".to_string(),
+                    count => format!("Repaired {count} files"),
+                };
+                set_game_progress_state(
+                    true,
+                    100.0,
+                    status,
+                    ProgressStatus::Success,
+                    Some(instance_id),
+                );
+            }
+            Err(e) => {
+                error!("Failed to repair instance {instance_id}: {e}");
+                set_game_progress_state(
+                    true,
+                    100.0,
+                    "Failed to repair instance".to_string(),
+                    ProgressStatus::Failed,
+                    Some(instance_id),
+                );
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        set_game_progress_state_simple(false, 0.0, String::new(), None);
+    });
+}
+
+/// How long the game gets to save and exit before it is killed.
+const STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Stop the game of a running instance, killing it if it doesn't exit in time.
+pub fn stop_instance(instance_id: u32) {
+    // Forgetting the PID first tells the log handler the exit was requested
+    let Some(pid) = take_game_pid(instance_id) else {
+        info!("Instance {instance_id} has no running game to stop");
+        return;
+    };
+
+    spawn(async move {
+        set_game_progress_state_simple(
+            true,
+            50.0,
+            "Stopping Minecraft...".to_string(),
+            Some(instance_id),
+        );
+        match stop_process(pid, STOP_TIMEOUT).await {
+            Ok(true) => info!("Stopped instance {instance_id}"),
+            Ok(false) => info!("Game of instance {instance_id} had already exited"),
+            Err(e) => {
+                error!("Failed to stop instance {instance_id}: {e}");
+                set_game_pid(instance_id, pid);
+                set_game_progress_state(
+                    true,
+                    100.0,
+                    "Failed to stop Minecraft".to_string(),
+                    ProgressStatus::Failed,
+                    Some(instance_id),
+                );
+                tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+                set_game_progress_state_simple(false, 0.0, String::new(), None);
+                return;
+            }
+        }
+
+        set_instance_running(instance_id, false);
+        set_game_progress_state_simple(false, 0.0, String::new(), None);
+    });
+}
+
+/// Cancel the install of an instance that is being launched.
+///
+/// The launch then fails with `InstallCancelled`, which resets the instance.
+pub async fn cancel_launch(instance_id: u32) -> anyhow::Result<()> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    if Communicator::new(archon)
+        .await?
+        .cancel_install(instance_id)
+        .await
+    {
+        info!("Cancelling launch of instance {instance_id}");
+    } else {
+        info!("Instance {instance_id} has no install to cancel");
+    }
+    Ok(())
+}
+
+/// Create an instance from an exported zip archive and add it to the instance list.
+pub async fn import_instance(path: &std::path::Path) -> anyhow::Result<Instance> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    let id = communicator
+        .import_instance(path)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Instance limit reached"))?;
+
+    let instance = communicator
+        .get_instances()
+        .await?
+        .into_iter()
+        .find(|i| i.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Imported instance {id} not found"))?;
+    INSTANCES.write().insert(id, instance.clone());
+    *NEXT_ID.write() = id + 1;
+
+    info!("Imported instance {id} from {path:?}");
+    Ok(instance)
+}
+
+/// Create instances from the versions of a vanilla `.minecraft` directory.
+pub async fn import_existing_installation(path: &std::path::Path) -> anyhow::Result<Vec<Instance>> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    let ids = communicator.import_existing_installation(path).await?;
+
+    let instances: Vec<Instance> = communicator
+        .get_instances()
+        .await?
+        .into_iter()
+        .filter(|i| ids.contains(&i.id))
+        .collect();
+    for instance in &instances {
+        INSTANCES.write().insert(instance.id, instance.clone());
+    }
+    if let Some(max_id) = ids.iter().max() {
+        *NEXT_ID.write() = max_id + 1;
+    }
+
+    info!("Imported {} instances from {path:?}", instances.len());
+    Ok(instances)
+}
+
+/// Save the debug console output of an instance to its logs folder.
+///
+/// Takes a snapshot of the lines captured so far, so it also works while the game is running.
+pub async fn export_debug_log(instance_id: u32) -> anyhow::Result<PathBuf> {
+    let contents = format_debug_logs(instance_id);
+    if contents.is_empty() {
+        return Err(anyhow::anyhow!("No logs to save yet"));
+    }
+
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    communicator.export_debug_log(instance_id, &contents).await
+}
+
+/// Build the command that would launch an instance without starting the game.
+pub async fn launch_command(instance_id: u32) -> anyhow::Result<String> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    Communicator::new(archon)
+        .await?
+        .launch_command(instance_id)
+        .await
+}
+
+/// List the singleplayer worlds of an instance.
+pub async fn list_worlds(instance_id: u32) -> anyhow::Result<Vec<String>> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    Communicator::new(archon)
+        .await?
+        .list_worlds(instance_id)
+        .await
+}
+
+/// Open the game logs folder of an instance.
+pub async fn open_logs_folder(instance_id: u32) -> anyhow::Result<()> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    Communicator::new(archon)
+        .await?
+        .open_logs_folder(instance_id)
+        .await
+}
+
+/// Path of the `logs/latest.log` the game of an instance writes to.
+pub async fn latest_log_path(instance_id: u32) -> anyhow::Result<PathBuf> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    Ok(Communicator::new(archon)
+        .await?
+        .latest_log_path(instance_id)
+        .await)
+}
+
+/// List the multiplayer servers saved in an instance.
+pub async fn list_servers(instance_id: u32) -> anyhow::Result<Vec<SavedServer>> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    Communicator::new(archon)
+        .await?
+        .list_servers(instance_id)
+        .await
+}
+
+/// Newest crash report of an instance written at or after `since`, in seconds since the Unix epoch.
+pub async fn latest_crash_report(instance_id: u32, since: u64) -> anyhow::Result<Option<PathBuf>> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    Communicator::new(archon)
+        .await?
+        .latest_crash_report(instance_id, since)
+        .await
+}
+
+/// Icons already encoded as data URLs, keyed by file path.
+static ICON_CACHE: LazyLock<Mutex<HashMap<PathBuf, String>>> = LazyLock::new(Default::default);
+
+/// Get the custom icon of an instance as a base64 data URL.
+#[must_use]
+pub fn icon_data_url(instance: &Instance) -> Option<String> {
+    let path = instance.icon_path.as_ref()?;
+    let mut cache = ICON_CACHE.lock().ok()?;
+    if let Some(data_url) = cache.get(path) {
+        return Some(data_url.clone());
+    }
+
+    // Icons are downscaled when set, so reading them here stays cheap
+    let bytes = std::fs::read(path)
+        .map_err(|e| warn!("Failed to read icon {path:?}: {e}"))
+        .ok()?;
+    let data_url = format!(
+        "data:image/png;base64,{}",
+        general_purpose::STANDARD.encode(bytes)
+    );
+    cache.insert(path.clone(), data_url.clone());
+    Some(data_url)
+}
+
+/// Ask for a PNG and use it as the icon of an instance.
+pub fn change_instance_icon(instance_id: u32) {
+    spawn(async move {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("PNG image", &["png"])
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+
+        let Some(archon) = crate::get_archon() else {
+            error!("Archon not available");
+            return;
+        };
+        let result = match Communicator::new(archon).await {
+            Ok(communicator) => {
+                communicator
+                    .set_instance_icon(instance_id, file.path())
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(icon_path) => {
+                // The icon file is overwritten in place, so drop the stale encoding
+                if let Ok(mut cache) = ICON_CACHE.lock() {
+                    cache.remove(&icon_path);
+                }
+                if let Some(instance) = INSTANCES.write().get_mut(&instance_id) {
+                    instance.icon_path = Some(icon_path);
+                }
+                info!("Changed icon of instance {instance_id}");
+            }
+            Err(e) => error!("Failed to change icon of instance {instance_id}: {e}"),
+        }
+    });
+}
@@ -1,7 +1,7 @@
 //! Launcher services.
 
 use crate::backend::communicator::communicator::Communicator;
-use crate::backend::services::VersionManifest;
+use crate::backend::services::{VersionFilter, VersionInfo, VersionManifest};
 use anyhow::Result;
 use log::{error, info};
 use std::sync::{Arc, OnceLock};
@@ -9,6 +9,27 @@ use tokio::sync::RwLock as AsyncRwLock;
 
 static VERSION_MANIFEST: OnceLock<Arc<AsyncRwLock<VersionManifest>>> = OnceLock::new();
 
+/// Versions that pass the filter and contain the search text, newest first.
+///
+/// The search ignores case, so `1.8.9` or `w14` find their versions among all types shown.
+#[must_use]
+pub fn filter_versions(
+    versions: &[VersionInfo],
+    filter: VersionFilter,
+    search: &str,
+) -> Vec<VersionInfo> {
+    let search = search.trim().to_lowercase();
+    let mut filtered: Vec<VersionInfo> = versions
+        .iter()
+        .filter(|version| filter.matches(version))
+        .filter(|version| search.is_empty() || version.id.to_lowercase().contains(&search))
+        .cloned()
+        .collect();
+    // Release times are ISO 8601 in UTC, so they sort chronologically as strings
+    filtered.sort_by(|a, b| b.release_time.cmp(&a.release_time));
+    filtered
+}
+
 pub async fn init_launcher() -> Result<(), Box<dyn std::error::Error>> {
     if VERSION_MANIFEST.get().is_some() {
         return Ok(());
@@ -47,10 +68,18 @@ pub async fn get_version_manifest() -> Result<VersionManifest> {
     Ok(manifest.clone())
 }
 
-pub async fn refresh_version_manifest() -> Result<()> {
+/// Seconds since the Unix epoch when the version manifest was last fetched or revalidated.
+pub async fn manifest_refreshed_at() -> Result<Option<u64>> {
     let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
     let communicator = Communicator::new(archon).await?;
-    match communicator.get_version_manifest().await {
+    Ok(communicator.manifest_refreshed_at().await)
+}
+
+/// Refresh the version manifest, ignoring the cache TTL when `force` is set.
+pub async fn refresh_version_manifest(force: bool) -> Result<()> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    match communicator.refresh_version_manifest(force).await {
         Ok(new_manifest) => {
             // Update the global manifest using AsyncRwLock
             let manifest_lock = VERSION_MANIFEST
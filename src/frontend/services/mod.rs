@@ -1,8 +1,16 @@
 //! Frontend services for business logic and data management.
 
+pub mod avatars;
 pub mod chats;
+pub mod cloud;
 pub mod context;
 pub mod instances;
 pub mod launcher;
+pub mod modpacks;
+pub mod mods;
+pub mod news;
+pub mod resourcepacks;
+pub mod screenshots;
+pub mod settings;
 pub mod states;
 pub mod user;
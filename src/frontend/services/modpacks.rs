@@ -0,0 +1,56 @@
+//! Modpack import and Modrinth browsing service.
+
+use crate::backend::communicator::communicator::Communicator;
+use crate::backend::services::{ModpackImport, ModpackSearchResult};
+use crate::frontend::services::instances::{INSTANCES, NEXT_ID};
+use anyhow::Result;
+use log::info;
+use std::path::Path;
+
+/// Create an instance from a `.mrpack` file and add it to the instance list.
+pub async fn import_modpack(path: &Path) -> Result<ModpackImport> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    let import = communicator
+        .import_modpack(path)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Instance limit reached"))?;
+
+    add_imported_instance(&communicator, &import).await?;
+    Ok(import)
+}
+
+/// Search Modrinth for modpacks.
+pub async fn search_modpacks(query: &str) -> Result<Vec<ModpackSearchResult>> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    communicator.search_modpacks(query).await
+}
+
+/// Download a Modrinth modpack and add the created instance to the instance list.
+pub async fn install_modpack(project_id: &str) -> Result<ModpackImport> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    let import = communicator
+        .install_modrinth_modpack(project_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Instance limit reached"))?;
+
+    add_imported_instance(&communicator, &import).await?;
+    Ok(import)
+}
+
+async fn add_imported_instance(communicator: &Communicator, import: &ModpackImport) -> Result<()> {
+    // Pick up the new instance with the version, loader and name from the pack
+    let instances = communicator.get_instances().await?;
+    if let Some(instance) = instances.into_iter().find(|i| i.id == import.instance_id) {
+        INSTANCES.write().insert(instance.id, instance);
+        *NEXT_ID.write() = import.instance_id + 1;
+    }
+
+    info!(
+        "Imported modpack {} as instance {}",
+        import.name, import.instance_id
+    );
+    Ok(())
+}
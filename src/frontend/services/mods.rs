@@ -0,0 +1,28 @@
+//! Installed mods service.
+
+use crate::backend::communicator::communicator::Communicator;
+use crate::backend::services::ModInfo;
+use anyhow::Result;
+
+#[derive(Clone, Copy)]
+pub struct ModManager;
+
+impl ModManager {
+    /// List the mods of an instance by name, including disabled ones.
+    pub async fn list(instance_id: u32) -> Result<Vec<ModInfo>> {
+        Self::communicator().await?.list_mods(instance_id).await
+    }
+
+    /// Enable or disable a mod, returning its new file name.
+    pub async fn set_enabled(instance_id: u32, info: &ModInfo, enabled: bool) -> Result<String> {
+        Self::communicator()
+            .await?
+            .set_mod_enabled(instance_id, &info.file_name, enabled)
+            .await
+    }
+
+    async fn communicator() -> Result<Communicator> {
+        let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+        Communicator::new(archon).await
+    }
+}
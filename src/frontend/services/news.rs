@@ -0,0 +1,14 @@
+//! News feed service.
+
+use crate::backend::communicator::communicator::Communicator;
+use crate::backend::services::NewsItem;
+use anyhow::Result;
+
+/// Number of articles shown on the home screen.
+pub const NEWS_LIMIT: usize = 5;
+
+/// Get the newest Minecraft news articles.
+pub async fn get_news() -> Result<Vec<NewsItem>> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    Communicator::new(archon).await?.get_news(NEWS_LIMIT).await
+}
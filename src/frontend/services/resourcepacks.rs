@@ -0,0 +1,211 @@
+//! Resource pack management for instances.
+
+use crate::backend::communicator::communicator::Communicator;
+use crate::backend::utils::archive::{is_zip_archive, zip_contains};
+use anyhow::Result;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use tokio::fs as async_fs;
+
+/// Key of the enabled resource packs line in `options.txt`.
+const RESOURCE_PACKS_KEY: &str = "resourcePacks:";
+
+/// Resource pack installed in an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourcePack {
+    pub name: String,
+    pub size: u64,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy)]
+pub struct ResourcePackManager;
+
+impl ResourcePackManager {
+    /// List resource packs of an instance.
+    pub async fn list_packs(instance_id: u32) -> Result<Vec<ResourcePack>> {
+        let instance_dir = Self::instance_directory(instance_id).await?;
+        let packs_dir = instance_dir.join("resourcepacks");
+        if !packs_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let enabled = Self::read_enabled_packs(&instance_dir).await?;
+        let mut packs = Vec::new();
+        let mut entries = async_fs::read_dir(&packs_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if !metadata.is_file() {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            packs.push(ResourcePack {
+                enabled: enabled.contains(&Self::option_entry(&name)),
+                name,
+                size: metadata.len(),
+            });
+        }
+
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(packs)
+    }
+
+    /// Copy a resource pack zip into an instance.
+    pub async fn add_pack(instance_id: u32, path: &Path) -> Result<ResourcePack> {
+        Self::validate_pack(path)?;
+
+        let name = path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid resource pack path: {}", path.display()))?
+            .to_string_lossy()
+            .to_string();
+
+        let packs_dir = Self::instance_directory(instance_id)
+            .await?
+            .join("resourcepacks");
+        async_fs::create_dir_all(&packs_dir).await?;
+        let size = async_fs::copy(path, packs_dir.join(&name)).await?;
+
+        info!("Added resource pack {name} to instance {instance_id}");
+        Ok(ResourcePack {
+            name,
+            size,
+            enabled: false,
+        })
+    }
+
+    /// Remove a resource pack from an instance.
+    pub async fn remove_pack(instance_id: u32, name: &str) -> Result<()> {
+        let instance_dir = Self::instance_directory(instance_id).await?;
+        let pack_path = Self::pack_path(&instance_dir, name)?;
+        async_fs::remove_file(&pack_path).await?;
+
+        // Make sure the game does not try to load a missing pack
+        let mut enabled = Self::read_enabled_packs(&instance_dir).await?;
+        let entry = Self::option_entry(name);
+        if enabled.contains(&entry) {
+            enabled.retain(|pack| *pack != entry);
+            Self::write_enabled_packs(&instance_dir, &enabled).await?;
+        }
+
+        info!("Removed resource pack {name} from instance {instance_id}");
+        Ok(())
+    }
+
+    /// Toggle a resource pack and return whether it is now enabled.
+    pub async fn toggle_pack(instance_id: u32, name: &str) -> Result<bool> {
+        let instance_dir = Self::instance_directory(instance_id).await?;
+        let pack_path = Self::pack_path(&instance_dir, name)?;
+        if !pack_path.exists() {
+            return Err(anyhow::anyhow!("Resource pack {name} not found"));
+        }
+
+        let mut enabled = Self::read_enabled_packs(&instance_dir).await?;
+        let entry = Self::option_entry(name);
+        let now_enabled = if enabled.contains(&entry) {
+            enabled.retain(|pack| *pack != entry);
+            false
+        } else {
+            Self::validate_pack(&pack_path)?;
+            enabled.push(entry);
+            true
+        };
+
+        Self::write_enabled_packs(&instance_dir, &enabled).await?;
+        info!("Resource pack {name} enabled: {now_enabled}");
+        Ok(now_enabled)
+    }
+
+    /// Check that a file is a ZIP archive with a `pack.mcmeta` at its root.
+    fn validate_pack(path: &Path) -> Result<()> {
+        if !is_zip_archive(path)? {
+            return Err(anyhow::anyhow!(
+                "{} is not a valid resource pack archive",
+                path.display()
+            ));
+        }
+
+        match zip_contains(path, "pack.mcmeta") {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(anyhow::anyhow!(
+                "{} does not contain pack.mcmeta",
+                path.display()
+            )),
+            Err(e) => Err(anyhow::anyhow!(
+                "Resource pack {} is corrupt: {e}",
+                path.display()
+            )),
+        }
+    }
+
+    /// Get the path of a pack inside the instance, rejecting path separators.
+    fn pack_path(instance_dir: &Path, name: &str) -> Result<PathBuf> {
+        if name.contains(['/', '\\']) || name == ".." {
+            return Err(anyhow::anyhow!("Invalid resource pack name: {name}"));
+        }
+        Ok(instance_dir.join("resourcepacks").join(name))
+    }
+
+    /// Name of a pack as stored in `options.txt`.
+    fn option_entry(name: &str) -> String {
+        format!("file/{name}")
+    }
+
+    /// Read the enabled packs from the instance's `options.txt`.
+    async fn read_enabled_packs(instance_dir: &Path) -> Result<Vec<String>> {
+        let options_path = instance_dir.join("options.txt");
+        if !options_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let options = async_fs::read_to_string(options_path).await?;
+        let Some(value) = options
+            .lines()
+            .find_map(|line| line.strip_prefix(RESOURCE_PACKS_KEY))
+        else {
+            return Ok(Vec::new());
+        };
+
+        Ok(serde_json::from_str(value).unwrap_or_else(|e| {
+            warn!("Failed to parse resourcePacks option: {e}");
+            Vec::new()
+        }))
+    }
+
+    /// Write the enabled packs to the instance's `options.txt`, keeping other options.
+    async fn write_enabled_packs(instance_dir: &Path, packs: &[String]) -> Result<()> {
+        let options_path = instance_dir.join("options.txt");
+        let options = if options_path.exists() {
+            async_fs::read_to_string(&options_path).await?
+        } else {
+            String::new()
+        };
+
+        let packs_line = format!("{RESOURCE_PACKS_KEY}{}", serde_json::to_string(packs)?);
+        let mut replaced = false;
+        let mut lines: Vec<String> = options
+            .lines()
+            .map(|line| {
+                if line.starts_with(RESOURCE_PACKS_KEY) {
+                    replaced = true;
+                    packs_line.clone()
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        if !replaced {
+            lines.push(packs_line);
+        }
+
+        async_fs::write(options_path, lines.join("\n") + "\n").await?;
+        Ok(())
+    }
+
+    async fn instance_directory(instance_id: u32) -> Result<PathBuf> {
+        let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+        let communicator = Communicator::new(archon).await?;
+        Ok(communicator.get_instance_directory(instance_id).await)
+    }
+}
@@ -0,0 +1,57 @@
+//! Screenshot gallery service.
+
+use crate::backend::communicator::communicator::Communicator;
+use crate::backend::services::Screenshot;
+use crate::backend::utils::paths::open_path;
+use anyhow::Result;
+use base64::{Engine as _, engine::general_purpose};
+use log::info;
+use std::path::Path;
+
+#[derive(Clone, Copy)]
+pub struct ScreenshotManager;
+
+impl ScreenshotManager {
+    /// List screenshots of an instance, newest first.
+    pub async fn list(instance_id: u32) -> Result<Vec<Screenshot>> {
+        Self::communicator()
+            .await?
+            .list_screenshots(instance_id)
+            .await
+    }
+
+    /// Load a screenshot as a base64 data URL.
+    pub async fn load_data_url(path: &Path) -> Result<String> {
+        let bytes = tokio::fs::read(path).await?;
+        Ok(format!(
+            "data:image/png;base64,{}",
+            general_purpose::STANDARD.encode(bytes)
+        ))
+    }
+
+    /// Open a screenshot in the system image viewer.
+    pub fn open(path: &Path) -> Result<()> {
+        open_path(path)?;
+        info!("Opened screenshot {path:?}");
+        Ok(())
+    }
+
+    /// Delete a screenshot of an instance.
+    pub async fn delete(instance_id: u32, screenshot: &Screenshot) -> Result<()> {
+        let file_name = screenshot
+            .path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid screenshot path"))?
+            .to_string_lossy()
+            .to_string();
+        Self::communicator()
+            .await?
+            .delete_screenshot(instance_id, &file_name)
+            .await
+    }
+
+    async fn communicator() -> Result<Communicator> {
+        let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+        Communicator::new(archon).await
+    }
+}
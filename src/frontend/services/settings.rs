@@ -0,0 +1,30 @@
+//! Launcher settings service.
+
+use crate::backend::communicator::communicator::Communicator;
+use crate::backend::services::LauncherSettings;
+use anyhow::Result;
+use dioxus::prelude::*;
+use log::info;
+
+pub static SETTINGS: GlobalSignal<LauncherSettings> = Signal::global(LauncherSettings::default);
+
+/// Load launcher settings from disk.
+pub async fn load_settings() -> Result<()> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    *SETTINGS.write() = communicator.load_settings().await;
+    Ok(())
+}
+
+/// Save launcher settings, keeping the current ones if they are invalid.
+pub async fn save_settings(mut settings: LauncherSettings) -> Result<()> {
+    let archon = crate::get_archon().ok_or_else(|| anyhow::anyhow!("Archon not available"))?;
+    let communicator = Communicator::new(archon).await?;
+    // The updater records skipped versions on disk while the window is open
+    settings.skipped_update = communicator.load_settings().await.skipped_update;
+    communicator.save_settings(&settings).await?;
+
+    info!("Launcher settings updated");
+    *SETTINGS.write() = settings;
+    Ok(())
+}
@@ -161,14 +161,17 @@ pub fn set_game_progress_state_simple(
 }
 
 // Running instances tracking
+use crate::backend::python::python::GameLogLine;
 use chrono::{DateTime, Local};
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 // Debug console logs
 #[derive(Clone, PartialEq, Debug)]
 pub struct DebugLogEntry {
     pub timestamp: String,
     pub level: String,
+    /// Thread or logger name of game log lines.
+    pub thread: Option<String>,
     pub message: String,
     pub instance_id: Option<u32>,
 }
@@ -177,11 +180,32 @@ static DEBUG_LOGS: std::sync::LazyLock<Arc<Mutex<VecDeque<DebugLogEntry>>>> =
     std::sync::LazyLock::new(|| Arc::new(Mutex::new(VecDeque::new())));
 
 pub fn add_debug_log(level: String, message: String, instance_id: Option<u32>) {
+    push_debug_log(level, None, message, instance_id);
+}
+
+/// Add a line of game output, split into its level, thread and message.
+pub fn add_game_log(line: &str, instance_id: Option<u32>) {
+    let line = GameLogLine::parse(line);
+    push_debug_log(
+        line.level.to_string(),
+        line.thread,
+        line.message,
+        instance_id,
+    );
+}
+
+fn push_debug_log(
+    level: String,
+    thread: Option<String>,
+    message: String,
+    instance_id: Option<u32>,
+) {
     if let Ok(mut logs) = DEBUG_LOGS.lock() {
         let now: DateTime<Local> = Local::now();
         logs.push_back(DebugLogEntry {
             timestamp: now.format("%H:%M:%S").to_string(),
             level,
+            thread,
             message,
             instance_id,
         });
@@ -201,6 +225,21 @@ pub fn get_debug_logs() -> VecDeque<DebugLogEntry> {
     }
 }
 
+/// Debug console output of an instance as text, including lines not tied to an instance.
+pub fn format_debug_logs(instance_id: u32) -> String {
+    get_debug_logs()
+        .iter()
+        .filter(|log| log.instance_id.is_none_or(|id| id == instance_id))
+        .map(|log| match &log.thread {
+            Some(thread) => format!(
+                "{} [{}] [{thread}] {}\n",
+                log.timestamp, log.level, log.message
+            ),
+            None => format!("{} [{}] {}\n", log.timestamp, log.level, log.message),
+        })
+        .collect()
+}
+
 pub fn clear_debug_logs() {
     if let Ok(mut logs) = DEBUG_LOGS.lock() {
         logs.clear();
@@ -232,3 +271,45 @@ pub fn set_instance_running(instance_id: u32, running: bool) {
         }
     }
 }
+
+#[must_use]
+pub fn is_instance_running(instance_id: u32) -> bool {
+    RUNNING_INSTANCES
+        .lock()
+        .is_ok_and(|instances| instances.contains(&instance_id))
+}
+
+/// PIDs of the game processes of running instances, keyed by instance id.
+static GAME_PIDS: std::sync::LazyLock<Arc<Mutex<HashMap<u32, u32>>>> =
+    std::sync::LazyLock::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+pub fn set_game_pid(instance_id: u32, pid: u32) {
+    if let Ok(mut pids) = GAME_PIDS.lock() {
+        pids.insert(instance_id, pid);
+    }
+}
+
+#[must_use]
+pub fn game_pid(instance_id: u32) -> Option<u32> {
+    GAME_PIDS
+        .lock()
+        .ok()
+        .and_then(|pids| pids.get(&instance_id).copied())
+}
+
+/// Forget the game process of an instance, returning its PID if one was known.
+pub fn take_game_pid(instance_id: u32) -> Option<u32> {
+    GAME_PIDS
+        .lock()
+        .ok()
+        .and_then(|mut pids| pids.remove(&instance_id))
+}
+
+/// Crash report of the last game that crashed, shown until dismissed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CrashReport {
+    pub instance_id: u32,
+    pub path: std::path::PathBuf,
+}
+
+pub static CRASH_REPORT: GlobalSignal<Option<CrashReport>> = Signal::global(|| None);
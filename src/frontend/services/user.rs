@@ -2,27 +2,93 @@
 
 use crate::backend::communicator::communicator::Communicator;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Names that can't be used for offline accounts, compared case-insensitively.
+const RESERVED_USERNAMES: [&str; 4] = ["Player", "Server", "Console", "Rcon"];
+
+/// Reason a username was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsernameError {
+    /// Shorter than 3 characters.
+    TooShort,
+    /// Longer than 16 characters.
+    TooLong,
+    /// Contains characters other than letters, numbers and underscores.
+    InvalidCharacters,
+    /// Starts or ends with whitespace.
+    SurroundingWhitespace,
+    /// Reserved by the launcher or the game.
+    Reserved,
+}
+
+impl fmt::Display for UsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "Username must be at least 3 characters long"),
+            Self::TooLong => write!(f, "Username can't be longer than 16 characters"),
+            Self::InvalidCharacters => write!(
+                f,
+                "Username can only contain letters, numbers, and underscores"
+            ),
+            Self::SurroundingWhitespace => {
+                write!(f, "Username can't start or end with a space")
+            }
+            Self::Reserved => write!(f, "This username is reserved"),
+        }
+    }
+}
+
+impl std::error::Error for UsernameError {}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserConfig {
     pub username: String,
+    /// Password of the cloud sync server, kept out of the launcher settings.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub cloud_password: String,
 }
 
 impl UserConfig {
     /// Creates a new user config with the given username.
     #[must_use]
     pub fn new(username: String) -> Self {
-        Self { username }
+        Self {
+            username,
+            cloud_password: String::new(),
+        }
     }
 
     /// Validates if a username meets the requirements.
     #[must_use]
     pub fn is_valid_username(username: &str) -> bool {
-        let trimmed = username.trim();
-        (3..=16).contains(&trimmed.len())
-            && trimmed
-                .chars()
-                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        Self::validate_username(username).is_ok()
+    }
+
+    /// Checks a username, returning why it was rejected.
+    pub fn validate_username(username: &str) -> Result<(), UsernameError> {
+        if username.trim() != username {
+            return Err(UsernameError::SurroundingWhitespace);
+        }
+        if username.len() < 3 {
+            return Err(UsernameError::TooShort);
+        }
+        if username.len() > 16 {
+            return Err(UsernameError::TooLong);
+        }
+        if !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(UsernameError::InvalidCharacters);
+        }
+        if RESERVED_USERNAMES
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(username))
+        {
+            return Err(UsernameError::Reserved);
+        }
+        Ok(())
     }
 
     /// Saves the user config to a file.
@@ -72,6 +138,83 @@ impl Default for UserConfig {
     fn default() -> Self {
         Self {
             username: "Player".to_string(),
+            cloud_password: String::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_usernames() {
+        assert_eq!(UserConfig::validate_username("Steve"), Ok(()));
+        assert_eq!(UserConfig::validate_username("abc"), Ok(()));
+        assert_eq!(UserConfig::validate_username("Frog_dream_2024_"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_short_usernames() {
+        assert_eq!(
+            UserConfig::validate_username("ab"),
+            Err(UsernameError::TooShort)
+        );
+        assert_eq!(
+            UserConfig::validate_username(""),
+            Err(UsernameError::TooShort)
+        );
+    }
+
+    #[test]
+    fn rejects_long_usernames() {
+        assert_eq!(
+            UserConfig::validate_username("abcdefghijklmnopq"),
+            Err(UsernameError::TooLong)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_characters() {
+        assert_eq!(
+            UserConfig::validate_username("Steve-1"),
+            Err(UsernameError::InvalidCharacters)
+        );
+        assert_eq!(
+            UserConfig::validate_username("Ste ve"),
+            Err(UsernameError::InvalidCharacters)
+        );
+        assert_eq!(
+            UserConfig::validate_username("Stevé"),
+            Err(UsernameError::InvalidCharacters)
+        );
+    }
+
+    #[test]
+    fn rejects_surrounding_whitespace() {
+        assert_eq!(
+            UserConfig::validate_username(" Steve"),
+            Err(UsernameError::SurroundingWhitespace)
+        );
+        assert_eq!(
+            UserConfig::validate_username("Steve\t"),
+            Err(UsernameError::SurroundingWhitespace)
+        );
+    }
+
+    #[test]
+    fn rejects_reserved_usernames_in_any_case() {
+        assert_eq!(
+            UserConfig::validate_username("Player"),
+            Err(UsernameError::Reserved)
+        );
+        assert_eq!(
+            UserConfig::validate_username("CONSOLE"),
+            Err(UsernameError::Reserved)
+        );
+        assert_eq!(
+            UserConfig::validate_username("rcon"),
+            Err(UsernameError::Reserved)
+        );
+    }
+}
@@ -6,11 +6,15 @@ pub mod frontend;
 use std::sync::OnceLock;
 
 use dioxus::{LaunchBuilder, prelude::*};
-use dioxus_desktop::{Config, LogicalSize, WindowBuilder};
+use dioxus_desktop::tao::event::{Event, WindowEvent};
+use dioxus_desktop::{Config, LogicalPosition, LogicalSize, WindowBuilder, use_wry_event_handler};
 use dioxus_router::Router;
 
 use crate::backend::Archon;
+use crate::backend::cli::{CliEvent, LaunchArgs, USAGE, run_launch};
 use crate::backend::utils::application::Route;
+use crate::backend::utils::css::ResourceLoader;
+use crate::backend::utils::window::{DEFAULT_HEIGHT, DEFAULT_WIDTH, WindowGeometry};
 use log::{error, info};
 use std::sync::Arc;
 use tokio::runtime::Runtime;
@@ -23,6 +27,20 @@ fn main() {
     // Logging
     env_logger::init();
 
+    // Catch asset path typos before they turn into invisible icons
+    if cfg!(debug_assertions)
+        && let Err(broken) = ResourceLoader::validate()
+    {
+        error!("Broken embedded assets: {}", broken.join(", "));
+    }
+
+    // Command-line arguments
+    let launch_args = LaunchArgs::parse(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    });
+
     // Tokio runtime
     let runtime = RUNTIME.get_or_init(|| {
         tokio::runtime::Builder::new_multi_thread()
@@ -53,6 +71,28 @@ fn main() {
         std::process::exit(1);
     }
 
+    // Launch a version without the window
+    if let Some(launch_args) = launch_args {
+        let json = launch_args.json;
+        let exit_code = runtime.block_on(async {
+            let exit_code = run_launch(&archon, launch_args).await.unwrap_or_else(|e| {
+                error!("Headless launch failed: {e}");
+                if json {
+                    CliEvent::Error {
+                        message: e.to_string(),
+                    }
+                    .print();
+                }
+                1
+            });
+            if let Err(e) = archon.shutdown().await {
+                error!("Error during shutdown: {e}");
+            }
+            exit_code
+        });
+        std::process::exit(exit_code);
+    }
+
     // Run the updater in a separate thread
     runtime.spawn(async {
         backend::services::updater::check_for_updates().await;
@@ -63,7 +103,7 @@ fn main() {
         let _ = frontend::services::launcher::init_launcher().await;
 
         // Refresh version manifest after initialization
-        if let Err(e) = frontend::services::launcher::refresh_version_manifest().await {
+        if let Err(e) = frontend::services::launcher::refresh_version_manifest(false).await {
             error!("Failed to refresh version manifest: {e}");
         }
     });
@@ -83,20 +123,19 @@ fn main() {
     });
 
     // Dioxus
-    let size = LogicalSize::new(1056.0, 709.0);
-
-    let config = Config::default()
-        .with_window(
-            WindowBuilder::new()
-                .with_title("Dream Launcher")
-                .with_inner_size(size)
-                .with_min_inner_size(size)
-                .with_max_inner_size(size)
-                .with_resizable(false)
-                .with_decorations(false)
-                .with_transparent(true),
-        )
-        .with_menu(None);
+    let geometry = WindowGeometry::load();
+    let mut window = WindowBuilder::new()
+        .with_title("Dream Launcher")
+        .with_inner_size(LogicalSize::new(geometry.width, geometry.height))
+        .with_min_inner_size(LogicalSize::new(DEFAULT_WIDTH, DEFAULT_HEIGHT))
+        .with_resizable(true)
+        .with_decorations(false)
+        .with_transparent(true);
+    if let Some((x, y)) = geometry.position {
+        window = window.with_position(LogicalPosition::new(x, y));
+    }
+
+    let config = Config::default().with_window(window).with_menu(None);
 
     // Configure WebView2 user data folder on Windows
     #[cfg(target_os = "windows")]
@@ -143,6 +182,17 @@ fn AppRoot() -> Element {
         current_user,
     };
 
+    // Remember the window geometry when the system closes the window, e.g. with Alt+F4
+    use_wry_event_handler(|event, _| {
+        if let Event::WindowEvent {
+            event: WindowEvent::CloseRequested,
+            ..
+        } = event
+        {
+            frontend::components::common::titlebar::save_window_geometry();
+        }
+    });
+
     // Load saved user data on component mount
     use_effect(move || {
         let auth_state = auth_state;